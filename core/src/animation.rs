@@ -0,0 +1,281 @@
+//! Running transitions and `@keyframes` animations.
+//!
+//! Every node owns an [`Animator`] that sits between the cascade and the computed style. When
+//! a restyle changes a property with a `transition`, the animator starts moving the property
+//! from the value on screen to the new one, and it plays the animations the element's rules
+//! name. The style it returns is the one elements are laid out and painted with.
+//!
+//! Time comes from the [`Clock`] of the window, which hands it down the element tree once per
+//! frame with [`Container::animate`](crate::prelude::Container::animate). Nodes with running
+//! animations restyle themselves when the time changes, so a [`ManualClock`] steps through
+//! frames deterministically. With `prefers-reduced-motion` transitions finish at once and
+//! animations don't play.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use style::{Animation, Appearance, Dimensions, Keyframes, Motion, Style, Transition, ANIMATABLE};
+
+/// Time between frames while something is animating.
+pub const FRAME: Duration = Duration::from_millis(16);
+
+/// The time animations are played against.
+pub trait Clock: fmt::Debug + Send {
+    /// Time since an arbitrary but fixed point.
+    fn now(&self) -> Duration;
+}
+
+/// The real time since the clock was created.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock(Instant);
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock(Instant::now())
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, for stepping through frames in tests. Clones share
+/// the same time.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.0.store(now.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.0.fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// A property moving from one value to another.
+#[derive(Debug, Clone)]
+struct Running {
+    property: &'static str,
+    from: Style,
+    to: Style,
+    start: Duration,
+    transition: Transition,
+}
+
+/// An animation and when it started.
+#[derive(Debug, Clone)]
+struct Playing {
+    animation: Animation,
+    keyframes: Keyframes,
+    start: Duration,
+}
+
+/// The transitions and animations of a node, see the [module docs](self).
+#[derive(Debug, Clone, Default)]
+pub struct Animator {
+    /// The last style from the cascade, changes to it start transitions.
+    target: Option<(Dimensions, Appearance)>,
+    /// The last style returned, transitions start from it so interrupting one doesn't jump.
+    shown: Option<(Dimensions, Appearance)>,
+    transitions: Vec<Running>,
+    animations: Vec<Playing>,
+    running: bool,
+}
+
+impl Animator {
+    /// Whether a transition or animation was still running at the last update.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// The style to show at `now` for the style `target` from the cascade and the element's
+    /// transitions and animations.
+    pub fn update(
+        &mut self,
+        target: (Dimensions, Appearance),
+        motion: &Motion,
+        now: Duration,
+        reduced_motion: bool,
+    ) -> (Dimensions, Appearance) {
+        if reduced_motion {
+            self.transitions.clear();
+            self.animations.clear();
+        } else {
+            self.start_transitions(&target, &motion.transitions, now);
+            self.sync_animations(&motion.animations, now);
+        }
+
+        let mut styles = target;
+        self.running = false;
+        for playing in self.animations.iter() {
+            let elapsed = now.saturating_sub(playing.start);
+            if let Some(progress) = playing.animation.progress(elapsed) {
+                playing
+                    .keyframes
+                    .apply(progress, playing.animation.easing, &target, &mut styles);
+            }
+            self.running |= !playing.animation.is_finished(elapsed);
+        }
+
+        self.transitions.retain(|running| {
+            let elapsed = now.saturating_sub(running.start);
+            let duration = running.transition.duration.as_secs_f32();
+            let t = match elapsed.checked_sub(running.transition.delay) {
+                None => 0.,
+                Some(_) if duration == 0. => 1.,
+                Some(active) => active.as_secs_f32() / duration,
+            };
+            if t >= 1. {
+                return false;
+            }
+            let eased = running.transition.easing.progress(t);
+            running
+                .from
+                .interpolate(&running.to, eased)
+                .apply(&mut styles.0, &mut styles.1);
+            true
+        });
+        self.running |= !self.transitions.is_empty();
+
+        self.target = Some(target);
+        self.shown = Some(styles);
+        styles
+    }
+
+    /// Start transitions for the properties whose value in the cascade changed. Running
+    /// transitions towards a value that is no longer the target are dropped.
+    fn start_transitions(
+        &mut self,
+        target: &(Dimensions, Appearance),
+        transitions: &[Transition],
+        now: Duration,
+    ) {
+        let (old, shown) = match (self.target, self.shown) {
+            (Some(old), Some(shown)) if old != *target => (old, shown),
+            _ => return,
+        };
+        self.transitions
+            .retain(|running| Style::of(running.property, target).as_ref() == Some(&running.to));
+
+        for property in ANIMATABLE {
+            // The last transition covering a property wins
+            let transition = match transitions
+                .iter()
+                .rev()
+                .find(|transition| transition.covers(property))
+            {
+                Some(transition) => transition,
+                None => continue,
+            };
+            let (from, to) = match (Style::of(property, &shown), Style::of(property, target)) {
+                (Some(from), Some(to)) => (from, to),
+                _ => continue,
+            };
+            if Style::of(property, &old).as_ref() == Some(&to) || from == to {
+                continue;
+            }
+
+            self.transitions
+                .retain(|running| running.property != property);
+            self.transitions.push(Running {
+                property,
+                from,
+                to,
+                start: now,
+                transition: transition.clone(),
+            });
+        }
+    }
+
+    /// Keep playing the animations that are still named, by name, and start the new ones.
+    fn sync_animations(&mut self, animations: &[(Animation, Keyframes)], now: Duration) {
+        let mut playing = Vec::new();
+        for (animation, keyframes) in animations {
+            let start = self
+                .animations
+                .iter()
+                .find(|playing| playing.animation.name == animation.name)
+                .map_or(now, |playing| playing.start);
+            playing.push(Playing {
+                animation: animation.clone(),
+                keyframes: keyframes.clone(),
+                start,
+            });
+        }
+        self.animations = playing;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, sync::Arc, time::Duration};
+
+    use style::{Stylesheet, Unit};
+
+    use crate::{
+        prelude::Renderable,
+        testing::{node, shared_leaf, Leaf},
+        State,
+    };
+
+    #[test]
+    fn transitions_and_animations_follow_the_time() {
+        let (_, leaf) = shared_leaf(node("", &["button"]), [0, 0, 10, 10], &[]);
+        leaf.borrow().node.set_scopes(&[Arc::new(
+            Stylesheet::try_parse(
+                ".button { width: 100px; transition: width 100ms linear; &:hover { width: 200px; } }
+                @keyframes open { from { height: 0px; } to { height: 40px; } }
+                .panel { animation: open 200ms linear forwards; }",
+            )
+            .unwrap(),
+        )]);
+        // A frame: move to the time, then resolve the style as a layout pass would
+        let frame = |leaf: &Arc<RefCell<Leaf>>, millis| {
+            let leaf = leaf.borrow();
+            leaf.node.set_time(Duration::from_millis(millis));
+            leaf.get_styles().0
+        };
+        assert_eq!(frame(&leaf, 0).width, Unit::PX(100.));
+
+        leaf.borrow_mut().set_state(State::HOVER, true);
+        assert_eq!(frame(&leaf, 0).width, Unit::PX(100.));
+        assert_eq!(frame(&leaf, 50).width, Unit::PX(150.));
+        assert!(leaf.borrow().node.is_animating());
+        assert_eq!(frame(&leaf, 100).width, Unit::PX(200.));
+        assert!(!leaf.borrow().node.is_animating());
+
+        // An interrupted transition starts from the width on screen
+        leaf.borrow_mut().set_state(State::HOVER, false);
+        frame(&leaf, 100);
+        assert_eq!(frame(&leaf, 150).width, Unit::PX(150.));
+        leaf.borrow_mut().set_state(State::HOVER, true);
+        frame(&leaf, 150);
+        assert_eq!(frame(&leaf, 175).width, Unit::PX(162.5));
+
+        leaf.borrow_mut().add_class("panel");
+        assert_eq!(frame(&leaf, 175).height, Unit::PX(0.));
+        assert_eq!(frame(&leaf, 275).height, Unit::PX(20.));
+        assert_eq!(frame(&leaf, 775).height, Unit::PX(40.));
+        assert!(!leaf.borrow().node.is_animating());
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt;
+
+use crate::{
+    layout::Child,
+    prelude::{Component, Container, Renderable},
+    Point, Rect,
+};
+
+/// A platform that windows and components are rendered with.
+///
+/// Each platform crate implements this for a marker type, e.g. `skylight::Skylight`, and the
+/// app is generic over it. Element operations default to the methods on the element traits so
+/// a backend only has to provide window creation, the event loop and message boxes.
+pub trait Backend: 'static {
+    /// Passed to components when they are created, usually the handle of the parent window.
+    type Data;
+    /// Errors raised by the platform.
+    type Error: fmt::Debug;
+    /// The top level window of the platform.
+    type Window: Container<Self::Data, Self::Error>;
+
+    /// Create a text element, `id` and `classes` are given without their `#` and `.` prefixes.
+    /// Used by [`text!`](crate::text).
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<Self::Data, Self::Error>;
+
+    /// Create the native window and its children. `index` is unique for each window in the app.
+    fn create_window(window: &mut Self::Window, index: u32) -> Result<(), Self::Error>;
+
+    fn create_component(
+        component: &mut dyn Component<Self::Data, Self::Error>,
+        data: Self::Data,
+    ) -> Result<(), Self::Error> {
+        component.create(data)
+    }
+
+    /// Move and resize an element returning the furthest point its content reaches.
+    fn set_rect(element: &mut dyn Renderable, rect: Rect) -> Point {
+        element.update(rect)
+    }
+
+    fn show(element: &mut dyn Renderable) {
+        element.show()
+    }
+
+    fn hide(element: &mut dyn Renderable) {
+        element.hide()
+    }
+
+    /// Show the windows and run the event loop until all of them are closed.
+    fn run(windows: Vec<Self::Window>) -> Result<(), Self::Error>;
+
+    /// Process the pending events of the windows without blocking. Returns whether any of the
+    /// windows is still open.
+    fn tick(windows: &mut [Self::Window]) -> Result<bool, Self::Error>;
+
+    /// Show a blocking message box. `buttons` and `icon` are names like `yes_no` and `info`.
+    ///
+    /// Returns `0` if the message was declined, `1` if it was accepted and `2` if it was dismissed.
+    fn message(title: &str, message: &str, buttons: &str, icon: &str) -> u8;
+}
+
+/// Create a text element with a backend, `text!(Headless; "Hello", "title", ["large"])` with
+/// an optional id and classes. Platforms wrap it without the backend in their preludes.
+#[macro_export]
+macro_rules! text {
+    ($backend: ty; $text: literal) => {
+        <$backend as $crate::Backend>::text($text, None, Vec::new())
+    };
+    ($backend: ty; $text: literal, $id: literal) => {
+        <$backend as $crate::Backend>::text($text, Some($id), Vec::new())
+    };
+    ($backend: ty; $text: literal, [$($class: literal),*]) => {
+        <$backend as $crate::Backend>::text($text, None, vec![$($class,)*])
+    };
+    ($backend: ty; $text: literal, $id: literal, [$($class: literal),*]) => {
+        <$backend as $crate::Backend>::text($text, Some($id), vec![$($class,)*])
+    };
+}
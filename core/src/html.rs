@@ -0,0 +1,263 @@
+//! Static HTML export of a laid out window.
+//!
+//! The layout tree becomes a tree of elements carrying the same ids and classes, and the
+//! active stylesheet is written to a `<style>` block, so the document can be opened in a
+//! browser to cross check layout or used as a mockup in docs. Scoped stylesheets follow it in
+//! `@scope` rules limited to the element that owns them, marked with a `data-scope` attribute.
+//! Each element also records the rect the layout engine computed for it in a `data-rect`
+//! attribute.
+
+use std::{fmt::Write, sync::Arc};
+
+use style::Stylesheet;
+
+use crate::{
+    layout::{Child, Layout},
+    prelude::{Container, Renderable},
+    raster::Content,
+    THEME,
+};
+
+/// Base rules so custom elements behave like the native boxes.
+const RESET: &str = "* { display: block; box-sizing: border-box; margin: 0; }\n\
+head, title, style { display: none; }\n\
+body { margin: 0; }\n";
+
+/// Export a window, the stylesheet of the active theme and the scoped stylesheets of the
+/// elements as a standalone HTML document.
+pub fn export<Data, Error, W>(window: &mut W, title: &str) -> String
+where
+    W: Container<Data, Error> + ?Sized,
+{
+    let mut body = String::new();
+    let mut scopes = Vec::new();
+    let size = window.rect().size();
+    let sizing = format!(
+        "width: {}px; height: {}px; overflow: hidden",
+        size.width, size.height
+    );
+    open_tag(&mut body, &mut scopes, 0, window, Some(&sizing));
+    body.push('\n');
+    write_layout(&mut body, &mut scopes, 1, window.layout());
+    let _ = writeln!(body, "</{}>", tag(window));
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{}</title>", escape(title));
+    html.push_str("<style>\n");
+    html.push_str(RESET);
+    let _ = write!(html, "{}", THEME.current());
+    for (index, stylesheet) in scopes.iter().enumerate() {
+        let _ = write!(
+            html,
+            "@scope ([data-scope=\"{}\"]) {{\n{}}}\n",
+            index, stylesheet
+        );
+    }
+    html.push_str("</style>\n</head>\n<body>\n");
+    html.push_str(&body);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_layout<Data, Error>(
+    html: &mut String,
+    scopes: &mut Vec<Arc<Stylesheet>>,
+    depth: usize,
+    layout: &mut Layout<Data, Error>,
+) {
+    for child in layout.children.iter() {
+        match child {
+            Child::Component(component) => {
+                let component = component.borrow();
+                open_tag(html, scopes, depth, &*component, None);
+                write_content(html, &component.content());
+                let _ = writeln!(html, "</{}>", tag(&*component));
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                open_tag(html, scopes, depth, container, None);
+                write_content(html, &container.content());
+                html.push('\n');
+                write_layout(html, scopes, depth + 1, container.layout());
+                let _ = writeln!(html, "{}</{}>", indent(depth), tag(container));
+            }
+        }
+    }
+}
+
+/// Write the opening tag of an element, collecting its scoped stylesheet into `scopes`.
+fn open_tag<R: Renderable + ?Sized>(
+    html: &mut String,
+    scopes: &mut Vec<Arc<Stylesheet>>,
+    depth: usize,
+    element: &R,
+    extra: Option<&str>,
+) {
+    let node = element.node();
+    let _ = write!(html, "{}<{}", indent(depth), tag(element));
+
+    let id = node.id.trim_start_matches('#');
+    if !id.is_empty() {
+        let _ = write!(html, " id=\"{}\"", escape(id));
+    }
+
+    let mut classes: Vec<&str> = node
+        .classes
+        .iter()
+        .map(|class| class.trim_start_matches('.'))
+        .collect();
+    classes.sort();
+    if !classes.is_empty() {
+        let _ = write!(html, " class=\"{}\"", escape(&classes.join(" ")));
+    }
+
+    let mut inline: Vec<String> = node
+        .inline()
+        .iter()
+        .map(|style| style.to_string())
+        .filter(|style| !style.is_empty())
+        .collect();
+    if let Some(extra) = extra {
+        inline.push(extra.to_string());
+    }
+    if !inline.is_empty() {
+        let _ = write!(html, " style=\"{}\"", escape(&inline.join("; ")));
+    }
+
+    if let Some(stylesheet) = node.stylesheet() {
+        let _ = write!(html, " data-scope=\"{}\"", scopes.len());
+        scopes.push(stylesheet.clone());
+    }
+
+    let rect = element.rect();
+    let _ = write!(
+        html,
+        " data-rect=\"{} {} {} {}\">",
+        rect.left,
+        rect.top,
+        rect.width(),
+        rect.height()
+    );
+}
+
+fn write_content(html: &mut String, content: &Content) {
+    match content {
+        Content::None => (),
+        Content::Text(text) => html.push_str(&escape(text)),
+        Content::Image(image) => {
+            let _ = write!(
+                html,
+                "<img src=\"data:image/png;base64,{}\" width=\"{}\" height=\"{}\">",
+                base64(&image.to_png()),
+                image.width(),
+                image.height()
+            );
+        }
+    }
+}
+
+/// The element name, the element type class like `text`, or `div` when there isn't one.
+fn tag<R: Renderable + ?Sized>(element: &R) -> String {
+    let mut types: Vec<&String> = element
+        .classes()
+        .iter()
+        .filter(|class| !class.starts_with('.') && !class.starts_with('#'))
+        .collect();
+    types.sort();
+    types
+        .first()
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| "div".to_string())
+}
+
+fn indent(depth: usize) -> String {
+    "  ".repeat(depth)
+}
+
+/// Escape text for use in element content and quoted attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{leaf, node, shared_block};
+
+    #[test]
+    fn escape_and_base64() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+    }
+
+    #[test]
+    fn scoped_stylesheets_are_exported() {
+        let (_, card) = shared_block(
+            node("card", &["card"]),
+            [0, 0, 100, 50],
+            &[],
+            vec![leaf([0, 0, 10, 10], &[]).1],
+        );
+        card.borrow_mut().set_stylesheet(Some(
+            Stylesheet::try_parse("text { width: 10px; }").unwrap(),
+        ));
+        let (_, window) = shared_block(
+            node("", &[]),
+            [0, 0, 200, 100],
+            &[],
+            vec![Child::Container(card)],
+        );
+
+        let html = export(&mut *window.borrow_mut(), "Scopes");
+        assert!(
+            html.contains("@scope ([data-scope=\"0\"]) {\ntext {"),
+            "{}",
+            html
+        );
+        assert!(
+            html.contains("id=\"card\" class=\"card\" data-scope=\"0\""),
+            "{}",
+            html
+        );
+        assert_eq!(html.matches(" data-scope=").count(), 1);
+    }
+}
@@ -1,17 +1,26 @@
-use std::{cell::RefCell, sync::Arc};
+use std::{cell::RefCell, collections::HashSet, sync::Arc, time::Duration};
 
-use style::{Dimensions, Position, Size, Unit};
+use style::{Appearance, Dimensions, Media, Position, Size, Stylesheet, Unit};
 
-use crate::Rect;
+use crate::{node::DirtyFlag, raster::Content, NodeId, Point, Rect, State, TreePosition};
 
 use super::prelude::{Component, Container};
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum Child<Data, Error> {
     Component(Arc<RefCell<dyn Component<Data, Error>>>),
     Container(Arc<RefCell<dyn Container<Data, Error>>>),
 }
 
+impl<Data, Error> Clone for Child<Data, Error> {
+    fn clone(&self) -> Self {
+        match self {
+            Child::Component(component) => Child::Component(component.clone()),
+            Child::Container(container) => Child::Container(container.clone()),
+        }
+    }
+}
+
 pub struct LayoutBuilder<Data, Error> {
     children: Vec<Child<Data, Error>>,
 }
@@ -23,28 +32,78 @@ impl<Data, Error> LayoutBuilder<Data, Error> {
     }
 
     pub fn build(self) -> Layout<Data, Error> {
-        Layout {
-            children: self.children,
-        }
+        Layout::from(self.children)
     }
 }
 
+/// Everything a child's layout depends on besides its own computed style.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutInput {
+    pub scale: f32,
+    pub parent: Rect,
+    pub padding: Size,
+    pub previous: Option<(Rect, Size)>,
+    pub rect: Rect,
+    pub default_rect: Rect,
+}
+
+/// The result of laying out a child along with the input that produced it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutCache {
+    pub input: LayoutInput,
+    /// The rect in logical pixels.
+    pub rect: Rect,
+    /// The furthest point reached by the child and its content.
+    pub extent: Point,
+}
+
+/// Children of a container and the logic to position them.
+///
+/// Layout math is done in logical pixels, where a CSS `px` is one logical pixel. Rects are
+/// multiplied by the layout's scale factor when they are committed to the components, so
+/// component rects are always in device pixels.
 #[derive(Debug, Clone)]
 pub struct Layout<Data, Error> {
     pub children: Vec<Child<Data, Error>>,
+    scale: f32,
+    /// Scoped stylesheets of the container owning the layout and its ancestors.
+    scopes: Vec<Arc<Stylesheet>>,
+    /// Selector keys of the container owning the layout and its ancestors, outermost first.
+    ancestors: Vec<HashSet<String>>,
+    /// Computed style of the container owning the layout.
+    parent_style: Option<(Dimensions, Appearance)>,
+    /// Media of the window the layout is in.
+    media: Media,
+    /// Time of the current frame of the window.
+    time: Duration,
+    /// Set when an element of the layout, or of a nested one, needs to be laid out again.
+    dirty: Arc<DirtyFlag>,
 }
 
 impl<Data, Error> From<Vec<Child<Data, Error>>> for Layout<Data, Error> {
     fn from(value: Vec<Child<Data, Error>>) -> Self {
-        Layout { children: value }
+        let layout = Layout {
+            children: value,
+            scale: 1.,
+            scopes: Vec::new(),
+            ancestors: Vec::new(),
+            parent_style: None,
+            media: Media::default(),
+            time: Duration::ZERO,
+            dirty: Arc::new(DirtyFlag::default()),
+        };
+        layout.dirty.set();
+        for child in layout.children.iter() {
+            layout.attach(child);
+        }
+        layout.reindex();
+        layout
     }
 }
 
 impl<Data, Error> Layout<Data, Error> {
     pub fn new() -> Layout<Data, Error> {
-        Layout {
-            children: Vec::new(),
-        }
+        Layout::from(Vec::new())
     }
 
     pub fn builder() -> LayoutBuilder<Data, Error> {
@@ -53,67 +112,453 @@ impl<Data, Error> Layout<Data, Error> {
         }
     }
 
-    pub fn update(&mut self, parent: &Rect, pstyle: &Dimensions) -> (i32, i32) {
-        let rect = parent.shift(&pstyle.padding.calc(parent.width(), parent.height()));
+    /// Device pixels per logical pixel.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
 
-        let mut previous: Option<(Rect, Size)> = None;
+    /// Change the scale factor, letting every element in the layout rescale.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        for child in self.children.iter() {
+            match child {
+                Child::Component(component) => component.borrow_mut().rescale(scale),
+                Child::Container(container) => {
+                    let container = &mut *container.borrow_mut();
+                    container.rescale(scale);
+                    container.layout().set_scale(scale);
+                }
+            }
+        }
+    }
+
+    /// The scoped stylesheets applied to the children, outermost first.
+    pub fn scopes(&self) -> &[Arc<Stylesheet>] {
+        &self.scopes
+    }
+
+    /// Set the scoped stylesheets of the owning container and its ancestors, usually
+    /// `node.child_scopes()`. Children whose scopes change are restyled. Nested layouts pick
+    /// the change up when they are updated.
+    pub fn set_scopes(&mut self, scopes: &[Arc<Stylesheet>]) {
+        let same = self.scopes.len() == scopes.len()
+            && self
+                .scopes
+                .iter()
+                .zip(scopes)
+                .all(|(a, b)| Arc::ptr_eq(a, b));
+        if same {
+            return;
+        }
+
+        self.scopes = scopes.to_vec();
+        for child in self.children.iter() {
+            match child {
+                Child::Component(component) => component.borrow().node().set_scopes(scopes),
+                Child::Container(container) => container.borrow().node().set_scopes(scopes),
+            };
+        }
+    }
+
+    /// Selector keys of the owning container and its ancestors, outermost first.
+    pub fn ancestors(&self) -> &[HashSet<String>] {
+        &self.ancestors
+    }
+
+    /// Set the selector keys of the owning container and its ancestors. Children are restyled
+    /// if they change, nested layouts pick the change up when they are updated.
+    pub fn set_ancestors(&mut self, ancestors: &[HashSet<String>]) {
+        if self.ancestors == ancestors {
+            return;
+        }
+
+        self.ancestors = ancestors.to_vec();
+        for child in self.children.iter() {
+            match child {
+                Child::Component(component) => component.borrow().node().set_ancestors(ancestors),
+                Child::Container(container) => container.borrow().node().set_ancestors(ancestors),
+            };
+        }
+    }
+
+    /// The computed style of the owning container the children inherit from.
+    pub fn parent_style(&self) -> Option<(Dimensions, Appearance)> {
+        self.parent_style
+    }
+
+    /// Set the computed style of the owning container. Children are restyled if it changed,
+    /// nested layouts pick the change up when their container is updated.
+    pub fn set_parent_style(&mut self, style: Option<(Dimensions, Appearance)>) {
+        if self.parent_style == style {
+            return;
+        }
+
+        self.parent_style = style;
+        for child in self.children.iter() {
+            match child {
+                Child::Component(component) => component.borrow().node().set_parent_style(style),
+                Child::Container(container) => container.borrow().node().set_parent_style(style),
+            };
+        }
+    }
+
+    /// The media `@media` rules of the children are evaluated against.
+    pub fn media(&self) -> Media {
+        self.media
+    }
 
-        let mut largest = (parent.right.clone(), parent.bottom.clone());
+    /// Set the media of the window, usually when it is resized. Elements anywhere in the layout
+    /// are restyled if one of their `@media` rules starts or stops matching.
+    pub fn set_media(&mut self, media: Media) {
+        if self.media == media {
+            return;
+        }
+
+        self.media = media;
         for child in self.children.iter() {
             match child {
                 Child::Component(component) => {
-                    let component = &mut *component.borrow_mut();
-                    let dimensions = component.get_styles().0;
-                    let br = component.update(self.calc(
-                        component.rect(),
-                        component.default_rect(),
-                        &dimensions,
-                        &rect,
-                        &pstyle.padding,
-                        previous,
-                    ));
-                    let margin = dimensions.margin.calc(parent.width(), parent.height());
-                    // PERF: Better larget point calc
-                    let br = (br.0 + margin.3, br.1 + margin.2);
+                    component.borrow().node().set_media(media);
+                }
+                Child::Container(container) => {
+                    let container = &mut *container.borrow_mut();
+                    container.node().set_media(media);
+                    container.layout().set_media(media);
+                }
+            }
+        }
+    }
 
-                    previous = Some((component.rect().clone(), dimensions.margin));
+    /// Move every element in the layout to the frame at `now`, see
+    /// [`Container::animate`]. Returns whether any of them is animating.
+    pub fn set_time(&mut self, now: Duration) -> bool {
+        self.time = now;
+        let mut running = false;
+        for child in self.children.iter() {
+            running |= match child {
+                Child::Component(component) => component.borrow().node().set_time(now),
+                Child::Container(container) => container.borrow_mut().animate(now),
+            };
+        }
+        running
+    }
 
-                    if br.0 > largest.0 {
-                        largest.0 = br.0;
-                    }
-                    if br.1 > largest.1 {
-                        largest.1 = br.1;
-                    }
+    /// Whether a transition or animation of an element in the layout is running.
+    pub fn is_animating(&mut self) -> bool {
+        self.children.iter().any(|child| match child {
+            Child::Component(component) => component.borrow().node().is_animating(),
+            Child::Container(container) => container.borrow_mut().is_animating(),
+        })
+    }
+
+    pub fn push(&mut self, child: Child<Data, Error>) {
+        self.adopt(&child);
+        self.children.push(child);
+        self.reindex();
+    }
+
+    pub fn insert(&mut self, index: usize, child: Child<Data, Error>) {
+        self.adopt(&child);
+        self.children.insert(index, child);
+        self.reindex();
+    }
+
+    /// Give a new child the scoped stylesheets of the layout.
+    fn adopt(&self, child: &Child<Data, Error>) {
+        self.attach(child);
+        match child {
+            Child::Component(component) => {
+                let component = component.borrow();
+                component.node().set_scopes(&self.scopes);
+                component.node().set_ancestors(&self.ancestors);
+                component.node().set_parent_style(self.parent_style);
+                component.node().set_media(self.media);
+                component.node().set_time(self.time);
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                container.node().set_scopes(&self.scopes);
+                container.node().set_ancestors(&self.ancestors);
+                container.node().set_parent_style(self.parent_style);
+                container.node().set_media(self.media);
+                container.layout().set_media(self.media);
+                container.animate(self.time);
+            }
+        };
+    }
+
+    pub fn remove(&mut self, index: usize) -> Child<Data, Error> {
+        let child = self.children.remove(index);
+        match &child {
+            Child::Component(component) => component.borrow().node().set_flag(None),
+            Child::Container(container) => container.borrow().node().set_flag(None),
+        }
+        self.dirty.set();
+        self.reindex();
+        child
+    }
+
+    /// Link the child to the dirty flag of the layout, so marking it, or anything nested in it,
+    /// flags the layout.
+    fn attach(&self, child: &Child<Data, Error>) {
+        match child {
+            Child::Component(component) => component.borrow().node().set_flag(Some(&self.dirty)),
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                container.node().set_flag(Some(&self.dirty));
+                container.layout().dirty.set_parent(&self.dirty);
+            }
+        }
+    }
+
+    /// Give every child its position among its siblings, restyling the ones that moved.
+    fn reindex(&self) {
+        let siblings = self.children.len();
+        for (index, child) in self.children.iter().enumerate() {
+            let position = |empty| TreePosition {
+                index: index + 1,
+                siblings,
+                empty,
+            };
+            match child {
+                Child::Component(component) => {
+                    let component = component.borrow();
+                    let empty = match component.content() {
+                        Content::None => true,
+                        Content::Text(text) => text.is_empty(),
+                        Content::Image(_) => false,
+                    };
+                    component.node().set_position(position(empty));
                 }
                 Child::Container(container) => {
                     let container = &mut *container.borrow_mut();
-                    let dimensions = container.get_styles().0;
-                    let crect = self.calc(
-                        container.rect(),
-                        container.default_rect(),
-                        &dimensions,
-                        &rect,
-                        &pstyle.padding,
-                        previous,
-                    );
+                    let empty = container.layout().children.is_empty()
+                        && matches!(container.content(), Content::None);
+                    container.node().set_position(position(empty));
+                }
+            };
+        }
+    }
+
+    /// Invalidate the computed styles of every element in the layout.
+    pub fn restyle(&mut self) {
+        for child in self.children.iter() {
+            match child {
+                Child::Component(component) => component.borrow().node().invalidate(),
+                Child::Container(container) => {
+                    let container = &mut *container.borrow_mut();
+                    container.node().invalidate();
+                    container.layout().restyle();
+                }
+            }
+        }
+    }
 
-                    container.layout().update(&crect, &dimensions);
-                    previous = Some((crect.clone(), dimensions.margin));
-                    let br = container.update(crect);
+    /// Add `state` to the elements in `path` and remove it from the others, see
+    /// [`Container::update_state`].
+    pub fn update_state(&mut self, state: State, path: &[NodeId]) -> bool {
+        let mut changed = false;
+        for child in self.children.iter() {
+            changed |= match child {
+                Child::Component(component) => {
+                    let node = component.borrow();
+                    let id = node.node().node_id();
+                    node.node().set_state(state, path.contains(&id))
+                }
+                Child::Container(container) => container.borrow_mut().update_state(state, path),
+            };
+        }
+        changed
+    }
 
-                    let margin = dimensions.margin.calc(parent.width(), parent.height());
-                    let br = (br.0 + margin.3, br.1 + margin.2);
+    /// Check if any element in the layout, nested layouts included, needs to be laid out
+    /// again.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.is_set()
+    }
 
-                    if br.0 > largest.0 {
-                        largest.0 = br.0;
+    /// Find the elements under `point`, given relative to the origin of the element owning
+    /// the layout. Returns the node ids from the outermost element hit to the innermost.
+    ///
+    /// Children are tested from the top of the stacking order down: a higher `z-index` first,
+    /// then absolutely positioned children, then later siblings before earlier ones. Containers
+    /// with `overflow: hidden` or `overflow: scroll` clip their content to their rect and
+    /// offset it by their scroll position.
+    pub fn hit_test(&mut self, point: Point) -> Vec<NodeId> {
+        let mut order: Vec<(i32, bool, usize)> = self
+            .children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let dimensions = match child {
+                    Child::Component(component) => component.borrow().get_styles().0,
+                    Child::Container(container) => container.borrow().get_styles().0,
+                };
+                (
+                    dimensions.z_index.unwrap_or(0),
+                    dimensions.position == Position::Absolute,
+                    index,
+                )
+            })
+            .collect();
+        order.sort();
+
+        for (_, _, index) in order.into_iter().rev() {
+            let path = match &self.children[index] {
+                Child::Component(component) => {
+                    let component = component.borrow();
+                    match component.rect().contains(point) {
+                        true => vec![component.node().node_id()],
+                        false => Vec::new(),
                     }
-                    if br.1 > largest.1 {
-                        largest.1 = br.1;
+                }
+                Child::Container(container) => container.borrow_mut().hit_test(point),
+            };
+
+            if !path.is_empty() {
+                return path;
+            }
+        }
+        Vec::new()
+    }
+
+    /// Lay out the children inside of the parent rect returning the furthest point reached.
+    ///
+    /// Children that are clean and see the same input as their last layout reuse the cached
+    /// result, and containers whose layout is clean aren't descended into. Components are only
+    /// updated, which moves the native control, when their rect actually changed.
+    ///
+    /// `parent` and the returned point are in logical pixels. Components receive their rect in
+    /// device pixels.
+    pub fn update(&mut self, parent: &Rect, pstyle: &Dimensions) -> Point {
+        self.reindex();
+        let scale = self.scale;
+        let rect = parent.deflate(&pstyle.padding.calc(parent.width(), parent.height()));
+
+        let mut previous: Option<(Rect, Size)> = None;
+
+        let mut largest = Point::new(parent.right, parent.bottom);
+        for child in self.children.iter() {
+            let (crect, br, margin) = match child {
+                Child::Component(component) => {
+                    let component = &mut *component.borrow_mut();
+                    component.node().set_scopes(&self.scopes);
+                    component.node().set_ancestors(&self.ancestors);
+                    component.node().set_parent_style(self.parent_style);
+                    component.node().set_flag(Some(&self.dirty));
+                    let dimensions = component.get_styles().0;
+                    let current =
+                        logical_rect(component.node().last_layout(), component.rect(), scale);
+                    let input = LayoutInput {
+                        scale,
+                        parent: rect,
+                        padding: pstyle.padding,
+                        previous,
+                        rect: current,
+                        default_rect: *component.default_rect(),
+                    };
+
+                    let cache = match component.node().cached_layout(&input) {
+                        Some(cache) => cache,
+                        None => {
+                            let crect = self.calc(
+                                &current,
+                                component.default_rect(),
+                                &dimensions,
+                                &rect,
+                                &pstyle.padding,
+                                previous,
+                            );
+
+                            let extent = match component.node().last_layout() {
+                                Some(last)
+                                    if !component.node().is_dirty()
+                                        && last.rect == crect
+                                        && *component.rect() == crect.scale(scale) =>
+                                {
+                                    last.extent
+                                }
+                                _ => component.update(crect.scale(scale)).scale(1. / scale),
+                            };
+
+                            let cache = LayoutCache {
+                                input,
+                                rect: crect,
+                                extent,
+                            };
+                            component.node().set_layout(cache);
+                            cache
+                        }
+                    };
+
+                    (cache.rect, cache.extent, dimensions.margin)
+                }
+                Child::Container(container) => {
+                    let container = &mut *container.borrow_mut();
+                    if container.layout().scale() != scale {
+                        container.layout().set_scale(scale);
                     }
+                    container.node().set_flag(Some(&self.dirty));
+                    container.layout().dirty.set_parent(&self.dirty);
+                    container.node().set_scopes(&self.scopes);
+                    let scopes = container.node().child_scopes();
+                    container.layout().set_scopes(&scopes);
+                    container.node().set_ancestors(&self.ancestors);
+                    let mut ancestors = self.ancestors.clone();
+                    ancestors.push(container.keys());
+                    container.layout().set_ancestors(&ancestors);
+                    container.node().set_parent_style(self.parent_style);
+                    let styles = container.get_styles();
+                    container.layout().set_parent_style(Some(styles));
+                    let dimensions = styles.0;
+                    let current =
+                        logical_rect(container.node().last_layout(), container.rect(), scale);
+                    let input = LayoutInput {
+                        scale,
+                        parent: rect,
+                        padding: pstyle.padding,
+                        previous,
+                        rect: current,
+                        default_rect: *container.default_rect(),
+                    };
+
+                    let cache = match container.node().cached_layout(&input) {
+                        Some(cache) if !container.layout().is_dirty() => cache,
+                        _ => {
+                            let crect = self.calc(
+                                &current,
+                                container.default_rect(),
+                                &dimensions,
+                                &rect,
+                                &pstyle.padding,
+                                previous,
+                            );
+
+                            container.layout().update(&crect, &dimensions);
+                            let cache = LayoutCache {
+                                input,
+                                rect: crect,
+                                extent: container.update(crect.scale(scale)).scale(1. / scale),
+                            };
+                            container.node().set_layout(cache);
+                            cache
+                        }
+                    };
+
+                    (cache.rect, cache.extent, dimensions.margin)
                 }
             };
+
+            // PERF: Better larget point calc
+            let cmargin = margin.calc(parent.width(), parent.height());
+            let br = br.translate(cmargin.left, cmargin.bottom);
+            previous = Some((crect, margin));
+
+            largest.x = largest.x.max(br.x);
+            largest.y = largest.y.max(br.y);
         }
 
+        self.dirty.clear();
         largest
     }
 
@@ -138,37 +583,39 @@ impl<Data, Error> Layout<Data, Error> {
             .calc(parent_rect.width(), parent_rect.height());
 
         let width = dimensions.width.as_i32(
-            parent_rect.width() - ppadding.1 - ppadding.3 - margin.1 - margin.3,
+            parent_rect.width() - ppadding.right - ppadding.left - margin.right - margin.left,
             match dimensions.position {
                 style::Position::Absolute
-                    if inset.3 != 0 && inset.1 != 0 && dimensions.width == Unit::Default =>
+                    if inset.left != 0 && inset.right != 0 && dimensions.width == Unit::Default =>
                 {
-                    parent_rect.width() - inset.3 - inset.1 - margin.1 - margin.3
+                    parent_rect.width() - inset.left - inset.right - margin.right - margin.left
                 }
                 _ => match dimensions.width {
                     Unit::FitConent => {
                         add_padding.0 = true;
                         default_rect.width()
                     }
-                    _ => parent_rect.width() - margin.1 - margin.3,
+                    _ => parent_rect.width() - margin.right - margin.left,
                 },
             },
         );
 
         let height = dimensions.height.as_i32(
-            parent_rect.height() - ppadding.0 - ppadding.2,
+            parent_rect.height() - ppadding.top - ppadding.bottom,
             match dimensions.position {
                 Position::Absolute
-                    if inset.0 != 0 && inset.2 != 0 && dimensions.height == Unit::Default =>
+                    if inset.top != 0
+                        && inset.bottom != 0
+                        && dimensions.height == Unit::Default =>
                 {
-                    parent_rect.height() - inset.2 - inset.0 - margin.2 - margin.0
+                    parent_rect.height() - inset.bottom - inset.top - margin.bottom - margin.top
                 }
                 _ => match dimensions.height {
                     Unit::FitConent | Unit::Default => {
                         add_padding.1 = true;
                         default_rect.height()
                     }
-                    _ => parent_rect.height() - margin.0 - margin.2,
+                    _ => parent_rect.height() - margin.top - margin.bottom,
                 },
             },
         );
@@ -179,28 +626,28 @@ impl<Data, Error> Layout<Data, Error> {
         crect.left = match dimensions.position {
             style::Position::Absolute => match dimensions.inset.left {
                 Unit::Default => match dimensions.inset.right {
-                    Unit::Default => margin.3,
-                    _ => rect.width() - margin.1 - inset.1 - width,
+                    Unit::Default => margin.left,
+                    _ => rect.width() - margin.right - inset.right - width,
                 },
-                _ => margin.3 + inset.3,
+                _ => margin.left + inset.left,
             },
-            _ => ppadding.3 + margin.3,
+            _ => ppadding.left + margin.left,
         };
 
         crect.top = match dimensions.position {
             style::Position::Absolute => match dimensions.inset.top {
                 Unit::Default => match dimensions.inset.bottom {
-                    Unit::Default => margin.0,
-                    _ => rect.height() - margin.2 - inset.2 - height,
+                    Unit::Default => margin.top,
+                    _ => rect.height() - margin.bottom - inset.bottom - height,
                 },
-                _ => margin.0 + inset.0,
+                _ => margin.top + inset.top,
             },
             _ => match previous {
                 Some((prect, pmargin)) => {
                     let (bottom, pad) = (prect.bottom, pmargin.bottom.as_i32(rect.height(), 0));
-                    bottom + pad + margin.0
+                    bottom + pad + margin.top
                 }
-                None => ppadding.0 + margin.0,
+                None => ppadding.top + margin.top,
             },
         };
 
@@ -208,11 +655,11 @@ impl<Data, Error> Layout<Data, Error> {
         crect.bottom = crect.top + height;
 
         if add_padding.0 {
-            crect.right += padding.3 + padding.1 + 4;
+            crect.right += padding.left + padding.right + 4;
         }
 
         if add_padding.1 {
-            crect.bottom += padding.0 + padding.2;
+            crect.bottom += padding.top + padding.bottom;
         }
 
         match dimensions.max_width {
@@ -258,3 +705,308 @@ impl<Data, Error> Layout<Data, Error> {
         crect
     }
 }
+
+/// The rect of an element in logical pixels. The rect of the last layout is used as long as the
+/// element is still at it, since at fractional scales scaling the device rect back doesn't
+/// always give the same rect.
+fn logical_rect(last: Option<LayoutCache>, rect: &Rect, scale: f32) -> Rect {
+    match last {
+        Some(last) if last.rect.scale(scale) == *rect => last.rect,
+        _ => rect.scale(1. / scale),
+    }
+}
+
+/// Create a layout from its children, `layout![text!("Hello"), text!("World")]`.
+#[macro_export]
+macro_rules! layout {
+    [$($child: expr),* $(,)?] => {
+        $crate::layout::Layout::from(vec![$($child,)*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashSet, sync::Arc};
+
+    use style::{color::Color, Dimensions, Direction, FontStyle, Media, Style, Stylesheet, Unit};
+
+    use super::{Child, Layout};
+    use crate::{
+        prelude::Renderable,
+        testing::{block, leaf, node, shared_block, shared_leaf, Block, Leaf},
+        Dirty, NodeId, Point, Rect, State,
+    };
+
+    fn sheet(src: &str) -> Arc<Stylesheet> {
+        Arc::new(Stylesheet::try_parse(src).unwrap())
+    }
+
+    fn width(leaf: &Arc<RefCell<Leaf>>) -> Unit {
+        leaf.borrow().get_styles().0.width
+    }
+
+    fn height(leaf: &Arc<RefCell<Leaf>>) -> Unit {
+        leaf.borrow().get_styles().0.height
+    }
+
+    fn label(classes: &[&str]) -> Arc<RefCell<Leaf>> {
+        shared_leaf(node("", classes), [0, 0, 10, 10], &[]).1
+    }
+
+    #[test]
+    fn only_dirty_subtrees_are_laid_out() {
+        let (_, changed) = shared_leaf(node("", &[]), [0, 0, 100, 20], &[]);
+        let (_, clean) = shared_leaf(node("", &[]), [0, 0, 100, 20], &[]);
+        let (_, nested) = shared_block(
+            node("", &[]),
+            [0, 0, 100, 40],
+            &[],
+            vec![Child::Component(changed.clone())],
+        );
+        let (_, sibling) = shared_block(
+            node("", &[]),
+            [0, 0, 100, 40],
+            &[],
+            vec![Child::Component(clean.clone())],
+        );
+        let mut layout = Layout::from(vec![
+            Child::Container(nested.clone()),
+            Child::Container(sibling.clone()),
+        ]);
+        let parent = Rect::new(0, 0, 200, 200);
+
+        // The second pass sees the rects of the first one
+        layout.update(&parent, &Dimensions::default());
+        layout.update(&parent, &Dimensions::default());
+        assert!(!layout.is_dirty());
+        let updates = |leaf: &Arc<RefCell<Leaf>>, block: &Arc<RefCell<Block>>| {
+            (leaf.borrow().updates, block.borrow().updates)
+        };
+        let (changed_before, clean_before) =
+            (updates(&changed, &nested), updates(&clean, &sibling));
+
+        changed.borrow_mut().set_style("height", "30px").unwrap();
+        assert!(layout.is_dirty());
+        layout.update(&parent, &Dimensions::default());
+        assert!(!layout.is_dirty());
+        assert_eq!(
+            updates(&changed, &nested),
+            (changed_before.0 + 1, changed_before.1 + 1)
+        );
+        assert_eq!(updates(&clean, &sibling), clean_before);
+    }
+
+    #[test]
+    fn cache_hits_at_fractional_scales() {
+        let (_, text) = shared_leaf(node("", &[]), [0, 0, 33, 13], &[("width", "33px")]);
+        // 10px is 7.5 device pixels, which rounds to 8 and scales back to 10.67. Positioning
+        // from the right depends on the current width, so reading it back from the device rect
+        // would move the block on every pass.
+        let (_, parent) = shared_block(
+            node("", &[]),
+            [0, 0, 10, 41],
+            &[
+                ("position", "absolute"),
+                ("right", "0px"),
+                ("width", "10px"),
+            ],
+            vec![Child::Component(text.clone())],
+        );
+        let mut layout = Layout::from(vec![Child::Container(parent.clone())]);
+        layout.set_scale(0.75);
+
+        let rect = Rect::new(0, 0, 201, 201);
+        layout.update(&rect, &Dimensions::default());
+        layout.update(&rect, &Dimensions::default());
+        let before = (parent.borrow().updates, text.borrow().updates);
+        parent.borrow().node.mark(Dirty::STYLE);
+        layout.update(&rect, &Dimensions::default());
+        layout.update(&rect, &Dimensions::default());
+        assert_eq!(
+            (parent.borrow().updates, text.borrow().updates),
+            (before.0 + 1, before.1)
+        );
+    }
+
+    #[test]
+    fn hit_test_stacking_order() {
+        let (absolute, absolute_child) = leaf([0, 0, 50, 50], &[("position", "absolute")]);
+        let (first, first_child) = leaf([0, 0, 100, 60], &[]);
+        let (raised, raised_child) = leaf([40, 0, 100, 20], &[("z-index", "2")]);
+        let mut layout = Layout::from(vec![absolute_child, raised_child, first_child]);
+
+        assert_eq!(layout.hit_test(Point::new(5, 5)), vec![absolute]);
+        assert_eq!(layout.hit_test(Point::new(45, 5)), vec![raised]);
+        assert_eq!(layout.hit_test(Point::new(20, 55)), vec![first]);
+        assert_eq!(layout.hit_test(Point::new(150, 5)), Vec::<NodeId>::new());
+    }
+
+    #[test]
+    fn hit_test_scroll_and_clip() {
+        let (inner, inner_child) = leaf([10, 60, 60, 80], &[]);
+        let (_, hidden_child) = leaf([0, 150, 50, 170], &[]);
+        let (clipped, clipped_child) = block(
+            [0, 20, 200, 120],
+            &[("overflow", "hidden")],
+            Point::new(0, 50),
+            vec![inner_child, hidden_child],
+        );
+        let (overflow, overflow_child) = leaf([0, 150, 50, 170], &[]);
+        let (visible, visible_child) = block(
+            [0, 200, 200, 220],
+            &[],
+            Point::default(),
+            vec![overflow_child],
+        );
+        let mut layout = Layout::from(vec![clipped_child, visible_child]);
+
+        assert_eq!(layout.hit_test(Point::new(20, 35)), vec![clipped, inner]);
+        assert_eq!(layout.hit_test(Point::new(20, 100)), vec![clipped]);
+        assert_eq!(layout.hit_test(Point::new(5, 125)), Vec::<NodeId>::new());
+        assert_eq!(layout.hit_test(Point::new(5, 355)), vec![visible, overflow]);
+    }
+
+    #[test]
+    fn scoped_stylesheets_style_only_their_layout() {
+        let (first, second) = (label(&["label"]), label(&["label"]));
+        let mut layout = Layout::<(), String>::from(vec![Child::Component(first.clone())]);
+        layout.set_scopes(&[sheet(".label { width: 50px; }")]);
+        let mut other = Layout::<(), String>::from(vec![Child::Component(second.clone())]);
+        other.set_scopes(&[sheet(".label { width: 80px; }")]);
+        assert_eq!(width(&first), Unit::PX(50.));
+        assert_eq!(width(&second), Unit::PX(80.));
+
+        // Inline styles still win over the scoped sheet
+        first.borrow_mut().set_style("width", "60px").unwrap();
+        other.set_scopes(&[]);
+        assert_eq!(width(&first), Unit::PX(60.));
+        assert_eq!(width(&second), Unit::Default);
+    }
+
+    #[test]
+    fn media_changes_restyle_across_breakpoints() {
+        let sidebar = label(&["sidebar"]);
+        let mut layout = Layout::<(), String>::from(vec![Child::Component(sidebar.clone())]);
+        layout.set_scopes(&[sheet(
+            ".sidebar { width: 100px; } @media (max-width: 300px) { .sidebar { width: 40px; } }",
+        )]);
+        let media = |width| Media {
+            width,
+            ..Media::default()
+        };
+        layout.set_media(media(400.));
+        assert_eq!(width(&sidebar), Unit::PX(100.));
+        layout.set_media(media(250.));
+        assert_eq!(width(&sidebar), Unit::PX(40.));
+
+        // Resizing without crossing the breakpoint keeps the computed style
+        layout.set_media(media(280.));
+        assert!(sidebar.borrow().node.computed().is_some());
+        layout.set_media(media(320.));
+        assert!(sidebar.borrow().node.computed().is_none());
+        assert_eq!(width(&sidebar), Unit::PX(100.));
+    }
+
+    #[test]
+    fn nested_rules_match_the_ancestors() {
+        let sidebar = label(&["sidebar"]);
+        let mut layout = Layout::<(), String>::from(vec![Child::Component(sidebar.clone())]);
+        layout.set_scopes(&[sheet(
+            ".sidebar { width: 100px; .compact > & { width: 40px; } .wide & { width: 200px; } }",
+        )]);
+        let keys =
+            |keys: &[&str]| -> HashSet<String> { keys.iter().map(|key| key.to_string()).collect() };
+
+        layout.set_ancestors(&[keys(&["window", ".compact"])]);
+        assert_eq!(width(&sidebar), Unit::PX(40.));
+        layout.set_ancestors(&[keys(&["window", ".wide"]), keys(&[".row"])]);
+        assert_eq!(width(&sidebar), Unit::PX(200.));
+        layout.set_ancestors(&[keys(&["window"])]);
+        assert_eq!(width(&sidebar), Unit::PX(100.));
+    }
+
+    #[test]
+    fn children_inherit_the_parent_style() {
+        let (menu, plain) = (label(&[]), label(&["plain"]));
+        let (_, parent) = shared_block(
+            node("", &["menu"]),
+            [0, 0, 100, 40],
+            &[],
+            vec![
+                Child::Component(menu.clone()),
+                Child::Component(plain.clone()),
+            ],
+        );
+        let parent = &mut *parent.borrow_mut();
+        parent.node.set_stylesheet(Some(sheet(
+            ".menu { font-style: italic; color: red; direction: rtl; } .plain { font-style: initial; }",
+        )));
+        let scopes = parent.node.child_scopes();
+        parent.layout.set_scopes(&scopes);
+        parent.layout.set_parent_style(Some(parent.get_styles()));
+
+        let appearance = menu.borrow().get_styles().1;
+        assert_eq!(
+            (appearance.font_style, appearance.direction),
+            (FontStyle::Italic, Direction::Rtl)
+        );
+        assert_eq!(appearance.color, Some(Color::new(255, 0, 0, 1.)));
+        assert_eq!(plain.borrow().get_styles().1.font_style, FontStyle::Normal);
+
+        // Restyling the parent reaches the children once its style is handed down again
+        parent.node.set_inline(Style::FontStyle(FontStyle::Oblique));
+        parent.restyle();
+        parent.layout.set_parent_style(Some(parent.get_styles()));
+        assert_eq!(menu.borrow().get_styles().1.font_style, FontStyle::Oblique);
+    }
+
+    #[test]
+    fn state_restyles_the_path() {
+        let (button, other) = (label(&["button"]), label(&["button"]));
+        let mut layout = Layout::<(), String>::from(vec![
+            Child::Component(button.clone()),
+            Child::Component(other.clone()),
+        ]);
+        layout.set_scopes(&[sheet(
+            ".button { width: 100px; &:hover { width: 120px; } }
+            .button:disabled { width: 50px !important; }",
+        )]);
+        let path = [button.borrow().node.node_id()];
+
+        assert!(layout.update_state(State::HOVER, &path));
+        assert!(!layout.update_state(State::HOVER, &path));
+        assert_eq!(width(&button), Unit::PX(120.));
+        assert_eq!(width(&other), Unit::PX(100.));
+
+        assert!(layout.update_state(State::HOVER, &[]));
+        assert_eq!(width(&button), Unit::PX(100.));
+
+        button.borrow_mut().set_state(State::DISABLED, true);
+        layout.update_state(State::HOVER, &path);
+        assert_eq!(width(&button), Unit::PX(50.));
+    }
+
+    #[test]
+    fn tree_position_and_attributes_restyle() {
+        let (a, b, c) = (label(&["item"]), label(&["item"]), label(&["item"]));
+        let mut layout = Layout::<(), String>::from(vec![
+            Child::Component(a.clone()),
+            Child::Component(b.clone()),
+        ]);
+        layout.set_scopes(&[sheet(
+            ".item { width: 10px; }
+            .item:nth-child(2n) { width: 20px; }
+            .item:last-child:not(:first-child) { height: 7px; }
+            [data-state^=err] { width: 30px; }",
+        )]);
+        assert_eq!(width(&a), Unit::PX(10.));
+        assert_eq!((width(&b), height(&b)), (Unit::PX(20.), Unit::PX(7.)));
+
+        layout.push(Child::Component(c.clone()));
+        assert_eq!(height(&b), Unit::Default);
+        assert_eq!(height(&c), Unit::PX(7.));
+
+        a.borrow_mut().set_attribute("data-state", "error");
+        assert_eq!(width(&a), Unit::PX(30.));
+    }
+}
@@ -1,8 +1,23 @@
+mod backend;
+mod node;
+mod query;
 mod rect;
-mod style_manager;
+#[cfg(test)]
+mod testing;
 
+pub mod animation;
 pub mod prelude;
+pub mod html;
 pub mod layout;
-pub use rect::Rect;
-pub use style_manager::STYLESHEET;
+pub mod raster;
+pub mod reload;
+pub mod theme;
+pub mod window;
+pub use backend::Backend;
+pub use node::{Dirty, Node, NodeId, State, TreePosition};
+pub use query::Handle;
+pub use rect::{Rect, RectF};
+pub use style::geometry::{Insets, InsetsF, Point, PointF, Size2D, Size2DF};
+pub use theme::{ThemeManager, THEME};
+pub use style;
 
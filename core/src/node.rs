@@ -0,0 +1,538 @@
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::{HashMap, HashSet},
+    mem::discriminant,
+    ops::{BitOr, BitOrAssign},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use style::{geometry::Point, Appearance, Dimensions, Media, Motion, Style, Stylesheet};
+
+use crate::{
+    animation::Animator,
+    layout::{LayoutCache, LayoutInput},
+    THEME,
+};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Unique identifier of a node for the lifetime of the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub usize);
+
+impl NodeId {
+    pub fn next() -> Self {
+        NodeId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Reasons a node needs to be laid out again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dirty(u8);
+
+impl Dirty {
+    pub const NONE: Dirty = Dirty(0);
+    /// The computed style changed.
+    pub const STYLE: Dirty = Dirty(1);
+    /// The content, and with it the measured size, changed.
+    pub const CONTENT: Dirty = Dirty(1 << 1);
+    /// A child was added or removed.
+    pub const CHILDREN: Dirty = Dirty(1 << 2);
+    pub const ALL: Dirty = Dirty(0b111);
+
+    pub fn contains(&self, other: Dirty) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for Dirty {
+    type Output = Dirty;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Dirty(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Dirty {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Whether an element of a layout, or of a layout nested in it, needs to be laid out again.
+///
+/// Every layout owns one and hands it to its children. Marking a node sets the flag of the
+/// layout it is in and of every layout above it, so checking a subtree doesn't walk it.
+#[derive(Debug, Default)]
+pub(crate) struct DirtyFlag {
+    dirty: AtomicBool,
+    /// The flag of the layout containing the container that owns this flag's layout.
+    parent: Mutex<Option<Arc<DirtyFlag>>>,
+}
+
+impl DirtyFlag {
+    pub fn is_set(&self) -> bool {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    /// Set the flag and the flags above it.
+    pub fn set(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+        if let Some(parent) = self.parent.lock().unwrap().as_ref() {
+            parent.set();
+        }
+    }
+
+    pub fn clear(&self) {
+        self.dirty.store(false, Ordering::Relaxed);
+    }
+
+    /// Link the flag to the one of the layout above, passing it on if it is set.
+    pub fn set_parent(&self, parent: &Arc<DirtyFlag>) {
+        let mut current = self.parent.lock().unwrap();
+        if !current
+            .as_ref()
+            .is_some_and(|current| Arc::ptr_eq(current, parent))
+        {
+            *current = Some(parent.clone());
+        }
+        if self.is_set() {
+            parent.set();
+        }
+    }
+}
+
+/// Interactive state of an element, matched by the `:hover`, `:focus`, `:active`, `:disabled`
+/// and `:checked` pseudo-classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct State(u8);
+
+impl State {
+    pub const NONE: State = State(0);
+    /// The pointer is over the element or one of its descendants.
+    pub const HOVER: State = State(1);
+    /// The element receives keyboard input.
+    pub const FOCUS: State = State(1 << 1);
+    /// The element, or one of its descendants, is being pressed.
+    pub const ACTIVE: State = State(1 << 2);
+    pub const DISABLED: State = State(1 << 3);
+    pub const CHECKED: State = State(1 << 4);
+
+    const PSEUDO_CLASSES: [(State, &'static str); 5] = [
+        (State::HOVER, ":hover"),
+        (State::FOCUS, ":focus"),
+        (State::ACTIVE, ":active"),
+        (State::DISABLED, ":disabled"),
+        (State::CHECKED, ":checked"),
+    ];
+
+    pub fn contains(&self, other: State) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The pseudo-classes the state matches, `:hover`.
+    pub fn pseudo_classes(&self) -> impl Iterator<Item = &'static str> + '_ {
+        State::PSEUDO_CLASSES
+            .iter()
+            .filter(|(state, _)| self.contains(*state))
+            .map(|(_, name)| *name)
+    }
+}
+
+impl BitOr for State {
+    type Output = State;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        State(self.0 | rhs.0)
+    }
+}
+
+/// Where an element sits among its siblings, matched by `:first-child`, `:last-child`,
+/// `:only-child`, `:nth-child()` and `:empty`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TreePosition {
+    /// Position among the siblings starting at 1, 0 for an element without a parent.
+    pub index: usize,
+    /// Number of children of the parent, the element included.
+    pub siblings: usize,
+    /// Whether the element has neither children nor content.
+    pub empty: bool,
+}
+
+impl TreePosition {
+    /// The pseudo-classes the position matches, `:first-child` and `:nth-child(1)`.
+    pub fn pseudo_classes(&self) -> Vec<String> {
+        let mut keys = Vec::new();
+        if self.index > 0 {
+            keys.push(format!(":nth-child({})", self.index));
+        }
+        if self.index == 1 {
+            keys.push(":first-child".to_string());
+        }
+        if self.index > 0 && self.index == self.siblings {
+            keys.push(":last-child".to_string());
+        }
+        if self.index > 0 && self.siblings == 1 {
+            keys.push(":only-child".to_string());
+        }
+        if self.empty {
+            keys.push(":empty".to_string());
+        }
+        keys
+    }
+}
+
+/// State shared by every element: its id, classes, inline styles, cached computed style,
+/// running animations and layout bookkeeping.
+#[derive(Debug)]
+pub struct Node {
+    node_id: NodeId,
+    pub id: String,
+    pub classes: HashSet<String>,
+    /// Attributes matched by attribute selectors, `[role="tab"]`.
+    pub attributes: HashMap<String, String>,
+    state: Cell<State>,
+    position: Cell<TreePosition>,
+    inline: Vec<Style>,
+    stylesheet: Option<Arc<Stylesheet>>,
+    scopes: RefCell<Vec<Arc<Stylesheet>>>,
+    ancestors: RefCell<Vec<HashSet<String>>>,
+    parent_style: Cell<Option<(Dimensions, Appearance)>>,
+    media: Cell<Media>,
+    computed: Cell<Option<(Dimensions, Appearance)>>,
+    animator: RefCell<Animator>,
+    /// The time of the current frame of the window.
+    time: Cell<Duration>,
+    dirty: Cell<Dirty>,
+    /// The flag of the layout the node is in.
+    flag: RefCell<Option<Arc<DirtyFlag>>>,
+    layout: Cell<Option<LayoutCache>>,
+    scroll: Cell<Point>,
+}
+
+impl Default for Node {
+    fn default() -> Self {
+        Node {
+            node_id: NodeId::next(),
+            id: String::new(),
+            classes: HashSet::new(),
+            attributes: HashMap::new(),
+            state: Cell::new(State::NONE),
+            position: Cell::new(TreePosition::default()),
+            inline: Vec::new(),
+            stylesheet: None,
+            scopes: RefCell::new(Vec::new()),
+            ancestors: RefCell::new(Vec::new()),
+            parent_style: Cell::new(None),
+            media: Cell::new(Media::default()),
+            computed: Cell::new(None),
+            animator: RefCell::new(Animator::default()),
+            time: Cell::new(Duration::ZERO),
+            dirty: Cell::new(Dirty::ALL),
+            flag: RefCell::new(None),
+            layout: Cell::new(None),
+            scroll: Cell::new(Point::default()),
+        }
+    }
+}
+
+impl Node {
+    pub fn new(id: String, classes: HashSet<String>) -> Self {
+        Node {
+            id,
+            classes,
+            ..Default::default()
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        self.node_id
+    }
+
+    /// How far the content of the node is scrolled horizontally and vertically.
+    pub fn scroll(&self) -> Point {
+        self.scroll.get()
+    }
+
+    pub fn set_scroll(&self, offset: Point) {
+        self.scroll.set(offset);
+    }
+
+    /// The interactive state of the element.
+    pub fn state(&self) -> State {
+        self.state.get()
+    }
+
+    /// Add or remove `state`, invalidating the computed style if it changed. Returns whether
+    /// it changed.
+    pub fn set_state(&self, state: State, on: bool) -> bool {
+        let old = self.state.get();
+        let new = match on {
+            true => State(old.0 | state.0),
+            false => State(old.0 & !state.0),
+        };
+        self.state.set(new);
+        if old != new {
+            self.invalidate();
+        }
+        old != new
+    }
+
+    /// Where the element sits among its siblings.
+    pub fn position(&self) -> TreePosition {
+        self.position.get()
+    }
+
+    /// Set the position among the siblings, invalidating the computed style if it changed.
+    /// Returns whether it changed.
+    pub fn set_position(&self, position: TreePosition) -> bool {
+        let changed = self.position.replace(position) != position;
+        if changed {
+            self.invalidate();
+        }
+        changed
+    }
+
+    /// Inline styles in the order they are applied.
+    pub fn inline(&self) -> &Vec<Style> {
+        &self.inline
+    }
+
+    /// Set an inline style replacing any previous value for the same property.
+    pub fn set_inline(&mut self, style: Style) {
+        self.inline
+            .retain(|inline| discriminant(inline) != discriminant(&style));
+        self.inline.push(style);
+    }
+
+    pub fn clear_inline(&mut self) {
+        self.inline.clear();
+    }
+
+    /// The stylesheet scoped to the element and its descendants.
+    pub fn stylesheet(&self) -> Option<&Arc<Stylesheet>> {
+        self.stylesheet.as_ref()
+    }
+
+    pub fn set_stylesheet(&mut self, stylesheet: Option<Arc<Stylesheet>>) {
+        self.stylesheet = stylesheet;
+        self.invalidate();
+    }
+
+    /// Scoped stylesheets of the ancestors, outermost first.
+    pub fn scopes(&self) -> Ref<'_, Vec<Arc<Stylesheet>>> {
+        self.scopes.borrow()
+    }
+
+    /// Set the scoped stylesheets inherited from the ancestors, invalidating the computed style
+    /// if they changed. Returns whether they changed.
+    pub fn set_scopes(&self, scopes: &[Arc<Stylesheet>]) -> bool {
+        let same = {
+            let current = self.scopes.borrow();
+            current.len() == scopes.len()
+                && current.iter().zip(scopes).all(|(a, b)| Arc::ptr_eq(a, b))
+        };
+        if !same {
+            *self.scopes.borrow_mut() = scopes.to_vec();
+            self.invalidate();
+        }
+        !same
+    }
+
+    /// The scoped stylesheets that apply to the children of the element, the inherited ones
+    /// followed by its own.
+    pub fn child_scopes(&self) -> Vec<Arc<Stylesheet>> {
+        let mut scopes = self.scopes.borrow().clone();
+        scopes.extend(self.stylesheet.clone());
+        scopes
+    }
+
+    /// Selector keys of the ancestors, outermost first, used to match descendant and child
+    /// selectors.
+    pub fn ancestors(&self) -> Ref<'_, Vec<HashSet<String>>> {
+        self.ancestors.borrow()
+    }
+
+    /// Set the selector keys of the ancestors, invalidating the computed style if they changed.
+    /// Returns whether they changed.
+    pub fn set_ancestors(&self, ancestors: &[HashSet<String>]) -> bool {
+        let same = *self.ancestors.borrow() == ancestors;
+        if !same {
+            *self.ancestors.borrow_mut() = ancestors.to_vec();
+            self.invalidate();
+        }
+        !same
+    }
+
+    /// The computed style of the parent element that inherited properties are taken from,
+    /// `None` for a window.
+    pub fn parent_style(&self) -> Option<(Dimensions, Appearance)> {
+        self.parent_style.get()
+    }
+
+    /// Set the computed style of the parent, invalidating the computed style if it changed.
+    /// Returns whether it changed.
+    pub fn set_parent_style(&self, style: Option<(Dimensions, Appearance)>) -> bool {
+        let changed = self.parent_style.replace(style) != style;
+        if changed {
+            self.invalidate();
+        }
+        changed
+    }
+
+    /// The media `@media` rules are evaluated against, the one of the window the node is in.
+    pub fn media(&self) -> Media {
+        self.media.get()
+    }
+
+    /// Set the media of the window the node is in. The computed style is invalidated only if an
+    /// `@media` rule of the theme or of a scoped stylesheet starts or stops matching. Returns
+    /// whether it was invalidated.
+    pub fn set_media(&self, media: Media) -> bool {
+        let old = self.media.replace(media);
+        if old == media {
+            return false;
+        }
+
+        let theme = THEME.current();
+        let scopes = self.scopes.borrow();
+        let changed = theme.media_changed(&old, &media)
+            || scopes
+                .iter()
+                .chain(self.stylesheet.as_ref())
+                .any(|stylesheet| stylesheet.media_changed(&old, &media));
+        if changed {
+            self.invalidate();
+        }
+        changed
+    }
+
+    pub fn computed(&self) -> Option<(Dimensions, Appearance)> {
+        self.computed.get()
+    }
+
+    pub fn set_computed(&self, styles: (Dimensions, Appearance)) {
+        self.computed.set(Some(styles));
+    }
+
+    /// The time of the current frame, see [`Clock`](crate::animation::Clock).
+    pub fn time(&self) -> Duration {
+        self.time.get()
+    }
+
+    /// Move to the frame at `now`, invalidating the computed style while a transition or
+    /// animation is running. Returns whether one is.
+    pub fn set_time(&self, now: Duration) -> bool {
+        self.time.set(now);
+        let running = self.is_animating();
+        if running {
+            self.invalidate();
+        }
+        running
+    }
+
+    /// Whether a transition or animation of the node is running.
+    pub fn is_animating(&self) -> bool {
+        self.animator.borrow().is_running()
+    }
+
+    /// The style to show for the style `target` from the cascade, with the transitions and
+    /// animations of `motion` applied at the current frame.
+    pub fn animate(
+        &self,
+        target: (Dimensions, Appearance),
+        motion: &Motion,
+    ) -> (Dimensions, Appearance) {
+        self.animator.borrow_mut().update(
+            target,
+            motion,
+            self.time.get(),
+            self.media.get().reduced_motion,
+        )
+    }
+
+    /// Drop the cached computed style so it is resolved again on the next lookup.
+    pub fn invalidate(&self) {
+        self.computed.set(None);
+        self.mark(Dirty::STYLE);
+    }
+
+    pub fn dirty(&self) -> Dirty {
+        self.dirty.get()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.get().is_empty()
+    }
+
+    /// Flag the node, and with it the layouts it is in, to be laid out again.
+    pub fn mark(&self, dirty: Dirty) {
+        self.dirty.set(self.dirty.get() | dirty);
+        if let Some(flag) = self.flag.borrow().as_ref() {
+            flag.set();
+        }
+    }
+
+    /// Move the node into the layout owning `flag`, or out of any with `None`.
+    pub(crate) fn set_flag(&self, flag: Option<&Arc<DirtyFlag>>) {
+        let mut current = self.flag.borrow_mut();
+        let same = match (current.as_ref(), flag) {
+            (Some(current), Some(flag)) => Arc::ptr_eq(current, flag),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            *current = flag.cloned();
+        }
+        if let (Some(flag), true) = (flag, self.is_dirty()) {
+            flag.set();
+        }
+    }
+
+    pub fn clean(&self) {
+        self.dirty.set(Dirty::NONE);
+    }
+
+    /// Clear the `dirty` flags, keeping the others.
+    pub fn unmark(&self, dirty: Dirty) {
+        self.dirty.set(Dirty(self.dirty.get().0 & !dirty.0));
+    }
+
+    /// The last layout result if the node is clean and was laid out with the same input.
+    pub fn cached_layout(&self, input: &LayoutInput) -> Option<LayoutCache> {
+        match self.layout.get() {
+            Some(cache) if !self.is_dirty() && cache.input == *input => Some(cache),
+            _ => None,
+        }
+    }
+
+    pub fn last_layout(&self) -> Option<LayoutCache> {
+        self.layout.get()
+    }
+
+    /// Store the layout result and clear the dirty flags.
+    pub fn set_layout(&self, cache: LayoutCache) {
+        self.layout.set(Some(cache));
+        self.dirty.set(Dirty::NONE);
+    }
+}
+
+/// Normalize a class name to its selector key, `error` -> `.error`.
+pub fn class_key(class: &str) -> String {
+    match class.starts_with('.') {
+        true => class.to_string(),
+        false => format!(".{}", class),
+    }
+}
@@ -1,35 +1,347 @@
-use std::{collections::HashSet, fmt};
+use std::{
+    any::Any,
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
 
-use super::layout::Layout;
-use crate::{Rect, STYLESHEET};
-use style::{Appearance, Dimensions};
+use super::layout::{Child, Layout};
+use crate::{
+    node::class_key, raster::Content, Dirty, Handle, Node, NodeId, Point, Rect, State, THEME,
+};
+use style::{Appearance, Attribute, Dimensions, Overflow, Style, Stylesheet};
 
 pub trait Renderable {
-    fn id(&self) -> &String;
+    fn node(&self) -> &Node;
+    fn node_mut(&mut self) -> &mut Node;
 
-    fn classes(&self) -> &HashSet<String>;
+    fn id(&self) -> &String {
+        &self.node().id
+    }
+
+    fn classes(&self) -> &HashSet<String> {
+        &self.node().classes
+    }
+
+    fn attributes(&self) -> &HashMap<String, String> {
+        &self.node().attributes
+    }
 
+    /// The rect of the element in device pixels.
     fn rect(&self) -> &Rect;
 
+    /// The natural size of the element in logical pixels.
     fn default_rect(&self) -> &Rect;
 
+    /// The keys used to match the element against selectors; its classes, id, attributes and
+    /// the pseudo-classes of its state and position.
+    fn keys(&self) -> HashSet<String> {
+        let mut keys = self.classes().clone();
+        if !self.id().is_empty() {
+            keys.insert(self.id().clone());
+        }
+        keys.extend(
+            self.attributes()
+                .iter()
+                .map(|(name, value)| Attribute::key(name, value)),
+        );
+        keys.extend(self.node().state().pseudo_classes().map(String::from));
+        keys.extend(self.node().position().pseudo_classes());
+        keys
+    }
+
+    /// The computed styles of the element, see [`style::cascade`]. The stylesheet of the theme
+    /// comes first, then the scoped stylesheets of the ancestors from the outermost in and the
+    /// element's own scoped stylesheet, with the inline styles on top. `@media` rules are
+    /// evaluated against the media of the element's window and selectors are matched against
+    /// the keys of the element's ancestors. Inherited properties come from the computed style
+    /// of the parent. Running transitions and animations are applied on top, see
+    /// [`animation`](crate::animation). The result is cached until the element is restyled.
     fn get_styles(&self) -> (Dimensions, Appearance) {
-        let mut styles = self.classes().clone();
-        styles.insert(self.id().clone());
-        // println!("{:?} {:?}", styles, STYLESHEET.0.read());
-        STYLESHEET.get().get_styles(styles)
+        if let Some(styles) = self.node().computed() {
+            return styles;
+        }
+
+        let theme = THEME.current();
+        let node = self.node();
+        let scopes = node.scopes();
+        let mut stylesheets: Vec<&Stylesheet> = vec![&theme];
+        stylesheets.extend(scopes.iter().chain(node.stylesheet()).map(|sheet| &**sheet));
+        let mut path = node.ancestors().clone();
+        path.push(self.keys());
+        let target = style::cascade(
+            &stylesheets,
+            &path,
+            node.inline(),
+            &node.media(),
+            node.parent_style().as_ref(),
+        );
+        let motion = style::motion(&stylesheets, &path, node.inline(), &node.media());
+        let (dimensions, appearance) = node.animate(target, &motion);
+
+        self.node().set_computed((dimensions, appearance));
+        (dimensions, appearance)
     }
 
+    /// Add a class, `error` or `.error`, restyling the element if it wasn't already present.
+    fn add_class(&mut self, class: &str) {
+        if self.node_mut().classes.insert(class_key(class)) {
+            self.restyle();
+        }
+    }
+
+    /// Remove a class, restyling the element if it was present.
+    fn remove_class(&mut self, class: &str) {
+        if self.node_mut().classes.remove(&class_key(class)) {
+            self.restyle();
+        }
+    }
+
+    /// Add the class if it is missing otherwise remove it. Returns whether the class is now present.
+    fn toggle_class(&mut self, class: &str) -> bool {
+        let present = self.classes().contains(&class_key(class));
+        match present {
+            true => self.remove_class(class),
+            false => self.add_class(class),
+        }
+        !present
+    }
+
+    /// Set an attribute, `set_attribute("role", "tab")`, restyling the element if it changed.
+    fn set_attribute(&mut self, name: &str, value: &str) {
+        let previous = self
+            .node_mut()
+            .attributes
+            .insert(name.to_string(), value.to_string());
+        if previous.as_deref() != Some(value) {
+            self.restyle();
+        }
+    }
+
+    /// Remove an attribute, restyling the element if it was present.
+    fn remove_attribute(&mut self, name: &str) {
+        if self.node_mut().attributes.remove(name).is_some() {
+            self.restyle();
+        }
+    }
+
+    /// Add or remove an interactive state, `set_state(State::DISABLED, true)`, restyling the
+    /// element if it changed. Hover, focus and active are usually tracked by the window.
+    fn set_state(&mut self, state: State, on: bool) {
+        if self.node().set_state(state, on) {
+            self.restyle();
+        }
+    }
+
+    /// Set an inline style override, `set_style("width", "120px")`. Inline styles take
+    /// priority over every stylesheet rule.
+    fn set_style(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let style = Style::parse(name, value)?;
+        self.node_mut().set_inline(style);
+        self.restyle();
+        Ok(())
+    }
+
+    /// Replace the stylesheet scoped to the element and its descendants. Its rules follow the
+    /// app stylesheet in the cascade and never match elements outside of the subtree.
+    fn set_stylesheet(&mut self, stylesheet: Option<Stylesheet>) {
+        self.node_mut().set_stylesheet(stylesheet.map(Arc::new));
+        self.restyle();
+    }
+
+    /// Remove all inline style overrides.
+    fn clear_styles(&mut self) {
+        self.node_mut().clear_inline();
+        self.restyle();
+    }
+
+    /// Invalidate the cached computed style and schedule a relayout and repaint.
+    ///
+    /// Containers should override this to also restyle their layout.
+    fn restyle(&mut self) {
+        self.node().invalidate();
+        self.request_layout();
+    }
+
+    /// Schedule a relayout and repaint of the element. Platforms hook into this to
+    /// queue the work with their event loop.
+    fn request_layout(&mut self) {}
+
+    /// Called when the scale factor of the window changes, e.g. when it is moved to a monitor
+    /// with a different DPI. Elements that measure their content should re-measure it here.
+    fn rescale(&mut self, _scale: f32) {
+        self.node().mark(Dirty::CONTENT);
+    }
+
+    /// What the element draws inside its padding, used when rendering without the platform.
+    fn content(&self) -> Content<'_> {
+        Content::None
+    }
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
     fn show(&mut self);
     fn hide(&mut self);
-    fn update(&mut self, rect: Rect) -> (i32, i32);
+    fn update(&mut self, rect: Rect) -> Point;
 }
 
 pub trait Container<Data, Error>: Renderable + fmt::Debug {
     fn layout(&mut self) -> &mut Layout<Data, Error>;
     fn init(&mut self) -> Result<(), Error>;
+
+    /// Find the elements under `point`, relative to the container's parent, returning the
+    /// node ids from the container down to the innermost element hit.
+    fn hit_test(&mut self, point: Point) -> Vec<NodeId> {
+        let rect = *self.rect();
+        let dimensions = self.get_styles().0;
+
+        let clip_x = matches!(dimensions.overflow_x, Overflow::Hidden | Overflow::Scroll);
+        let clip_y = matches!(dimensions.overflow_y, Overflow::Hidden | Overflow::Scroll);
+        if (clip_x && (point.x < rect.left || point.x >= rect.right))
+            || (clip_y && (point.y < rect.top || point.y >= rect.bottom))
+        {
+            return Vec::new();
+        }
+
+        let scroll = self.node().scroll();
+        let mut path = self.layout().hit_test(point - rect.origin() + scroll);
+
+        if !path.is_empty() || rect.contains(point) {
+            path.insert(0, self.node().node_id());
+        }
+        path
+    }
+
+    /// Add `state` to the container and the elements in `path`, node ids as returned by
+    /// [`hit_test`](Container::hit_test), and remove it from every other element. Returns
+    /// whether any element changed, the changed ones are restyled on the next update.
+    fn update_state(&mut self, state: State, path: &[NodeId]) -> bool {
+        let id = self.node().node_id();
+        let changed = self.node().set_state(state, path.contains(&id));
+        self.layout().update_state(state, path) || changed
+    }
+
+    /// Move the container and its descendants to the frame at `now`, restyling the elements
+    /// with running transitions and animations. Windows call this with the time of their
+    /// [`Clock`](crate::animation::Clock) before every update. Returns whether anything is
+    /// still animating.
+    fn animate(&mut self, now: Duration) -> bool {
+        let running = self.node().set_time(now);
+        self.layout().set_time(now) || running
+    }
+
+    /// Whether a transition or animation of the container or its descendants is running, so
+    /// the window has to keep drawing frames.
+    fn is_animating(&mut self) -> bool {
+        self.node().is_animating() || self.layout().is_animating()
+    }
+
+    /// Append a child and schedule a relayout.
+    fn add_child(&mut self, child: Child<Data, Error>) {
+        self.layout().push(child);
+        self.node().mark(Dirty::CHILDREN);
+        self.request_layout();
+    }
+
+    /// Insert a child at `index` and schedule a relayout.
+    fn insert_child(&mut self, index: usize, child: Child<Data, Error>) {
+        self.layout().insert(index, child);
+        self.node().mark(Dirty::CHILDREN);
+        self.request_layout();
+    }
+
+    /// Remove the child at `index`, hiding it, and schedule a relayout.
+    fn remove_child(&mut self, index: usize) -> Child<Data, Error> {
+        let child = self.layout().remove(index);
+        match &child {
+            Child::Component(component) => component.borrow_mut().hide(),
+            Child::Container(container) => container.borrow_mut().hide(),
+        }
+        self.node().mark(Dirty::CHILDREN);
+        self.request_layout();
+        child
+    }
+
+    /// Find the first element in the container with the given id, `#name`.
+    fn find_by_id(&mut self, id: &str) -> Option<Handle<Data, Error>> {
+        self.layout().find_by_id(id)
+    }
+
+    /// Find the first element in the container matching the selector, `.row text`. Errors if
+    /// the selector is invalid.
+    ///
+    /// The container itself takes part in matching but is never returned.
+    fn query_selector(&mut self, selector: &str) -> Result<Option<Handle<Data, Error>>, String> {
+        let root = vec![self.keys()];
+        Ok(self
+            .layout()
+            .query(root, selector, true)?
+            .into_iter()
+            .next())
+    }
+
+    /// Find every element in the container matching the selector in tree order. Errors if the
+    /// selector is invalid.
+    fn query_selector_all(&mut self, selector: &str) -> Result<Vec<Handle<Data, Error>>, String> {
+        let root = vec![self.keys()];
+        self.layout().query(root, selector, false)
+    }
 }
 
 pub trait Component<Data, Error>: Renderable + fmt::Debug {
     fn create(&mut self, data: Data) -> Result<(), Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Renderable;
+    use crate::{
+        testing::{node, Leaf},
+        Dirty, Rect,
+    };
+
+    fn leaf(id: &str) -> Leaf {
+        let leaf = Leaf {
+            node: node(id, &[]),
+            rect: Rect::default(),
+            default_rect: Rect::default(),
+            updates: 0,
+        };
+        leaf.node.clean();
+        leaf
+    }
+
+    #[test]
+    fn classes_restyle_only_when_they_change() {
+        let mut leaf = leaf("");
+        leaf.add_class("error");
+        assert!(leaf.classes().contains(".error"));
+        assert!(leaf.node.dirty().contains(Dirty::STYLE));
+
+        leaf.node.clean();
+        leaf.add_class(".error");
+        leaf.remove_class("missing");
+        assert!(!leaf.node.is_dirty());
+
+        leaf.remove_class(".error");
+        assert!(leaf.classes().is_empty());
+        assert!(leaf.node.is_dirty());
+    }
+
+    #[test]
+    fn toggle_class() {
+        let mut leaf = leaf("");
+        assert!(leaf.toggle_class("open"));
+        assert!(leaf.classes().contains(".open"));
+        assert!(!leaf.toggle_class(".open"));
+        assert!(!leaf.classes().contains(".open"));
+    }
+
+    #[test]
+    fn keys_skip_an_empty_id() {
+        assert!(!leaf("").keys().contains(""));
+        assert!(leaf("title").keys().contains("#title"));
+    }
+}
@@ -0,0 +1,261 @@
+//! Element lookup in a built layout.
+//!
+//! Elements are found by id or by selector and returned as a [`Handle`] which can be
+//! downcast to the concrete component type.
+
+use std::{
+    cell::{Ref, RefMut},
+    collections::HashSet,
+};
+
+use style::{Appearance, Dimensions, Selector};
+
+use crate::{
+    layout::{Child, Layout},
+    NodeId, Rect,
+};
+
+/// Called with the keys of the elements from the root to a visited element and the element.
+/// Returning true stops the walk.
+type Visitor<'a, Data, Error> = dyn FnMut(&[HashSet<String>], &Child<Data, Error>) -> bool + 'a;
+
+/// A reference to an element found in a layout.
+#[derive(Debug, Clone)]
+pub struct Handle<Data, Error>(Child<Data, Error>);
+
+impl<Data, Error> Handle<Data, Error> {
+    pub fn child(&self) -> &Child<Data, Error> {
+        &self.0
+    }
+
+    pub fn id(&self) -> String {
+        match &self.0 {
+            Child::Component(component) => component.borrow().id().clone(),
+            Child::Container(container) => container.borrow().id().clone(),
+        }
+    }
+
+    pub fn classes(&self) -> HashSet<String> {
+        match &self.0 {
+            Child::Component(component) => component.borrow().classes().clone(),
+            Child::Container(container) => container.borrow().classes().clone(),
+        }
+    }
+
+    pub fn node_id(&self) -> NodeId {
+        match &self.0 {
+            Child::Component(component) => component.borrow().node().node_id(),
+            Child::Container(container) => container.borrow().node().node_id(),
+        }
+    }
+
+    /// The rect the element was last laid out at.
+    pub fn rect(&self) -> Rect {
+        match &self.0 {
+            Child::Component(component) => *component.borrow().rect(),
+            Child::Container(container) => *container.borrow().rect(),
+        }
+    }
+
+    /// The computed styles of the element.
+    pub fn styles(&self) -> (Dimensions, Appearance) {
+        match &self.0 {
+            Child::Component(component) => component.borrow().get_styles(),
+            Child::Container(container) => container.borrow().get_styles(),
+        }
+    }
+
+    /// Check if the element is of type `T`.
+    pub fn is<T: 'static>(&self) -> bool {
+        match &self.0 {
+            Child::Component(component) => component.borrow().as_any().is::<T>(),
+            Child::Container(container) => container.borrow().as_any().is::<T>(),
+        }
+    }
+
+    /// Borrow the element as `T`. `None` if the element is a different type.
+    ///
+    /// # Panics
+    /// If the element is currently mutably borrowed.
+    pub fn downcast_ref<T: 'static>(&self) -> Option<Ref<'_, T>> {
+        match &self.0 {
+            Child::Component(component) => {
+                Ref::filter_map(component.borrow(), |c| c.as_any().downcast_ref::<T>()).ok()
+            }
+            Child::Container(container) => {
+                Ref::filter_map(container.borrow(), |c| c.as_any().downcast_ref::<T>()).ok()
+            }
+        }
+    }
+
+    /// Mutably borrow the element as `T`. `None` if the element is a different type.
+    ///
+    /// # Panics
+    /// If the element is currently borrowed.
+    pub fn downcast_mut<T: 'static>(&self) -> Option<RefMut<'_, T>> {
+        match &self.0 {
+            Child::Component(component) => RefMut::filter_map(component.borrow_mut(), |c| {
+                c.as_any_mut().downcast_mut::<T>()
+            })
+            .ok(),
+            Child::Container(container) => RefMut::filter_map(container.borrow_mut(), |c| {
+                c.as_any_mut().downcast_mut::<T>()
+            })
+            .ok(),
+        }
+    }
+}
+
+impl<Data, Error> Layout<Data, Error> {
+    /// Find the first element with the given id. The leading `#` is optional.
+    pub fn find_by_id(&mut self, id: &str) -> Option<Handle<Data, Error>> {
+        let id = match id.starts_with('#') {
+            true => id.to_string(),
+            false => format!("#{}", id),
+        };
+
+        let mut found = None;
+        self.walk(&mut Vec::new(), &mut |_, child| {
+            let matches = match child {
+                Child::Component(component) => *component.borrow().id() == id,
+                Child::Container(container) => *container.borrow().id() == id,
+            };
+            if matches {
+                found = Some(Handle(child.clone()));
+            }
+            matches
+        });
+        found
+    }
+
+    /// Find the first element matching the selector. Errors if the selector is invalid.
+    pub fn query_selector(
+        &mut self,
+        selector: &str,
+    ) -> Result<Option<Handle<Data, Error>>, String> {
+        Ok(self.query(Vec::new(), selector, true)?.into_iter().next())
+    }
+
+    /// Find all elements matching the selector in tree order. Errors if the selector is
+    /// invalid.
+    pub fn query_selector_all(
+        &mut self,
+        selector: &str,
+    ) -> Result<Vec<Handle<Data, Error>>, String> {
+        self.query(Vec::new(), selector, false)
+    }
+
+    /// Match the selector against every element in the layout. `root` holds the keys of the
+    /// elements containing the layout.
+    pub(crate) fn query(
+        &mut self,
+        mut root: Vec<HashSet<String>>,
+        selector: &str,
+        first: bool,
+    ) -> Result<Vec<Handle<Data, Error>>, String> {
+        let selectors = Selector::parse_list(selector)?;
+
+        let mut found = Vec::new();
+        self.walk(&mut root, &mut |path, child| {
+            if selectors.iter().any(|selector| selector.matches(path)) {
+                found.push(Handle(child.clone()));
+                return first;
+            }
+            false
+        });
+        Ok(found)
+    }
+
+    /// Visit every element depth first. `path` is the keys of the elements from the root to the
+    /// visited element. Stops when `visit` returns true.
+    fn walk(
+        &mut self,
+        path: &mut Vec<HashSet<String>>,
+        visit: &mut Visitor<'_, Data, Error>,
+    ) -> bool {
+        for child in self.children.iter() {
+            let stop = match child {
+                Child::Component(component) => {
+                    path.push(component.borrow().keys());
+                    visit(path, child)
+                }
+                Child::Container(container) => {
+                    path.push(container.borrow().keys());
+                    visit(path, child) || container.borrow_mut().layout().walk(path, visit)
+                }
+            };
+            path.pop();
+
+            if stop {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        layout::{Child, Layout},
+        testing::{node, shared_block, shared_leaf, Leaf},
+        NodeId,
+    };
+
+    fn layout() -> (Layout<(), String>, [NodeId; 3]) {
+        let (title, title_leaf) = shared_leaf(node("title", &["label"]), [0, 0, 100, 20], &[]);
+        let (error, error_leaf) = shared_leaf(node("", &["label", "error"]), [0, 20, 100, 40], &[]);
+        let (row, row_block) = shared_block(
+            node("row", &["row"]),
+            [0, 0, 100, 40],
+            &[],
+            vec![Child::Component(title_leaf), Child::Component(error_leaf)],
+        );
+        (
+            Layout::from(vec![Child::Container(row_block)]),
+            [row, title, error],
+        )
+    }
+
+    fn ids(handles: Vec<crate::Handle<(), String>>) -> Vec<NodeId> {
+        handles.iter().map(|handle| handle.node_id()).collect()
+    }
+
+    #[test]
+    fn find_by_id() {
+        let (mut layout, [row, title, _]) = layout();
+        assert_eq!(layout.find_by_id("title").unwrap().node_id(), title);
+        assert_eq!(layout.find_by_id("#row").unwrap().node_id(), row);
+        assert!(layout.find_by_id("missing").is_none());
+        assert!(layout.find_by_id("title").unwrap().is::<Leaf>());
+    }
+
+    #[test]
+    fn query_by_class_and_compound() {
+        let (mut layout, [row, title, error]) = layout();
+        assert_eq!(
+            ids(layout.query_selector_all(".label").unwrap()),
+            vec![title, error]
+        );
+        assert_eq!(
+            ids(layout.query_selector_all(".label.error").unwrap()),
+            vec![error]
+        );
+        assert_eq!(
+            ids(layout.query_selector_all(".row > #title, .error").unwrap()),
+            vec![title, error]
+        );
+        assert_eq!(
+            layout.query_selector(".row").unwrap().unwrap().node_id(),
+            row
+        );
+        assert!(layout.query_selector(".missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn invalid_selector_is_an_error() {
+        let (mut layout, _) = layout();
+        assert!(layout.query_selector(".label >").is_err());
+        assert!(layout.query_selector_all("..label").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{fs, io, path::Path};
+
+use style::color::Color;
+
+use super::{
+    font::{glyph, GLYPH_COLUMNS, GLYPH_ROWS},
+    measure_text, png, TEXT_SCALE,
+};
+use crate::{Insets, Point, Rect, Size2D};
+
+/// An 8 bit RGBA image stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// A fully transparent image.
+    pub fn new(width: u32, height: u32) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Wrap existing RGBA pixels. Fails if there aren't exactly `width * height` pixels.
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self, String> {
+        let expected = width as usize * height as usize * 4;
+        match pixels.len() == expected {
+            true => Ok(Image {
+                width,
+                height,
+                pixels,
+            }),
+            false => Err(format!(
+                "Expected {} bytes for a {}x{} image, got {}",
+                expected,
+                width,
+                height,
+                pixels.len()
+            )),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn size(&self) -> Size2D {
+        Size2D::new(self.width as i32, self.height as i32)
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The RGBA value of a pixel. Transparent black outside of the image.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        if x >= self.width || y >= self.height {
+            return [0; 4];
+        }
+        let i = self.index(x, y);
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        if x < self.width && y < self.height {
+            let i = self.index(x, y);
+            self.pixels[i..i + 4].copy_from_slice(&rgba);
+        }
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    fn bounds(&self) -> Rect {
+        Rect::from([self.width as i32, self.height as i32])
+    }
+
+    /// Blend `rgba` over a pixel.
+    fn blend(&mut self, x: i32, y: i32, rgba: [u8; 4]) {
+        let i = self.index(x as u32, y as u32);
+        let alpha = rgba[3] as u32;
+        if alpha == 255 {
+            self.pixels[i..i + 4].copy_from_slice(&rgba);
+            return;
+        }
+
+        let inverse = 255 - alpha;
+        for (channel, source) in self.pixels[i..i + 3].iter_mut().zip(rgba) {
+            *channel = ((source as u32 * alpha + *channel as u32 * inverse + 127) / 255) as u8;
+        }
+        self.pixels[i + 3] = (alpha + (self.pixels[i + 3] as u32 * inverse + 127) / 255) as u8;
+    }
+
+    /// Fill the whole image, replacing what was there.
+    pub fn fill(&mut self, color: Color) {
+        let rgba = rgba(color);
+        for pixel in self.pixels.chunks_mut(4) {
+            pixel.copy_from_slice(&rgba);
+        }
+    }
+
+    /// Blend a color over the part of `rect` that is inside `clip`.
+    pub fn fill_rect(&mut self, rect: Rect, clip: Rect, color: Color) {
+        let rgba = rgba(color);
+        if let Some(area) = self.clip(rect, clip) {
+            for y in area.top..area.bottom {
+                for x in area.left..area.right {
+                    self.blend(x, y, rgba);
+                }
+            }
+        }
+    }
+
+    /// Draw the edges of `rect` with the given widths.
+    pub fn stroke_rect(&mut self, rect: Rect, widths: Insets, clip: Rect, color: Color) {
+        let inner = rect.deflate(&widths);
+        self.fill_rect(
+            Rect::new(rect.left, rect.top, rect.right, inner.top),
+            clip,
+            color,
+        );
+        self.fill_rect(
+            Rect::new(rect.left, inner.bottom, rect.right, rect.bottom),
+            clip,
+            color,
+        );
+        self.fill_rect(
+            Rect::new(rect.left, inner.top, inner.left, inner.bottom),
+            clip,
+            color,
+        );
+        self.fill_rect(
+            Rect::new(inner.right, inner.top, rect.right, inner.bottom),
+            clip,
+            color,
+        );
+    }
+
+    /// Stretch `image` over `rect` using the nearest pixel.
+    pub fn draw_image(&mut self, image: &Image, rect: Rect, clip: Rect) {
+        if image.width == 0 || image.height == 0 || rect.is_empty() {
+            return;
+        }
+
+        if let Some(area) = self.clip(rect, clip) {
+            for y in area.top..area.bottom {
+                let sy = (y - rect.top) as i64 * image.height as i64 / rect.height() as i64;
+                for x in area.left..area.right {
+                    let sx = (x - rect.left) as i64 * image.width as i64 / rect.width() as i64;
+                    self.blend(x, y, image.pixel(sx as u32, sy as u32));
+                }
+            }
+        }
+    }
+
+    /// Draw a single line of text centered in `rect` with the built in font.
+    pub fn draw_text(&mut self, text: &str, rect: Rect, clip: Rect, color: Color) {
+        let rgba = rgba(color);
+        let clip = match self.clip(rect, clip) {
+            Some(clip) => clip,
+            None => return,
+        };
+
+        let size = measure_text(text);
+        let origin = Point::new(
+            rect.left + (rect.width() - size.width) / 2,
+            rect.top + (rect.height() - size.height) / 2,
+        );
+
+        let advance = (GLYPH_COLUMNS as i32 + 1) * TEXT_SCALE;
+        for (i, c) in text.chars().enumerate() {
+            let left = origin.x + i as i32 * advance;
+            for (column, bits) in glyph(c).iter().enumerate() {
+                for row in 0..GLYPH_ROWS {
+                    if bits >> row & 1 == 1 {
+                        let dot = Rect::from_origin(
+                            Point::new(
+                                left + column as i32 * TEXT_SCALE,
+                                origin.y + row as i32 * TEXT_SCALE,
+                            ),
+                            Size2D::new(TEXT_SCALE, TEXT_SCALE),
+                        );
+                        if let Some(dot) = dot.intersect(&clip) {
+                            for y in dot.top..dot.bottom {
+                                for x in dot.left..dot.right {
+                                    self.blend(x, y, rgba);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn clip(&self, rect: Rect, clip: Rect) -> Option<Rect> {
+        rect.intersect(&clip)?.intersect(&self.bounds())
+    }
+
+    /// Encode the image as a PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self.width, self.height, &self.pixels)
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_png())
+    }
+
+    /// Decode an 8 bit, non interlaced PNG file.
+    pub fn from_png(png: &[u8]) -> Result<Self, String> {
+        let (width, height, pixels) = png::decode(png)?;
+        Image::from_rgba(width, height, pixels)
+    }
+
+    pub fn open_png<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let png =
+            fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Image::from_png(&png)
+    }
+}
+
+fn rgba(color: Color) -> [u8; 4] {
+    [
+        color.red,
+        color.green,
+        color.blue,
+        (color.alpha.clamp(0., 1.) * 255.).round() as u8,
+    ]
+}
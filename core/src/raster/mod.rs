@@ -0,0 +1,180 @@
+//! CPU rendering of a laid out window to an RGBA [`Image`].
+//!
+//! The renderer paints the same computed styles and layout rects the native backends use:
+//! background colors, borders, text and images. Text is drawn with a small built in bitmap
+//! font so the output is identical on every machine.
+
+mod font;
+mod image;
+mod inflate;
+mod png;
+
+use std::borrow::Cow;
+
+use style::{color::Color, Appearance, Dimensions, Overflow, Position, Visibility};
+
+use crate::{
+    layout::{Child, Layout},
+    prelude::Container,
+    Point, Rect, Size2D,
+};
+
+pub use image::Image;
+
+/// Each pixel of a glyph is drawn as a square of this size.
+pub const TEXT_SCALE: i32 = 2;
+/// Horizontal advance of each character of text.
+pub const CHAR_WIDTH: i32 = (font::GLYPH_COLUMNS as i32 + 1) * TEXT_SCALE;
+/// Height of a line of text.
+pub const LINE_HEIGHT: i32 = font::GLYPH_ROWS as i32 * TEXT_SCALE;
+
+/// What an element draws inside its padding.
+#[derive(Debug, Clone)]
+pub enum Content<'a> {
+    None,
+    Text(Cow<'a, str>),
+    Image(&'a Image),
+}
+
+/// The size a single line of text takes up when rendered.
+pub fn measure_text(text: &str) -> Size2D {
+    Size2D::new(text.chars().count() as i32 * CHAR_WIDTH, LINE_HEIGHT)
+}
+
+/// Render a window that has been laid out.
+///
+/// The window is painted white, matching the native default, before its own background.
+/// Children are painted from the bottom of the stacking order up and containers with
+/// `overflow: hidden` or `overflow: scroll` clip their content.
+pub fn render<Data, Error, W>(window: &mut W) -> Image
+where
+    W: Container<Data, Error> + ?Sized,
+{
+    let size = window.rect().size();
+    let mut image = Image::new(size.width.max(0) as u32, size.height.max(0) as u32);
+    image.fill(Color::new(255, 255, 255, 1.));
+
+    let bounds = Rect::from([size.width, size.height]);
+    let (dimensions, appearance) = window.get_styles();
+    paint(
+        &mut image,
+        bounds,
+        bounds,
+        &dimensions,
+        &appearance,
+        &Content::None,
+    );
+
+    let scroll = window.node().scroll();
+    paint_layout(
+        &mut image,
+        window.layout(),
+        Point::default() - scroll,
+        bounds,
+    );
+    image
+}
+
+fn paint_layout<Data, Error>(
+    image: &mut Image,
+    layout: &mut Layout<Data, Error>,
+    offset: Point,
+    clip: Rect,
+) {
+    let mut order: Vec<(i32, bool, usize)> = layout
+        .children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| {
+            let dimensions = match child {
+                Child::Component(component) => component.borrow().get_styles().0,
+                Child::Container(container) => container.borrow().get_styles().0,
+            };
+            (
+                dimensions.z_index.unwrap_or(0),
+                dimensions.position == Position::Absolute,
+                index,
+            )
+        })
+        .collect();
+    order.sort();
+
+    for (_, _, index) in order {
+        match &layout.children[index] {
+            Child::Component(component) => {
+                let component = component.borrow();
+                let rect = component.rect().translate(offset.x, offset.y);
+                let (dimensions, appearance) = component.get_styles();
+                paint(
+                    image,
+                    rect,
+                    clip,
+                    &dimensions,
+                    &appearance,
+                    &component.content(),
+                );
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                let rect = container.rect().translate(offset.x, offset.y);
+                let (dimensions, appearance) = container.get_styles();
+                paint(
+                    image,
+                    rect,
+                    clip,
+                    &dimensions,
+                    &appearance,
+                    &container.content(),
+                );
+
+                let mut inner = clip;
+                if matches!(dimensions.overflow_x, Overflow::Hidden | Overflow::Scroll) {
+                    inner.left = inner.left.max(rect.left);
+                    inner.right = inner.right.min(rect.right);
+                }
+                if matches!(dimensions.overflow_y, Overflow::Hidden | Overflow::Scroll) {
+                    inner.top = inner.top.max(rect.top);
+                    inner.bottom = inner.bottom.min(rect.bottom);
+                }
+
+                if !inner.is_empty() {
+                    let offset = rect.origin() - container.node().scroll();
+                    paint_layout(image, container.layout(), offset, inner);
+                }
+            }
+        }
+    }
+}
+
+/// Paint the background, border and content of a single element.
+fn paint(
+    image: &mut Image,
+    rect: Rect,
+    clip: Rect,
+    dimensions: &Dimensions,
+    appearance: &Appearance,
+    content: &Content,
+) {
+    if appearance.visibility == Visibility::Hidden {
+        return;
+    }
+    let color = appearance.color.unwrap_or(Color::new(0, 0, 0, 1.));
+
+    if let Some(background) = appearance.background_color {
+        image.fill_rect(rect, clip, background);
+    }
+
+    let border = dimensions.border.calc(rect.width(), rect.height());
+    if border != Default::default() {
+        image.stroke_rect(rect, border, clip, appearance.border_color.unwrap_or(color));
+    }
+
+    let inner = rect
+        .deflate(&border)
+        .deflate(&dimensions.padding.calc(rect.width(), rect.height()));
+    match content {
+        Content::None => (),
+        Content::Text(text) => image.draw_text(text, inner, clip, color),
+        Content::Image(source) => image.draw_image(source, inner, clip),
+    }
+}
@@ -0,0 +1,240 @@
+//! Minimal PNG encoding and decoding of RGBA images.
+//!
+//! Image data is stored in uncompressed deflate blocks which keeps the encoder small and the
+//! output byte for byte deterministic. Decoding accepts any non interlaced 8 bit grayscale,
+//! RGB or RGBA file so snapshots touched by other tools can still be read.
+
+use super::inflate;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+/// Largest payload of a stored deflate block.
+const MAX_STORED: usize = 0xFFFF;
+
+/// Encode 8 bit RGBA pixels, row by row from the top left, as a PNG file.
+pub fn encode(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend(width.to_be_bytes());
+    ihdr.extend(height.to_be_bytes());
+    // Bit depth 8, color type RGBA, default compression, filter and no interlacing
+    ihdr.extend([8, 6, 0, 0, 0]);
+
+    // Every row starts with its filter type, always none
+    let stride = width as usize * 4;
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    for row in pixels.chunks(stride.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut png = Vec::from(SIGNATURE);
+    chunk(&mut png, b"IHDR", &ihdr);
+    chunk(&mut png, b"IDAT", &zlib(&raw));
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Decode a PNG file into its width, height and 8 bit RGBA pixels.
+pub fn decode(png: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    if png.len() < 8 || png[..8] != SIGNATURE {
+        return Err("Not a PNG file".to_string());
+    }
+
+    let mut header = None;
+    let mut data = Vec::new();
+    let mut rest = &png[8..];
+    while rest.len() >= 12 {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if rest.len() < len + 12 {
+            return Err("Truncated PNG chunk".to_string());
+        }
+        let kind = &rest[4..8];
+        let body = &rest[8..8 + len];
+        match kind {
+            b"IHDR" if len == 13 => header = Some(body),
+            b"IDAT" => data.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        rest = &rest[len + 12..];
+    }
+
+    let header = header.ok_or("PNG is missing its header")?;
+    let width = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    let height = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+    let (depth, color, interlace) = (header[8], header[9], header[12]);
+
+    let channels = match color {
+        0 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return Err(format!("Unsupported PNG color type {}", color)),
+    };
+    if depth != 8 {
+        return Err(format!("Unsupported PNG bit depth {}", depth));
+    }
+    if interlace != 0 {
+        return Err("Interlaced PNGs are not supported".to_string());
+    }
+
+    let raw = inflate::zlib(&data)?;
+    let stride = width as usize * channels;
+    if raw.len() < (stride + 1) * height as usize {
+        return Err("PNG image data is too short".to_string());
+    }
+
+    let mut previous = vec![0u8; stride];
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in raw.chunks(stride + 1).take(height as usize) {
+        let mut current = row[1..].to_vec();
+        unfilter(row[0], &mut current, &previous, channels)?;
+
+        for pixel in current.chunks(channels) {
+            pixels.extend(match pixel {
+                [v] => [*v, *v, *v, 255],
+                [v, a] => [*v, *v, *v, *a],
+                [r, g, b] => [*r, *g, *b, 255],
+                [r, g, b, a] => [*r, *g, *b, *a],
+                _ => unreachable!(),
+            });
+        }
+        previous = current;
+    }
+
+    Ok((width, height, pixels))
+}
+
+/// Reverse the filter of a single row given the already decoded row above it.
+fn unfilter(filter: u8, row: &mut [u8], previous: &[u8], bpp: usize) -> Result<(), String> {
+    for i in 0..row.len() {
+        let left = match i >= bpp {
+            true => row[i - bpp],
+            false => 0,
+        };
+        let up = previous[i];
+        let corner = match i >= bpp {
+            true => previous[i - bpp],
+            false => 0,
+        };
+
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((left as u16 + up as u16) / 2) as u8,
+            4 => paeth(left, up, corner),
+            _ => return Err(format!("Unknown PNG filter type {}", filter)),
+        };
+        row[i] = row[i].wrapping_add(predicted);
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(MAX_STORED).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_layout() {
+        let png = encode(1, 1, &[255, 0, 0, 255]);
+
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        // IEND is always the same 12 bytes, including its checksum
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn decode_round_trip() {
+        let pixels = (0..6 * 4 * 4).map(|i| (i * 7) as u8).collect::<Vec<_>>();
+        assert_eq!(decode(&encode(6, 4, &pixels)), Ok((6, 4, pixels)));
+    }
+
+    #[test]
+    fn decode_huffman_blocks() {
+        // 16x16 images of 4x4 tiles compressed by zlib with fixed and with dynamic Huffman codes
+        let fixed = include_bytes!("fixtures/fixed.png");
+        let dynamic = include_bytes!("fixtures/dynamic.png");
+        // The block type follows the final block bit in the first byte after the zlib header
+        let block_type = |png: &[u8]| (png[43] >> 1) & 3;
+        assert_eq!((block_type(fixed), block_type(dynamic)), (1, 2));
+
+        let pixels = (0..16u8)
+            .flat_map(|y| (0..16u8).flat_map(move |x| [x / 4 * 64, y / 4 * 64, 128, 255]))
+            .collect::<Vec<_>>();
+        assert_eq!(decode(fixed), Ok((16, 16, pixels.clone())));
+        assert_eq!(decode(dynamic), Ok((16, 16, pixels)));
+    }
+}
@@ -1,13 +1,19 @@
-#[derive(Debug, Clone, Copy)]
-pub struct Rect {
-    pub left: i32,
-    pub top: i32,
-    pub right: i32,
-    pub bottom: i32,
+use style::geometry::{Insets, Point, Scalar, Size2D};
+
+/// An axis aligned rectangle. The right and bottom edges are exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect<T = i32> {
+    pub left: T,
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
 }
 
-impl Rect {
-    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+/// A rect with sub-pixel precision.
+pub type RectF = Rect<f32>;
+
+impl<T: Scalar> Rect<T> {
+    pub fn new(left: T, top: T, right: T, bottom: T) -> Self {
         Rect {
             left,
             top,
@@ -16,48 +22,166 @@ impl Rect {
         }
     }
 
-    pub fn width(&self) -> i32 {
+    /// Create a rect from its top left corner and size.
+    pub fn from_origin(origin: Point<T>, size: Size2D<T>) -> Self {
+        Rect {
+            left: origin.x,
+            top: origin.y,
+            right: origin.x + size.width,
+            bottom: origin.y + size.height,
+        }
+    }
+
+    pub fn width(&self) -> T {
         self.right - self.left
     }
 
-    pub fn height(&self) -> i32 {
+    pub fn height(&self) -> T {
         self.bottom - self.top
     }
 
-    pub fn shift(&self, amount: &(i32, i32, i32, i32)) -> Rect {
-        // top right bottom left
+    pub fn origin(&self) -> Point<T> {
+        Point::new(self.left, self.top)
+    }
+
+    pub fn size(&self) -> Size2D<T> {
+        Size2D::new(self.width(), self.height())
+    }
+
+    pub fn center(&self) -> Point<T> {
+        Point::new(
+            self.left + self.width().half(),
+            self.top + self.height().half(),
+        )
+    }
+
+    /// A rect is empty when it has no area.
+    pub fn is_empty(&self) -> bool {
+        self.right <= self.left || self.bottom <= self.top
+    }
+
+    pub fn contains(&self, point: Point<T>) -> bool {
+        point.x >= self.left && point.x < self.right && point.y >= self.top && point.y < self.bottom
+    }
+
+    /// The overlapping area of both rects, `None` if they don't overlap.
+    pub fn intersect(&self, other: &Rect<T>) -> Option<Rect<T>> {
+        let rect = Rect {
+            left: self.left.larger(other.left),
+            top: self.top.larger(other.top),
+            right: self.right.smaller(other.right),
+            bottom: self.bottom.smaller(other.bottom),
+        };
+
+        match rect.is_empty() {
+            true => None,
+            false => Some(rect),
+        }
+    }
+
+    /// The smallest rect containing both rects. Empty rects are ignored.
+    pub fn union(&self, other: &Rect<T>) -> Rect<T> {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+
         Rect {
-            top: self.top + amount.0,
-            right: self.right - amount.1,
-            bottom: self.bottom - amount.2,
-            left: self.left + amount.3,
+            left: self.left.smaller(other.left),
+            top: self.top.smaller(other.top),
+            right: self.right.larger(other.right),
+            bottom: self.bottom.larger(other.bottom),
         }
     }
 
-    pub fn translate(&self, x: i32, y: i32) -> Rect {
+    /// Grow the rect outward by the insets.
+    pub fn inflate(&self, insets: &Insets<T>) -> Rect<T> {
+        Rect {
+            top: self.top - insets.top,
+            right: self.right + insets.right,
+            bottom: self.bottom + insets.bottom,
+            left: self.left - insets.left,
+        }
+    }
+
+    /// Shrink the rect inward by the insets.
+    pub fn deflate(&self, insets: &Insets<T>) -> Rect<T> {
+        Rect {
+            top: self.top + insets.top,
+            right: self.right - insets.right,
+            bottom: self.bottom - insets.bottom,
+            left: self.left + insets.left,
+        }
+    }
+
+    pub fn translate(&self, x: T, y: T) -> Rect<T> {
         Rect {
             top: self.top + y,
             left: self.left + x,
             bottom: self.bottom + y,
-            right: self.right + x
+            right: self.right + x,
         }
     }
 
-    pub fn translate_x(&self, x: i32) -> Rect {
+    pub fn translate_x(&self, x: T) -> Rect<T> {
         Rect {
             top: self.top,
             left: self.left + x,
             bottom: self.bottom,
-            right: self.right + x
+            right: self.right + x,
         }
     }
 
-    pub fn translate_y(&self, y: i32) -> Rect {
+    pub fn translate_y(&self, y: T) -> Rect<T> {
         Rect {
             top: self.top + y,
             left: self.left,
             bottom: self.bottom + y,
-            right: self.right
+            right: self.right,
+        }
+    }
+}
+
+impl From<Rect> for RectF {
+    fn from(value: Rect) -> Self {
+        Rect {
+            left: value.left as f32,
+            top: value.top as f32,
+            right: value.right as f32,
+            bottom: value.bottom as f32,
+        }
+    }
+}
+
+impl Rect {
+    /// Shrink the rect by `(top, right, bottom, left)`.
+    #[deprecated(note = "use `Rect::deflate` with `Insets`")]
+    pub fn shift(&self, amount: &(i32, i32, i32, i32)) -> Rect {
+        self.deflate(&Insets::new(amount.0, amount.1, amount.2, amount.3))
+    }
+
+    /// Multiply every edge by `factor`, rounding to whole pixels. Converts logical pixels
+    /// to device pixels and back with the inverse factor.
+    pub fn scale(&self, factor: f32) -> Rect {
+        Rect {
+            left: (self.left as f32 * factor).round() as i32,
+            top: (self.top as f32 * factor).round() as i32,
+            right: (self.right as f32 * factor).round() as i32,
+            bottom: (self.bottom as f32 * factor).round() as i32,
+        }
+    }
+}
+
+impl RectF {
+    /// Round each edge to the nearest device pixel.
+    pub fn round(&self) -> Rect {
+        Rect {
+            left: self.left.round() as i32,
+            top: self.top.round() as i32,
+            right: self.right.round() as i32,
+            bottom: self.bottom.round() as i32,
         }
     }
 }
@@ -106,3 +230,76 @@ impl From<[i32; 4]> for Rect {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use style::geometry::{Insets, Point, Scalar};
+
+    use super::{Rect, RectF};
+
+    #[test]
+    fn contains_excludes_right_and_bottom() {
+        let rect = Rect::new(0, 0, 10, 10);
+        assert!(rect.contains(Point::new(0, 0)));
+        assert!(rect.contains(Point::new(9, 9)));
+        assert!(!rect.contains(Point::new(10, 5)));
+        assert!(!rect.contains(Point::new(5, -1)));
+        assert!(!Rect::new(5, 5, 5, 5).contains(Point::new(5, 5)));
+    }
+
+    #[test]
+    fn intersect() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert_eq!(a.intersect(&Rect::new(5, -5, 15, 5)), Some(Rect::new(5, 0, 10, 5)));
+        assert_eq!(a.intersect(&Rect::new(-20, -20, -10, -10)), None);
+        // Touching edges don't overlap
+        assert_eq!(a.intersect(&Rect::new(10, 0, 20, 10)), None);
+        assert_eq!(a.intersect(&Rect::new(2, 2, 2, 8)), None);
+    }
+
+    #[test]
+    fn union_ignores_empty_rects() {
+        let a = Rect::new(0, 0, 10, 10);
+        assert_eq!(a.union(&Rect::new(-5, 5, 3, 20)), Rect::new(-5, 0, 10, 20));
+        assert_eq!(a.union(&Rect::new(50, 50, 50, 60)), a);
+        assert_eq!(Rect::new(8, 8, 4, 4).union(&a), a);
+    }
+
+    #[test]
+    fn inflate_and_deflate() {
+        let rect = Rect::new(10, 10, 50, 30);
+        let insets = Insets::new(1, 2, 3, 4);
+        assert_eq!(rect.inflate(&insets), Rect::new(6, 9, 52, 33));
+        assert_eq!(rect.deflate(&insets), Rect::new(14, 11, 48, 27));
+        assert_eq!(rect.inflate(&insets).deflate(&insets), rect);
+        // Deflating past the size leaves an empty, inverted rect
+        assert!(Rect::new(0, 0, 4, 4).deflate(&Insets::uniform(3)).is_empty());
+        #[allow(deprecated)]
+        let shifted = rect.shift(&(1, 2, 3, 4));
+        assert_eq!(shifted, rect.deflate(&insets));
+    }
+
+    #[test]
+    fn center() {
+        assert_eq!(Rect::new(0, 0, 10, 20).center(), Point::new(5, 10));
+        assert_eq!(Rect::new(-10, -10, 0, 1).center(), Point::new(-5, -5));
+        assert_eq!(RectF::new(0., 0., 3., 1.).center(), Point::new(1.5, 0.5));
+    }
+
+    #[test]
+    fn is_empty() {
+        assert!(!Rect::new(0, 0, 1, 1).is_empty());
+        assert!(Rect::default().is_empty());
+        assert!(Rect::new(0, 0, 10, 0).is_empty());
+        assert!(Rect::new(10, 10, 0, 20).is_empty());
+        assert!(!Rect::new(-20, -20, -10, -10).is_empty());
+    }
+
+    #[test]
+    fn scalar_names_dont_shadow_ord() {
+        let rect = Rect::new(3, 4, 5, 6);
+        assert_eq!(rect.left.max(rect.top), 4);
+        assert_eq!(rect.left.smaller(rect.top), 3);
+        assert_eq!(1.5f32.larger(0.5), 1.5);
+    }
+}
@@ -0,0 +1,171 @@
+//! Reloading stylesheets from disk while the app runs, for development.
+//!
+//! A [`StyleWatcher`] polls the modification time of its files and the files they import. When
+//! one of them changes all of them are parsed again, in the order they were added, and the
+//! result replaces the app stylesheet of the [`THEME`]. If any file fails to parse the previous
+//! stylesheet stays in place.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use style::Stylesheet;
+
+use crate::THEME;
+
+/// How often the files are checked for changes while the app runs.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+struct Watched {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Watches stylesheet files and swaps them in as the app stylesheet when they change.
+#[derive(Debug, Default)]
+pub struct StyleWatcher {
+    files: Vec<Watched>,
+    /// Files imported by the watched ones when they were last loaded.
+    imports: Vec<Watched>,
+    last_check: Option<Instant>,
+}
+
+impl StyleWatcher {
+    pub fn new() -> Self {
+        StyleWatcher::default()
+    }
+
+    /// Watch another file. Files added later override rules of earlier ones. The file is loaded
+    /// on the next check.
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) {
+        self.files.push(Watched {
+            path: path.as_ref().to_path_buf(),
+            modified: None,
+        });
+    }
+
+    pub fn paths(&self) -> Vec<&Path> {
+        self.files.iter().map(|file| file.path.as_path()).collect()
+    }
+
+    /// Whether the files were last checked at least [`POLL_INTERVAL`] ago.
+    pub fn is_due(&self) -> bool {
+        match self.last_check {
+            Some(last) => last.elapsed() >= POLL_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Reload the stylesheet if any of the files changed since the last check. Returns whether
+    /// the app stylesheet was replaced, the windows then have to be restyled.
+    ///
+    /// Errors are prefixed with the file and line they occurred on.
+    pub fn check(&mut self) -> Result<bool, Vec<String>> {
+        match self.load_changes()? {
+            Some(stylesheet) => {
+                THEME.set_style(stylesheet);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Like [`check`](Self::check), but return the reloaded stylesheet instead of replacing
+    /// the app stylesheet with it. `None` if none of the files changed.
+    pub fn load_changes(&mut self) -> Result<Option<Stylesheet>, Vec<String>> {
+        // Everything is loaded on the first check, even files that don't exist yet
+        let mut changed = self.last_check.is_none();
+        self.last_check = Some(Instant::now());
+
+        for file in self.files.iter_mut().chain(self.imports.iter_mut()) {
+            let modified = modified(&file.path);
+            if modified != file.modified {
+                changed = true;
+            }
+            file.modified = modified;
+        }
+        if !changed {
+            return Ok(None);
+        }
+
+        let mut stylesheet = Stylesheet::default();
+        let mut errors = Vec::new();
+        for file in self.files.iter() {
+            let (sheet, file_errors) = Stylesheet::load(&file.path);
+            errors.extend(file_errors.iter().map(ToString::to_string));
+            stylesheet.extend(sheet);
+        }
+
+        // Keep watching the imports even if they failed to load so fixing them reloads
+        self.imports = stylesheet
+            .sources()
+            .iter()
+            .filter(|path| self.files.iter().all(|file| file.path != **path))
+            .map(|path| Watched {
+                path: path.clone(),
+                modified: modified(path),
+            })
+            .collect();
+
+        match errors.is_empty() {
+            true => Ok(Some(stylesheet)),
+            false => Err(errors),
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::theme::ThemeManager;
+
+    #[test]
+    fn reload_keeps_previous_sheet_on_error() {
+        let path = std::env::temp_dir().join(format!("reload-{}.css", std::process::id()));
+        fs::write(&path, ".reloaded { width: 10px; }").unwrap();
+
+        // A theme of its own keeps the app stylesheet of the other tests untouched
+        let theme = ThemeManager::new();
+        let mut watcher = StyleWatcher::new();
+        watcher.watch(&path);
+        theme.set_style(watcher.load_changes().unwrap().unwrap());
+        assert!(watcher.load_changes().unwrap().is_none());
+
+        let width = || {
+            theme
+                .current()
+                .get_styles(HashSet::from([".reloaded".to_string()]))
+                .0
+                .width
+        };
+        assert_eq!(width(), style::Unit::PX(10.));
+
+        // Make sure the modification time differs on file systems with coarse timestamps
+        let later = SystemTime::now() + Duration::from_secs(2);
+        fs::write(&path, ".reloaded {\n  width: 10px;\n  color: nope(;\n}").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        let errors = watcher.load_changes().unwrap_err();
+        assert!(
+            errors[0].starts_with(&format!("{}:3:", path.display())),
+            "{:?}",
+            errors
+        );
+        assert_eq!(width(), style::Unit::PX(10.));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
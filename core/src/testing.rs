@@ -0,0 +1,156 @@
+//! Elements without a platform for the unit tests of the crate.
+
+// Layouts hold their children in an `Arc` even though elements are not `Sync`
+#![allow(clippy::arc_with_non_send_sync)]
+
+use std::{any::Any, cell::RefCell, sync::Arc};
+
+use crate::{
+    layout::{Child, Layout},
+    node::class_key,
+    prelude::{Component, Container, Renderable},
+    Node, NodeId, Point, Rect,
+};
+
+/// A component that takes the rect it is given.
+#[derive(Debug)]
+pub struct Leaf {
+    pub node: Node,
+    pub rect: Rect,
+    /// The natural size in logical pixels, the rect it was created with.
+    pub default_rect: Rect,
+    /// Number of times it was updated.
+    pub updates: usize,
+}
+
+/// A container that takes the rect it is given.
+#[derive(Debug)]
+pub struct Block {
+    pub node: Node,
+    pub rect: Rect,
+    pub default_rect: Rect,
+    pub layout: Layout<(), String>,
+    /// Number of times it was updated.
+    pub updates: usize,
+}
+
+macro_rules! renderable {
+    ($type: ty) => {
+        impl Renderable for $type {
+            fn node(&self) -> &Node {
+                &self.node
+            }
+
+            fn node_mut(&mut self) -> &mut Node {
+                &mut self.node
+            }
+
+            fn rect(&self) -> &Rect {
+                &self.rect
+            }
+
+            fn default_rect(&self) -> &Rect {
+                &self.default_rect
+            }
+
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn Any {
+                self
+            }
+
+            fn show(&mut self) {}
+            fn hide(&mut self) {}
+
+            fn update(&mut self, rect: Rect) -> Point {
+                self.updates += 1;
+                self.rect = rect;
+                Point::new(rect.right, rect.bottom)
+            }
+        }
+    };
+}
+
+renderable!(Leaf);
+renderable!(Block);
+
+impl Component<(), String> for Leaf {
+    fn create(&mut self, _data: ()) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl Container<(), String> for Block {
+    fn layout(&mut self) -> &mut Layout<(), String> {
+        &mut self.layout
+    }
+
+    fn init(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A node with the id, `#name`, and classes, `.name`.
+pub fn node(id: &str, classes: &[&str]) -> Node {
+    let id = match id.is_empty() || id.starts_with('#') {
+        true => id.to_string(),
+        false => format!("#{}", id),
+    };
+    Node::new(id, classes.iter().map(|class| class_key(class)).collect())
+}
+
+pub fn leaf(rect: [i32; 4], styles: &[(&str, &str)]) -> (NodeId, Child<(), String>) {
+    let (id, leaf) = shared_leaf(node("", &[]), rect, styles);
+    (id, Child::Component(leaf))
+}
+
+/// A leaf that stays accessible after it is added to a layout.
+pub fn shared_leaf(
+    node: Node,
+    rect: [i32; 4],
+    styles: &[(&str, &str)],
+) -> (NodeId, Arc<RefCell<Leaf>>) {
+    let mut leaf = Leaf {
+        node,
+        rect: Rect::from(rect),
+        default_rect: Rect::from(rect),
+        updates: 0,
+    };
+    for (name, value) in styles {
+        leaf.set_style(name, value).unwrap();
+    }
+    (leaf.node.node_id(), Arc::new(RefCell::new(leaf)))
+}
+
+pub fn block(
+    rect: [i32; 4],
+    styles: &[(&str, &str)],
+    scroll: Point,
+    children: Vec<Child<(), String>>,
+) -> (NodeId, Child<(), String>) {
+    let (id, block) = shared_block(node("", &[]), rect, styles, children);
+    block.borrow().node.set_scroll(scroll);
+    (id, Child::Container(block))
+}
+
+/// A block that stays accessible after it is added to a layout.
+pub fn shared_block(
+    node: Node,
+    rect: [i32; 4],
+    styles: &[(&str, &str)],
+    children: Vec<Child<(), String>>,
+) -> (NodeId, Arc<RefCell<Block>>) {
+    let mut block = Block {
+        node,
+        rect: Rect::from(rect),
+        default_rect: Rect::from(rect),
+        layout: Layout::from(children),
+        updates: 0,
+    };
+    for (name, value) in styles {
+        block.set_style(name, value).unwrap();
+    }
+    (block.node.node_id(), Arc::new(RefCell::new(block)))
+}
@@ -0,0 +1,231 @@
+//! Named themes layered over a built in default theme and the app's stylesheet.
+//!
+//! The stylesheet elements resolve their styles from is the built in [`DEFAULT_THEME`], the
+//! app stylesheet and the active theme merged in that order, so later sheets override earlier
+//! ones. The default theme has the user agent origin, any app or theme rule overrides it
+//! regardless of its selector. The merged sheet is rebuilt only when one of the sheets changes
+//! and handed out as a shared snapshot, lookups never wait on each other.
+//!
+//! The manager also holds the preferences of the user `@media` rules can query, the
+//! preferred color scheme and whether motion should be reduced.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::Lazy;
+use style::{ColorScheme, Media, Origin, Stylesheet};
+
+use crate::Rect;
+
+/// Rules every app starts with. They restate the defaults of the renderers so app and theme
+/// sheets have something to override.
+pub const DEFAULT_THEME: &str = "
+window {
+    position: relative;
+    overflow: auto;
+    font-style: normal;
+}
+text {
+    position: relative;
+}
+";
+
+/// The theme manager of the app.
+pub static THEME: Lazy<ThemeManager> = Lazy::new(ThemeManager::new);
+
+#[derive(Debug, Default)]
+struct Sheets {
+    default: Stylesheet,
+    app: Stylesheet,
+    themes: HashMap<String, Stylesheet>,
+    active: Option<String>,
+}
+
+impl Sheets {
+    fn merge(&self) -> Stylesheet {
+        let mut merged = Stylesheet::default();
+        merged.extend(self.default.clone());
+        merged.extend(self.app.clone());
+        if let Some(theme) = self.active.as_ref().and_then(|name| self.themes.get(name)) {
+            merged.extend(theme.clone());
+        }
+        merged
+    }
+}
+
+/// Holds the stylesheets of the app and the named themes that can be switched between.
+///
+/// Changing the sheets doesn't restyle anything by itself, `App::set_theme` and friends
+/// restyle the open windows afterwards.
+#[derive(Debug)]
+pub struct ThemeManager {
+    sheets: RwLock<Sheets>,
+    current: RwLock<Arc<Stylesheet>>,
+    /// The media features that don't depend on the window.
+    preferences: RwLock<Media>,
+}
+
+impl Default for ThemeManager {
+    fn default() -> Self {
+        ThemeManager::new()
+    }
+}
+
+impl ThemeManager {
+    pub fn new() -> Self {
+        ThemeManager::with_default(Stylesheet::parse(DEFAULT_THEME).0)
+    }
+
+    /// A manager with a different built in theme. Its rules are given the user agent origin.
+    pub fn with_default(default: Stylesheet) -> Self {
+        let sheets = Sheets {
+            default: default.with_origin(Origin::UserAgent),
+            ..Default::default()
+        };
+        let current = Arc::new(sheets.merge());
+        ThemeManager {
+            sheets: RwLock::new(sheets),
+            current: RwLock::new(current),
+            preferences: RwLock::new(Media::default()),
+        }
+    }
+
+    /// The merged stylesheet elements are styled with.
+    pub fn current(&self) -> Arc<Stylesheet> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Replace the app stylesheet.
+    pub fn set_style(&self, stylesheet: Stylesheet) {
+        self.update(|sheets| sheets.app = stylesheet);
+    }
+
+    /// Add a stylesheet after the app stylesheet, its rules win over earlier ones.
+    pub fn add_style(&self, stylesheet: Stylesheet) {
+        self.update(|sheets| sheets.app.extend(stylesheet));
+    }
+
+    /// Add or replace a named theme, e.g. `dark`. Themes are applied on top of the app
+    /// stylesheet while they are active.
+    pub fn add_theme(&self, name: &str, stylesheet: Stylesheet) {
+        self.update(|sheets| {
+            sheets.themes.insert(name.to_string(), stylesheet);
+        });
+    }
+
+    pub fn remove_theme(&self, name: &str) -> Option<Stylesheet> {
+        let mut removed = None;
+        self.update(|sheets| {
+            removed = sheets.themes.remove(name);
+            if sheets.active.as_deref() == Some(name) {
+                sheets.active = None;
+            }
+        });
+        removed
+    }
+
+    /// Activate a theme, or only use the default and app stylesheets with `None`. Returns
+    /// `false` if there is no theme with the name.
+    pub fn set_theme(&self, name: Option<&str>) -> bool {
+        if let Some(name) = name {
+            if !self.sheets.read().unwrap().themes.contains_key(name) {
+                return false;
+            }
+        }
+        self.update(|sheets| sheets.active = name.map(String::from));
+        true
+    }
+
+    /// Name of the active theme.
+    pub fn theme(&self) -> Option<String> {
+        self.sheets.read().unwrap().active.clone()
+    }
+
+    /// Names of the themes, sorted.
+    pub fn themes(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.sheets.read().unwrap().themes.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn color_scheme(&self) -> ColorScheme {
+        self.preferences.read().unwrap().color_scheme
+    }
+
+    /// Set the color scheme matched by `prefers-color-scheme`.
+    pub fn set_color_scheme(&self, color_scheme: ColorScheme) {
+        self.preferences.write().unwrap().color_scheme = color_scheme;
+    }
+
+    pub fn reduced_motion(&self) -> bool {
+        self.preferences.read().unwrap().reduced_motion
+    }
+
+    /// Set whether `prefers-reduced-motion: reduce` matches.
+    pub fn set_reduced_motion(&self, reduced_motion: bool) {
+        self.preferences.write().unwrap().reduced_motion = reduced_motion;
+    }
+
+    /// The media of a window with a client area of `rect`, in device pixels, and the given
+    /// scale factor.
+    pub fn media(&self, rect: &Rect, scale: f32) -> Media {
+        Media {
+            width: rect.width() as f32 / scale,
+            height: rect.height() as f32 / scale,
+            resolution: scale,
+            ..*self.preferences.read().unwrap()
+        }
+    }
+
+    fn update<F: FnOnce(&mut Sheets)>(&self, change: F) {
+        let mut sheets = self.sheets.write().unwrap();
+        change(&mut sheets);
+        *self.current.write().unwrap() = Arc::new(sheets.merge());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use style::{color::Color, Position};
+
+    use super::*;
+
+    #[test]
+    fn themes_layer_over_the_app_stylesheet() {
+        let theme = ThemeManager::new();
+        theme.set_style(Stylesheet::try_parse("text { color: red; position: absolute; }").unwrap());
+        theme.add_theme(
+            "dark",
+            Stylesheet::try_parse("text { color: white; }").unwrap(),
+        );
+
+        let text = || {
+            theme
+                .current()
+                .get_styles(HashSet::from(["text".to_string()]))
+        };
+        assert_eq!(text().1.color, Some(Color::new(255, 0, 0, 1.)));
+
+        let before = theme.current();
+        assert!(theme.set_theme(Some("dark")));
+        assert!(!theme.set_theme(Some("missing")));
+        assert_eq!(theme.theme().as_deref(), Some("dark"));
+        assert_eq!(text().1.color, Some(Color::new(255, 255, 255, 1.)));
+        assert_eq!(text().0.position, Position::Absolute);
+        // Snapshots handed out earlier are left untouched
+        assert_eq!(
+            before
+                .get_styles(HashSet::from(["text".to_string()]))
+                .1
+                .color,
+            Some(Color::new(255, 0, 0, 1.))
+        );
+
+        theme.set_theme(None);
+        assert_eq!(text().1.color, Some(Color::new(255, 0, 0, 1.)));
+    }
+}
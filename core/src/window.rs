@@ -0,0 +1,134 @@
+//! The options every platform's window builder shares.
+//!
+//! A platform's `WindowBuilder` keeps a [`WindowOptions`] in its `options` field and expands
+//! [`window_builder!`](crate::window_builder) in its `impl` block for the common builder
+//! methods, adding only the options of the platform itself.
+
+use std::{collections::HashSet, sync::Arc};
+
+use style::Stylesheet;
+
+use crate::{layout::Layout, Node, Rect};
+
+pub struct WindowOptions<Data, Error> {
+    pub index: u32,
+    pub id: String,
+    pub classes: HashSet<String>,
+    pub title: String,
+    /// The requested size of the window in logical pixels.
+    pub rect: Rect,
+    pub layout: Layout<Data, Error>,
+    /// Styles scoped to the window and its children.
+    pub stylesheet: Option<Stylesheet>,
+}
+
+impl<Data, Error> Default for WindowOptions<Data, Error> {
+    fn default() -> Self {
+        WindowOptions {
+            index: 0,
+            id: String::new(),
+            classes: HashSet::from(["window".to_string()]),
+            title: String::new(),
+            rect: Rect::from([400, 300]),
+            layout: Layout::new(),
+            stylesheet: None,
+        }
+    }
+}
+
+/// What a window is built from once the options are resolved.
+pub struct WindowParts<Data, Error> {
+    pub index: u32,
+    pub title: String,
+    pub rect: Rect,
+    pub node: Node,
+    pub layout: Layout<Data, Error>,
+}
+
+impl<Data, Error> WindowOptions<Data, Error> {
+    /// Create the node of the window with its scoped stylesheet and pass the stylesheet on to
+    /// the children.
+    pub fn into_parts(self) -> WindowParts<Data, Error> {
+        let mut node = Node::new(self.id, self.classes);
+        node.set_stylesheet(self.stylesheet.map(Arc::new));
+        let mut layout = self.layout;
+        layout.set_scopes(&node.child_scopes());
+
+        WindowParts {
+            index: self.index,
+            title: self.title,
+            rect: self.rect,
+            node,
+            layout,
+        }
+    }
+}
+
+/// The builder methods shared by every platform, expanded inside the `impl` block of a builder
+/// with an `options: WindowOptions<Data, Error>` field.
+///
+/// ```ignore
+/// impl WindowBuilder {
+///     native_core::window_builder!((), Error);
+/// }
+/// ```
+#[macro_export]
+macro_rules! window_builder {
+    ($data: ty, $error: ty) => {
+        pub fn index(mut self, index: u32) -> Self {
+            self.options.index = index;
+            self
+        }
+
+        /// The size of the window in logical pixels.
+        pub fn size(mut self, width: i32, height: i32) -> Self {
+            self.options.rect.right = width;
+            self.options.rect.bottom = height;
+            self
+        }
+
+        pub fn title(mut self, title: &str) -> Self {
+            self.options.title = String::from(title);
+            self
+        }
+
+        pub fn classes(mut self, classes: Vec<&str>) -> Self {
+            self.options
+                .classes
+                .extend(classes.iter().map(|c| match c.starts_with(".") {
+                    true => c.to_string(),
+                    false => format!(".{}", c),
+                }));
+            self
+        }
+
+        pub fn class(mut self, class: &str) -> Self {
+            self.options.classes.insert(match class.starts_with("#") {
+                true => class.to_string(),
+                false => format!(".{}", class),
+            });
+            self
+        }
+
+        pub fn layout(mut self, layout: $crate::layout::Layout<$data, $error>) -> Self {
+            self.options.layout = layout;
+            self
+        }
+
+        pub fn id(mut self, id: &str) -> Self {
+            self.options.id = match id.starts_with("#") {
+                true => id.to_string(),
+                false if id.trim() != "" => format!("#{}", id),
+                _ => String::new(),
+            };
+            self
+        }
+
+        /// Styles scoped to the window and its children, following the app stylesheet in the
+        /// cascade. Selectors in it never match elements of other windows.
+        pub fn stylesheet(mut self, stylesheet: $crate::style::Stylesheet) -> Self {
+            self.options.stylesheet = Some(stylesheet);
+            self
+        }
+    };
+}
@@ -33,6 +33,6 @@ fn main() {
         //         padding: 10px 10px 5px 50%;
         //     }
         // })
-        .stylesheet(Stylesheet::file("styles.css"))
+        .stylesheet(Stylesheet::file("styles.css").0)
         .open();
 }
@@ -8,8 +8,18 @@ pub fn styles(input: TokenStream) -> TokenStream {
     let src = input.to_string().replace(" ", "");
     let src = &src[..];
 
+    // The styles are part of the program so invalid rules fail the build
+    if let Err(errors) = style::Stylesheet::try_parse(src) {
+        let message = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<String>>()
+            .join("\n");
+        return quote!(compile_error!(#message)).into();
+    }
+
     quote!(style::Stylesheet::parse(
         #src
-    ))
+    ).0)
     .into()
 }
@@ -0,0 +1,57 @@
+use std::{cell::RefCell, sync::Arc};
+
+use native_core::{layout::Child, Backend};
+
+use crate::{
+    core::error::Error,
+    ui::{component::Text, Window},
+};
+
+/// A backend that renders nothing. Elements are laid out in memory and input is injected with
+/// [`Window::send`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Headless;
+
+impl Backend for Headless {
+    type Data = ();
+    type Error = Error;
+    type Window = Window;
+
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<(), Error> {
+        let mut text =
+            Text::builder(text).classes(classes.iter().map(|c| format!(".{}", c)).collect());
+
+        if let Some(id) = id {
+            text = text.id(format!("#{}", id).as_str());
+        }
+
+        // Layouts hold their children in an `Arc` even though elements are not `Sync`
+        #[allow(clippy::arc_with_non_send_sync)]
+        Child::Component(Arc::new(RefCell::new(text.build())))
+    }
+
+    fn create_window(window: &mut Window, index: u32) -> Result<(), Error> {
+        window.set_index(index);
+        window.build()
+    }
+
+    /// There is no input besides the injected events so this returns once the events that are
+    /// already queued have been processed.
+    fn run(mut windows: Vec<Window>) -> Result<(), Error> {
+        Headless::tick(&mut windows)?;
+        Ok(())
+    }
+
+    fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+        let mut open = false;
+        for window in windows.iter_mut() {
+            open |= window.tick()?;
+        }
+        Ok(open)
+    }
+
+    fn message(title: &str, message: &str, _buttons: &str, _icon: &str) -> u8 {
+        eprintln!("[{}] {}", title, message);
+        2
+    }
+}
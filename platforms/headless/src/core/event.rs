@@ -0,0 +1,32 @@
+use native_core::Point;
+
+/// Input injected into a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// A click at a point relative to the top left of the window. Focuses the innermost
+    /// element hit.
+    Click(Point),
+    /// The pointer moved to a point, updating the hovered elements.
+    MouseMove(Point),
+    /// A button was pressed at a point, the elements hit become active until it is released.
+    MouseDown(Point),
+    MouseUp,
+    KeyPress(Key),
+    /// Resize the window to a new width and height.
+    Resize(i32, i32),
+    /// Ask the window to close. The quit hook can keep it open.
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+}
@@ -0,0 +1,116 @@
+use std::{any::Any, collections::HashSet};
+
+use crate::core::error::Error;
+
+use native_core::{
+    prelude::{Component, Renderable},
+    raster::{self, Content},
+    Node, Point, Rect,
+};
+
+pub struct ImageBuilder {
+    pub image: raster::Image,
+    pub rect: Rect,
+    pub id: String,
+    pub classes: HashSet<String>,
+}
+
+impl ImageBuilder {
+    pub fn new(image: raster::Image) -> ImageBuilder {
+        ImageBuilder {
+            image,
+            rect: Rect::default(),
+            id: String::new(),
+            classes: HashSet::from(["image".to_string()]),
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = String::from(id);
+        self
+    }
+
+    pub fn classes(mut self, classes: Vec<String>) -> Self {
+        self.classes.extend(classes);
+        self
+    }
+
+    pub fn class(mut self, class: &str) -> Self {
+        self.classes.insert(String::from(class));
+        self
+    }
+
+    pub fn build(self) -> Image {
+        let size = self.image.size();
+        Image {
+            image: self.image,
+            rect: self.rect,
+            default_rect: Rect::from([size.width, size.height]),
+            node: Node::new(self.id, self.classes),
+        }
+    }
+}
+
+/// Displays an RGBA image stretched over its content area.
+#[derive(Debug)]
+pub struct Image {
+    pub image: raster::Image,
+    pub rect: Rect,
+    pub default_rect: Rect,
+    pub node: Node,
+}
+
+impl Image {
+    pub fn new(image: raster::Image) -> Self {
+        ImageBuilder::new(image).build()
+    }
+
+    pub fn builder(image: raster::Image) -> ImageBuilder {
+        ImageBuilder::new(image)
+    }
+}
+
+impl Component<(), Error> for Image {
+    fn create(&mut self, _data: ()) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl Renderable for Image {
+    fn show(&mut self) {}
+
+    fn hide(&mut self) {}
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn update(&mut self, rect: Rect) -> Point {
+        self.rect = rect;
+        Point::new(self.rect.right, self.rect.bottom)
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.default_rect
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn content(&self) -> Content<'_> {
+        Content::Image(&self.image)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
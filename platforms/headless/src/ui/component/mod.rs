@@ -0,0 +1,5 @@
+mod image;
+mod text;
+
+pub use image::Image;
+pub use text::{Text, CHAR_WIDTH, LINE_HEIGHT};
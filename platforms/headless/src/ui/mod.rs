@@ -0,0 +1,4 @@
+mod window;
+
+pub mod component;
+pub use window::{Window, WindowBuilder};
@@ -0,0 +1,605 @@
+use std::{any::Any, collections::VecDeque};
+
+use crate::{
+    core::{
+        error::Error,
+        event::{Event, Key},
+    },
+    Headless,
+};
+
+use native_core::{
+    animation::{Clock, SystemClock},
+    html,
+    layout::{Child, Layout},
+    prelude::{Container, Renderable},
+    raster::{self, Image},
+    window::WindowOptions,
+    Backend, Dirty, Node, NodeId, Point, Rect, State, THEME,
+};
+
+#[derive(Default, Debug)]
+struct Hooks {
+    quit: Option<fn(&mut Window) -> bool>,
+    click: Option<fn(&mut Window, &[NodeId])>,
+    key: Option<fn(&mut Window, Key)>,
+}
+
+#[derive(Debug)]
+pub struct Window {
+    index: u32,
+    initialized: bool,
+    open: bool,
+    pub title: String,
+    node: Node,
+    pub rect: Rect,
+    pub layout: Layout<(), Error>,
+    events: VecDeque<Event>,
+    last_hit: Vec<NodeId>,
+    hooks: Hooks,
+    clock: Box<dyn Clock>,
+    viewport: Option<Rect>,
+}
+
+impl Window {
+    /// Size the window from its styles in logical pixels and convert the size to device pixels.
+    /// Percentages are resolved against the viewport, or the requested size without one.
+    fn apply_styles(&mut self) {
+        let dimensions = self.get_styles().0;
+        let requested = Rect::from([
+            match self.rect.width() {
+                0 => 400,
+                width => width,
+            },
+            match self.rect.height() {
+                0 => 300,
+                height => height,
+            },
+        ]);
+        let viewport = self.viewport.unwrap_or(requested);
+        let width = dimensions.width.as_i32(viewport.width(), requested.width());
+        let height = dimensions
+            .height
+            .as_i32(viewport.height(), requested.height());
+        self.rect = Rect::from([width, height]).scale(self.layout.scale());
+    }
+}
+
+pub struct WindowBuilder {
+    options: WindowOptions<(), Error>,
+    hooks: Hooks,
+    clock: Box<dyn Clock>,
+    scale: f32,
+    viewport: Option<Rect>,
+}
+
+impl Default for WindowBuilder {
+    fn default() -> Self {
+        WindowBuilder {
+            options: WindowOptions::default(),
+            hooks: Hooks::default(),
+            clock: Box::new(SystemClock::default()),
+            scale: 1.,
+            viewport: None,
+        }
+    }
+}
+
+impl WindowBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    native_core::window_builder!((), Error);
+
+    /// Called when the window is asked to close. Return `false` to keep it open.
+    pub fn on_quit(mut self, callback: fn(&mut Window) -> bool) -> Self {
+        self.hooks.quit = Some(callback);
+        self
+    }
+
+    /// Called with the node ids under the cursor, outermost first, when the window is clicked.
+    pub fn on_click(mut self, callback: fn(&mut Window, &[NodeId])) -> Self {
+        self.hooks.click = Some(callback);
+        self
+    }
+
+    pub fn on_key(mut self, callback: fn(&mut Window, Key)) -> Self {
+        self.hooks.key = Some(callback);
+        self
+    }
+
+    /// The clock transitions and animations are played against, a
+    /// [`ManualClock`](native_core::animation::ManualClock) to step through frames.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Device pixels per logical pixel, as if the window was on a high DPI monitor.
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// The size of the screen the window is on in logical pixels, the size percentages of the
+    /// window are resolved against. Defaults to the requested size of the window.
+    pub fn viewport(mut self, width: i32, height: i32) -> Self {
+        self.viewport = Some(Rect::from([width, height]));
+        self
+    }
+
+    pub fn build(self) -> Window {
+        let mut parts = self.options.into_parts();
+        parts.layout.set_scale(self.scale);
+
+        Window {
+            index: parts.index,
+            initialized: false,
+            open: false,
+            title: parts.title,
+            node: parts.node,
+            rect: parts.rect,
+            layout: parts.layout,
+            events: VecDeque::new(),
+            last_hit: Vec::new(),
+            hooks: self.hooks,
+            clock: self.clock,
+            viewport: self.viewport,
+        }
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        WindowBuilder::default().build()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Change the scale factor the window's content is laid out with.
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale != self.layout.scale() {
+            self.layout.set_scale(scale);
+            self.restyle();
+        }
+    }
+
+    /// The node ids hit by the last click, outermost first.
+    pub fn last_hit(&self) -> &[NodeId] {
+        &self.last_hit
+    }
+
+    /// Create the children of the window, lay them out and open the window.
+    pub fn build(&mut self) -> Result<(), Error> {
+        if !self.initialized {
+            self.apply_styles();
+            self.init()?;
+            self.update(self.rect);
+            self.node.clean();
+            self.initialized = true;
+            self.open = true;
+        }
+        Ok(())
+    }
+
+    /// Render the window with the software renderer.
+    pub fn render(&mut self) -> Image {
+        raster::render(self)
+    }
+
+    /// Export the window and the active stylesheet as a standalone HTML document.
+    pub fn to_html(&mut self, title: &str) -> String {
+        html::export(self, title)
+    }
+
+    /// Queue an event to be processed on the next tick.
+    pub fn send(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    /// Process the queued events in order, move to the current frame of the clock, then lay out
+    /// anything that changed. Returns whether the window is still open.
+    pub fn tick(&mut self) -> Result<bool, Error> {
+        while let Some(event) = self.events.pop_front() {
+            if !self.open {
+                self.events.clear();
+                break;
+            }
+
+            match event {
+                Event::Resize(width, height) => {
+                    self.rect = Rect::from([width, height]);
+                    self.update(self.rect);
+                }
+                Event::Click(point) => {
+                    self.last_hit = self.hit_test(point);
+                    let path = self.last_hit.clone();
+                    self.update_state(State::HOVER, &path);
+                    self.update_state(State::FOCUS, path.last().copied().as_slice());
+                    if let Some(on_click) = self.hooks.click {
+                        let path = self.last_hit.clone();
+                        on_click(self, &path);
+                    }
+                }
+                Event::MouseMove(point) => {
+                    let path = self.hit_test(point);
+                    self.update_state(State::HOVER, &path);
+                }
+                Event::MouseDown(point) => {
+                    let path = self.hit_test(point);
+                    self.update_state(State::HOVER, &path);
+                    self.update_state(State::ACTIVE, &path);
+                    self.update_state(State::FOCUS, path.last().copied().as_slice());
+                }
+                Event::MouseUp => {
+                    self.update_state(State::ACTIVE, &[]);
+                }
+                Event::KeyPress(key) => {
+                    if let Some(on_key) = self.hooks.key {
+                        on_key(self, key);
+                    }
+                }
+                Event::Close => {
+                    self.open = match self.hooks.quit {
+                        Some(on_quit) => !on_quit(self),
+                        None => false,
+                    };
+                }
+            }
+        }
+
+        if self.open {
+            self.animate(self.clock.now());
+        }
+        if self.open && (self.node.is_dirty() || self.layout.is_dirty()) {
+            if self.node.dirty().contains(Dirty::CHILDREN) {
+                // Components added after the window was built still need to be created
+                self.init()?;
+            }
+            self.update(self.rect);
+            self.node.clean();
+        }
+
+        Ok(self.open)
+    }
+}
+
+impl Container<(), Error> for Window {
+    fn layout(&mut self) -> &mut Layout<(), Error> {
+        &mut self.layout
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        for child in self.layout.children.iter() {
+            match child {
+                Child::Component(component) => {
+                    let component = &mut *component.borrow_mut();
+                    Headless::create_component(component, ())?;
+                }
+                Child::Container(container) => {
+                    container.borrow_mut().init()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for Window {
+    fn update(&mut self, rect: Rect) -> Point {
+        self.animate(self.clock.now());
+        self.layout.set_scopes(&self.node.child_scopes());
+        self.layout.set_ancestors(&[self.keys()]);
+        let media = THEME.media(&rect, self.layout.scale());
+        self.node.set_media(media);
+        self.layout.set_media(media);
+        self.rect = rect;
+        let (dimensions, appearance) = self.get_styles();
+        self.layout.set_parent_style(Some((dimensions, appearance)));
+        let scale = self.layout.scale();
+        self.layout
+            .update(
+                &Rect::from([rect.width(), rect.height()]).scale(1. / scale),
+                &dimensions,
+            )
+            .scale(scale)
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn restyle(&mut self) {
+        self.node.invalidate();
+        self.layout.restyle();
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn show(&mut self) {}
+
+    fn hide(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::{component, layout},
+        ui::component::{Text, CHAR_WIDTH, LINE_HEIGHT},
+    };
+
+    use style::Stylesheet;
+
+    use super::*;
+
+    fn text_rect(window: &mut Window, id: &str) -> Rect {
+        window.find_by_id(id).unwrap().rect()
+    }
+
+    #[test]
+    fn resize_lays_out_again() {
+        let mut window = Window::builder()
+            .size(200, 100)
+            .layout(layout![
+                component::text!("Hello", "first"),
+                component::text!("World", "second")
+            ])
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+
+        assert_eq!(
+            text_rect(&mut window, "first"),
+            Rect::new(0, 0, 200, LINE_HEIGHT)
+        );
+        assert_eq!(
+            text_rect(&mut window, "second"),
+            Rect::new(0, LINE_HEIGHT, 200, 2 * LINE_HEIGHT)
+        );
+
+        window.send(Event::Resize(320, 100));
+        assert!(window.tick().unwrap());
+        assert_eq!(text_rect(&mut window, "second").width(), 320);
+    }
+
+    #[test]
+    fn fractional_scale_rounds_consistently() {
+        let mut window = Window::builder()
+            .size(201, 101)
+            .scale(1.5)
+            .layout(layout![
+                component::text!("Hello", "first"),
+                component::text!("World", "second")
+            ])
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        window
+            .find_by_id("second")
+            .unwrap()
+            .downcast_mut::<Text>()
+            .unwrap()
+            .set_style("width", "33px")
+            .unwrap();
+        window.tick().unwrap();
+
+        assert_eq!(window.rect, Rect::new(0, 0, 302, 152));
+        let first = text_rect(&mut window, "first");
+        let second = text_rect(&mut window, "second");
+        assert_eq!(first, Rect::new(0, 0, 201, LINE_HEIGHT).scale(1.5));
+        assert_eq!(
+            second,
+            Rect::new(0, LINE_HEIGHT, 33, 2 * LINE_HEIGHT).scale(1.5)
+        );
+        // Neighbours share their edge after rounding and rects scale back to the logical ones
+        assert_eq!(second.top, first.bottom);
+        assert_eq!(
+            second.scale(1. / 1.5),
+            Rect::new(0, LINE_HEIGHT, 33, 2 * LINE_HEIGHT)
+        );
+
+        let hit = window.hit_test(Point::new(49, second.top));
+        assert_eq!(
+            hit.last(),
+            Some(&window.find_by_id("second").unwrap().node_id())
+        );
+        assert_eq!(window.hit_test(Point::new(50, second.top)).len(), 1);
+    }
+
+    #[test]
+    fn percentages_resolve_against_the_viewport() {
+        let stylesheet = || Stylesheet::try_parse("window { width: 50%; height: 25%; }").unwrap();
+        let mut window = Window::builder()
+            .size(200, 100)
+            .stylesheet(stylesheet())
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        assert_eq!(window.rect, Rect::new(0, 0, 100, 25));
+
+        let mut window = Window::builder()
+            .size(200, 100)
+            .viewport(1000, 800)
+            .stylesheet(stylesheet())
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        assert_eq!(window.rect, Rect::new(0, 0, 500, 200));
+    }
+
+    #[test]
+    fn click_hook_updates_elements() {
+        let mut window = Window::builder()
+            .size(200, 100)
+            .layout(layout![
+                component::text!("Hello", "first"),
+                component::text!("World", "second")
+            ])
+            .on_click(|window, _path| {
+                let handle = window.find_by_id("second").unwrap();
+                let mut text = handle.downcast_mut::<Text>().unwrap();
+                text.set_text("Clicked");
+                text.set_style("width", "100px").unwrap();
+            })
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+
+        let second = window.find_by_id("second").unwrap();
+        window.send(Event::Click(Point::new(10, LINE_HEIGHT + 2)));
+        window.tick().unwrap();
+
+        assert_eq!(
+            window.last_hit(),
+            &[window.node.node_id(), second.node_id()]
+        );
+        assert_eq!(second.downcast_ref::<Text>().unwrap().text, "Clicked");
+        assert_eq!(
+            second.downcast_ref::<Text>().unwrap().default_rect.width(),
+            7 * CHAR_WIDTH
+        );
+        assert_eq!(second.rect().width(), 100);
+    }
+
+    #[test]
+    fn quit_hook_can_keep_window_open() {
+        let mut window = Window::builder().on_quit(|_| false).build();
+        Headless::create_window(&mut window, 0).unwrap();
+
+        window.send(Event::Close);
+        assert!(window.tick().unwrap());
+
+        let mut window = Window::builder().build();
+        Headless::create_window(&mut window, 0).unwrap();
+
+        window.send(Event::Close);
+        assert!(!window.tick().unwrap());
+    }
+
+    #[test]
+    fn render_paints_styles() {
+        let mut window = Window::builder()
+            .size(100, 40)
+            .layout(layout![component::text!("I", "label")])
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        {
+            let label = window.find_by_id("label").unwrap();
+            let mut label = label.downcast_mut::<Text>().unwrap();
+            label.set_style("background-color", "#00f").unwrap();
+            label.set_style("border", "2px solid red").unwrap();
+            label.set_style("color", "#0f0").unwrap();
+            label.set_style("height", "20px").unwrap();
+        }
+        window.tick().unwrap();
+
+        let image = window.render();
+        assert_eq!((image.width(), image.height()), (100, 40));
+        // Window background, border, element background and the stem of the `I`
+        assert_eq!(image.pixel(50, 30), [255, 255, 255, 255]);
+        assert_eq!(image.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(image.pixel(3, 3), [0, 0, 255, 255]);
+        assert_eq!(image.pixel(48, 10), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn export_html_carries_ids_and_classes() {
+        let mut window = Window::builder()
+            .size(200, 100)
+            .id("main")
+            .layout(layout![component::text!("<Hello>", "first", ["greeting"])])
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        {
+            let first = window.find_by_id("first").unwrap();
+            let mut first = first.downcast_mut::<Text>().unwrap();
+            first.set_style("width", "120px").unwrap();
+        }
+        window.tick().unwrap();
+
+        let html = window.to_html("Export");
+        assert!(html.contains("<title>Export</title>"));
+        assert!(html.contains(
+            "<window id=\"main\" class=\"window\" style=\"width: 200px; height: 100px; overflow: hidden\""
+        ));
+        assert!(html.contains(&format!(
+            "<text id=\"first\" class=\"greeting text\" style=\"width: 120px\" data-rect=\"0 0 120 {}\">&lt;Hello&gt;</text>",
+            LINE_HEIGHT
+        )));
+    }
+
+    #[test]
+    fn pointer_state_restyles_elements() {
+        let mut window = Window::builder()
+            .size(400, 100)
+            .stylesheet(
+                Stylesheet::try_parse(
+                    ".button { width: 100px; &:hover { width: 120px; } &:active { width: 140px; } }
+                .button:focus { height: 30px; }
+                .button:disabled { width: 50px !important; }",
+                )
+                .unwrap(),
+            )
+            .layout(layout![component::text!("Ok", "ok", ["button"])])
+            .build();
+        Headless::create_window(&mut window, 0).unwrap();
+        assert_eq!(text_rect(&mut window, "ok").width(), 100);
+
+        window.send(Event::MouseMove(Point::new(5, 5)));
+        window.tick().unwrap();
+        assert_eq!(text_rect(&mut window, "ok").width(), 120);
+
+        window.send(Event::MouseDown(Point::new(5, 5)));
+        window.tick().unwrap();
+        assert_eq!(text_rect(&mut window, "ok").width(), 140);
+        assert_eq!(text_rect(&mut window, "ok").height(), 30);
+
+        window.send(Event::MouseUp);
+        window.send(Event::MouseMove(Point::new(300, 90)));
+        window.tick().unwrap();
+        assert_eq!(text_rect(&mut window, "ok").width(), 100);
+        assert_eq!(text_rect(&mut window, "ok").height(), 30);
+
+        let button = window.find_by_id("ok").unwrap();
+        button
+            .downcast_mut::<Text>()
+            .unwrap()
+            .set_state(State::DISABLED, true);
+        window.tick().unwrap();
+        assert_eq!(text_rect(&mut window, "ok").width(), 50);
+    }
+}
@@ -0,0 +1,51 @@
+use std::{cell::RefCell, sync::Arc};
+
+use gtk::Fixed;
+use native_core::{layout::Child, Backend};
+
+use crate::{
+    core::error::Error,
+    ui::{self, component::Text, popup, Window},
+};
+
+/// The GTK 4 backend.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Humboldt;
+
+impl Backend for Humboldt {
+    type Data = Fixed;
+    type Error = Error;
+    type Window = Window;
+
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<Fixed, Error> {
+        let mut text =
+            Text::builder(text).classes(classes.iter().map(|c| format!(".{}", c)).collect());
+
+        if let Some(id) = id {
+            text = text.id(format!("#{}", id).as_str());
+        }
+
+        // Layouts hold their children in an `Arc` even though elements are not `Sync`
+        #[allow(clippy::arc_with_non_send_sync)]
+        Child::Component(Arc::new(RefCell::new(text.build())))
+    }
+
+    fn create_window(window: &mut Window, index: u32) -> Result<(), Error> {
+        window.set_index(index);
+        window.build()?;
+        window.alive = true;
+        Ok(())
+    }
+
+    fn run(windows: Vec<Window>) -> Result<(), Error> {
+        ui::run(windows)
+    }
+
+    fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+        ui::tick(windows)
+    }
+
+    fn message(title: &str, message: &str, buttons: &str, icon: &str) -> u8 {
+        popup::message(title, message, buttons, icon)
+    }
+}
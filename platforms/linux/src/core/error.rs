@@ -0,0 +1,41 @@
+use std::fmt::{Debug, Display};
+
+#[derive(Clone)]
+pub enum Error {
+    /// The platform feature isn't available yet.
+    Unsupported(String),
+    GeneralError(String),
+}
+
+impl Debug for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unsupported(feature) => write!(f, "[NativeUI] {} is not supported yet", feature),
+            Self::GeneralError(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<&str> for Error {
+    fn from(value: &str) -> Self {
+        Error::GeneralError(value.to_string())
+    }
+}
+
+impl From<String> for Error {
+    fn from(value: String) -> Self {
+        Error::GeneralError(value)
+    }
+}
+
+impl From<gtk::glib::BoolError> for Error {
+    fn from(value: gtk::glib::BoolError) -> Self {
+        Error::GeneralError(value.to_string())
+    }
+}
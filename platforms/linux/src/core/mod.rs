@@ -0,0 +1,84 @@
+pub mod error;
+
+use gtk::{gdk, prelude::*, CssProvider};
+use native_core::Rect;
+use style::{color::Color, Appearance, Dimensions, Visibility};
+
+use self::error::Error;
+
+/// Sizes are resolved against this when no monitor can be queried.
+pub const SCREEN: Rect = Rect {
+    left: 0,
+    top: 0,
+    right: 1920,
+    bottom: 1080,
+};
+
+/// Initialize GTK on the current thread. Does nothing if it is already initialized.
+pub fn init() -> Result<(), Error> {
+    gtk::init().map_err(|e| Error::from(e.to_string()))
+}
+
+/// Geometry of the primary monitor in logical pixels.
+pub fn work_area() -> Rect {
+    gdk::Display::default()
+        .and_then(|display| display.monitors().item(0))
+        .and_then(|monitor| monitor.downcast::<gdk::Monitor>().ok())
+        .map(|monitor| {
+            let geometry = monitor.geometry();
+            Rect::new(
+                geometry.x(),
+                geometry.y(),
+                geometry.x() + geometry.width(),
+                geometry.y() + geometry.height(),
+            )
+        })
+        .unwrap_or(SCREEN)
+}
+
+/// Attach a style provider to a widget that only styles that widget.
+pub fn style_provider(widget: &impl IsA<gtk::Widget>) -> CssProvider {
+    let provider = CssProvider::new();
+    widget
+        .style_context()
+        .add_provider(&provider, gtk::STYLE_PROVIDER_PRIORITY_APPLICATION);
+    provider
+}
+
+pub fn css_color(color: &Color) -> String {
+    format!(
+        "rgba({}, {}, {}, {})",
+        color.red, color.green, color.blue, color.alpha
+    )
+}
+
+/// CSS for the colors and border of an element's computed styles.
+pub fn appearance_css(rect: &Rect, dimensions: &Dimensions, appearance: &Appearance) -> String {
+    let mut css = String::from("* {");
+    if let Some(color) = &appearance.background_color {
+        css.push_str(&format!(" background: {};", css_color(color)));
+    }
+    if let Some(color) = &appearance.color {
+        css.push_str(&format!(" color: {};", css_color(color)));
+    }
+
+    let border = dimensions.border.calc(rect.width(), rect.height());
+    if border != Default::default() {
+        css.push_str(&format!(
+            " border-style: solid; border-width: {}px {}px {}px {}px;",
+            border.top, border.right, border.bottom, border.left
+        ));
+        if let Some(color) = appearance
+            .border_color
+            .as_ref()
+            .or(appearance.color.as_ref())
+        {
+            css.push_str(&format!(" border-color: {};", css_color(color)));
+        }
+    }
+    if appearance.visibility == Visibility::Hidden {
+        css.push_str(" opacity: 0;");
+    }
+    css.push_str(" }");
+    css
+}
@@ -1,14 +1,15 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+use proc_macro_hack::proc_macro_hack;
+
+mod backend;
+
+pub mod core;
+pub mod prelude;
+pub mod ui;
+
+pub use backend::Humboldt;
+pub use gtk;
+pub use native_core;
+pub use style;
+
+#[proc_macro_hack]
+pub use macros::styles;
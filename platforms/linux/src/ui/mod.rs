@@ -0,0 +1,40 @@
+use gtk::glib::MainContext;
+
+use crate::core::error::Error;
+
+mod window;
+
+pub mod component;
+pub mod popup;
+pub use window::{HookType, Window, WindowBuilder};
+
+/// Show the windows and run the GTK main loop until all of them are closed.
+pub fn run(mut windows: Vec<Window>) -> Result<(), Error> {
+    for win in windows.iter_mut() {
+        win.build()?;
+        win.alive = true;
+    }
+
+    let context = MainContext::default();
+    while windows.iter().any(|e| e.alive) {
+        context.iteration(true);
+        for win in windows.iter_mut() {
+            win.tick()?;
+        }
+    }
+    Ok(())
+}
+
+/// Dispatch the events that are already pending without waiting for new ones.
+/// The windows are expected to be built already.
+pub fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+    let context = MainContext::default();
+    while context.pending() {
+        context.iteration(false);
+    }
+
+    for win in windows.iter_mut() {
+        win.tick()?;
+    }
+    Ok(windows.iter().any(|e| e.alive))
+}
@@ -0,0 +1,91 @@
+use std::{cell::Cell, rc::Rc};
+
+use gtk::{glib, prelude::*, ButtonsType, MessageDialog, MessageType, ResponseType};
+
+use crate::core::init;
+
+/// The buttons of a message box layout, named like the skylight layouts, from left to right.
+fn buttons(layout: &str) -> Vec<(&'static str, ResponseType)> {
+    match layout {
+        "ok_cancel" => vec![("Cancel", ResponseType::Cancel), ("OK", ResponseType::Ok)],
+        "yes_no" => vec![("No", ResponseType::No), ("Yes", ResponseType::Yes)],
+        "yes_no_cancel" => vec![
+            ("Cancel", ResponseType::Cancel),
+            ("No", ResponseType::No),
+            ("Yes", ResponseType::Yes),
+        ],
+        "retry_cancel" => vec![
+            ("Cancel", ResponseType::Cancel),
+            ("Retry", ResponseType::Accept),
+        ],
+        "abort_retry_ignore" => vec![
+            ("Abort", ResponseType::Reject),
+            ("Retry", ResponseType::Accept),
+            ("Ignore", ResponseType::Cancel),
+        ],
+        "cancel_try_continue" => vec![
+            ("Cancel", ResponseType::Cancel),
+            ("Try Again", ResponseType::Accept),
+            ("Continue", ResponseType::Apply),
+        ],
+        "help" => vec![("Help", ResponseType::Help), ("OK", ResponseType::Ok)],
+        _ => vec![("OK", ResponseType::Ok)],
+    }
+}
+
+fn icon(icon: &str) -> MessageType {
+    match icon {
+        "info" => MessageType::Info,
+        "question" => MessageType::Question,
+        "warning" => MessageType::Warning,
+        "error" => MessageType::Error,
+        _ => MessageType::Other,
+    }
+}
+
+/// Show a modal message dialog and wait for it to be answered.
+///
+/// Returns `1` if it was accepted, `0` if it was declined and `2` if it was dismissed.
+pub fn message(title: &str, message: &str, layout: &str, icon_name: &str) -> u8 {
+    if let Err(error) = init() {
+        eprintln!("{}", error);
+        return 2;
+    }
+
+    let dialog = MessageDialog::builder()
+        .title(title)
+        .text(title)
+        .secondary_text(message)
+        .message_type(icon(icon_name))
+        .buttons(ButtonsType::None)
+        .modal(true)
+        .build();
+    for (label, response) in buttons(layout) {
+        dialog.add_button(label, response);
+    }
+
+    let response = Rc::new(Cell::new(ResponseType::None));
+    let main_loop = glib::MainLoop::new(None, false);
+    {
+        let response = response.clone();
+        let main_loop = main_loop.clone();
+        dialog.connect_response(move |_, value| {
+            response.set(value);
+            main_loop.quit();
+        });
+    }
+
+    dialog.present();
+    main_loop.run();
+    dialog.destroy();
+
+    match response.get() {
+        ResponseType::Ok
+        | ResponseType::Yes
+        | ResponseType::Accept
+        | ResponseType::Apply
+        | ResponseType::Help => 1,
+        ResponseType::No | ResponseType::Reject => 0,
+        _ => 2,
+    }
+}
@@ -0,0 +1,500 @@
+use std::{any::Any, cell::RefCell, collections::VecDeque, rc::Rc};
+
+use gtk::{
+    glib, prelude::*, CssProvider, EventControllerMotion, Fixed, GestureClick, ScrolledWindow,
+};
+use style::color::Color;
+
+use crate::{
+    core::{appearance_css, error::Error, init, style_provider, work_area},
+    Humboldt,
+};
+
+use native_core::{
+    animation::{Clock, SystemClock, FRAME},
+    layout::{Child, Layout},
+    prelude::{Container, Renderable},
+    window::WindowOptions,
+    Backend, Dirty, Node, Point, Rect, State, THEME,
+};
+
+pub enum HookType {
+    QUIT,
+}
+
+#[derive(Default, Debug)]
+struct Hooks {
+    quit: Option<fn(&gtk::Window) -> bool>,
+}
+
+/// Events GTK signals queue for the window to handle on its next tick.
+#[derive(Debug)]
+enum Event {
+    Resize(i32, i32),
+    Close,
+    /// The pointer moved over the content, `None` when it left the window.
+    Motion(Option<Point>),
+    Press(Point),
+    Release,
+    /// Time for the next frame of the running animations.
+    Frame,
+}
+
+#[derive(Debug)]
+pub struct Window {
+    index: u32,
+    initialized: bool,
+    pub alive: bool,
+    pub icon: Option<&'static str>,
+
+    pub title: String,
+    pub background: Option<Color>,
+    node: Node,
+
+    pub handle: Option<gtk::Window>,
+    /// Container the components are positioned in.
+    pub fixed: Option<Fixed>,
+    provider: Option<CssProvider>,
+    events: Rc<RefCell<VecDeque<Event>>>,
+    pub rect: Rect,
+
+    pub layout: Layout<Fixed, Error>,
+    hooks: Hooks,
+    clock: SystemClock,
+    /// Whether a frame is scheduled.
+    frame: bool,
+}
+
+impl Window {
+    /// Size the window from its styles. Sizes are resolved against the primary monitor.
+    fn apply_styles(&mut self) {
+        let dimensions = self.get_styles().0;
+        let screen = work_area();
+        self.rect.right = dimensions.width.as_i32(
+            screen.width(),
+            match self.rect.width() {
+                0 => 400,
+                _ => self.rect.width(),
+            },
+        );
+        self.rect.bottom = dimensions.height.as_i32(
+            screen.height(),
+            match self.rect.height() {
+                0 => 300,
+                _ => self.rect.height(),
+            },
+        );
+    }
+
+    /// Style the window background from the `background` color and the computed styles.
+    fn apply_appearance(&self) {
+        if let Some(provider) = &self.provider {
+            let (dimensions, mut appearance) = self.get_styles();
+            if appearance.background_color.is_none() {
+                appearance.background_color = self.background;
+            }
+            provider.load_from_data(&appearance_css(&self.rect, &dimensions, &appearance));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct WindowBuilder {
+    options: WindowOptions<Fixed, Error>,
+    background: Option<Color>,
+    icon: Option<&'static str>,
+    hooks: Hooks,
+}
+
+impl WindowBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    native_core::window_builder!(Fixed, Error);
+
+    /// Name of an icon in the icon theme.
+    pub fn icon(mut self, name: &'static str) -> Self {
+        self.icon = Some(name);
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn hook(mut self, event_key: HookType, callback: fn(&gtk::Window) -> bool) -> Self {
+        match event_key {
+            HookType::QUIT => self.hooks.quit = Some(callback),
+        }
+
+        self
+    }
+
+    pub fn build(self) -> Window {
+        let parts = self.options.into_parts();
+
+        Window {
+            index: parts.index,
+            initialized: false,
+            alive: false,
+            icon: self.icon,
+            title: parts.title,
+            background: self.background,
+            node: parts.node,
+            handle: None,
+            fixed: None,
+            provider: None,
+            events: Rc::default(),
+            rect: parts.rect,
+            layout: parts.layout,
+            hooks: self.hooks,
+            clock: SystemClock::default(),
+            frame: false,
+        }
+    }
+
+    pub fn open(self) -> Result<(), Error> {
+        self.build().open()
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        WindowBuilder::default().build()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+
+    /// Create the GTK window and its children, lay them out and show it.
+    pub fn build(&mut self) -> Result<(), Error> {
+        if !self.initialized {
+            init()?;
+            self.apply_styles();
+            self.init()?;
+            self.update(self.rect);
+            self.node.clean();
+            self.show();
+            self.initialized = true;
+        }
+        Ok(())
+    }
+
+    /// Show the window and run the GTK main loop until it is closed.
+    pub fn open(&mut self) -> Result<(), Error> {
+        self.alive = true;
+        self.build()?;
+
+        let context = glib::MainContext::default();
+        while self.alive {
+            context.iteration(true);
+            self.tick()?;
+        }
+        Ok(())
+    }
+
+    /// Create the widgets of components added after the window was created, nested layouts
+    /// included. Returns the error of the first one that fails.
+    fn create_children(&mut self) -> Result<(), Error> {
+        let Some(fixed) = &self.fixed else {
+            return Ok(());
+        };
+        if self.initialized {
+            create_components(&mut self.layout, fixed)?;
+        }
+        self.node.unmark(Dirty::CHILDREN);
+        Ok(())
+    }
+
+    /// Handle the events GTK queued for the window and schedule the next frame while
+    /// something animates. Returns whether the window is still open.
+    pub fn tick(&mut self) -> Result<bool, Error> {
+        loop {
+            let event = self.events.borrow_mut().pop_front();
+            let Some(event) = event else { break };
+
+            match event {
+                Event::Resize(width, height) => {
+                    if width > 0
+                        && height > 0
+                        && (width, height) != (self.rect.width(), self.rect.height())
+                    {
+                        self.update(Rect::from([width, height]));
+                    }
+                }
+                Event::Motion(point) => {
+                    let path = point.map(|point| self.hit_test(point)).unwrap_or_default();
+                    self.update_state(State::HOVER, &path);
+                }
+                Event::Press(point) => {
+                    let path = self.hit_test(point);
+                    self.update_state(State::ACTIVE, &path);
+                    self.update_state(State::FOCUS, path.last().copied().as_slice());
+                }
+                Event::Release => {
+                    self.update_state(State::ACTIVE, &[]);
+                }
+                Event::Frame => self.frame = false,
+                Event::Close => {
+                    if let Some(handle) = self.handle.clone() {
+                        let close = match self.hooks.quit {
+                            Some(on_quit) => on_quit(&handle),
+                            None => true,
+                        };
+                        if close {
+                            handle.destroy();
+                            self.alive = false;
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.alive {
+            self.animate(self.clock.now());
+        }
+        if self.alive && (self.node.is_dirty() || self.layout.is_dirty()) {
+            // Components added after the window was created still need their widgets
+            self.create_children()?;
+            self.update(self.rect);
+            self.node.clean();
+        }
+
+        if self.alive && !self.frame && self.is_animating() {
+            let events = self.events.clone();
+            glib::timeout_add_local_once(FRAME, move || {
+                events.borrow_mut().push_back(Event::Frame)
+            });
+            self.frame = true;
+        }
+        Ok(self.alive)
+    }
+}
+
+impl Container<Fixed, Error> for Window {
+    fn layout(&mut self) -> &mut Layout<Fixed, Error> {
+        &mut self.layout
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        let handle = gtk::Window::builder()
+            .title(self.title.as_str())
+            .default_width(self.rect.width())
+            .default_height(self.rect.height())
+            .build();
+        if let Some(icon) = self.icon {
+            handle.set_icon_name(Some(icon));
+        }
+
+        // Components are placed at absolute positions and the window scrolls when they overflow
+        let fixed = Fixed::new();
+        let scroll = ScrolledWindow::builder().child(&fixed).build();
+        handle.set_child(Some(&scroll));
+
+        let events = self.events.clone();
+        handle.connect_close_request(move |_| {
+            events.borrow_mut().push_back(Event::Close);
+            glib::Propagation::Stop
+        });
+        let events = self.events.clone();
+        handle.connect_default_width_notify(move |window| {
+            events.borrow_mut().push_back(Event::Resize(
+                window.default_width(),
+                window.default_height(),
+            ));
+        });
+        let events = self.events.clone();
+        handle.connect_default_height_notify(move |window| {
+            events.borrow_mut().push_back(Event::Resize(
+                window.default_width(),
+                window.default_height(),
+            ));
+        });
+
+        // Pointer input drives the :hover, :active and :focus states of the elements
+        let motion = EventControllerMotion::new();
+        let events = self.events.clone();
+        motion.connect_motion(move |_, x, y| {
+            events
+                .borrow_mut()
+                .push_back(Event::Motion(Some(Point::new(x as i32, y as i32))));
+        });
+        let events = self.events.clone();
+        motion.connect_leave(move |_| events.borrow_mut().push_back(Event::Motion(None)));
+        fixed.add_controller(motion);
+
+        let click = GestureClick::new();
+        let events = self.events.clone();
+        click.connect_pressed(move |_, _, x, y| {
+            events
+                .borrow_mut()
+                .push_back(Event::Press(Point::new(x as i32, y as i32)));
+        });
+        let events = self.events.clone();
+        click.connect_released(move |_, _, _, _| events.borrow_mut().push_back(Event::Release));
+        fixed.add_controller(click);
+
+        self.provider = Some(style_provider(&handle));
+        self.handle = Some(handle);
+        self.fixed = Some(fixed.clone());
+        self.apply_appearance();
+
+        for child in self.layout.children.iter() {
+            match child {
+                Child::Component(component) => {
+                    let component = &mut *component.borrow_mut();
+                    Humboldt::create_component(component, fixed.clone())?;
+                }
+                Child::Container(container) => {
+                    container.borrow_mut().init()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for Window {
+    fn update(&mut self, rect: Rect) -> Point {
+        self.animate(self.clock.now());
+        self.layout.set_scopes(&self.node.child_scopes());
+        self.layout.set_ancestors(&[self.keys()]);
+        let media = THEME.media(&rect, self.layout.scale());
+        self.node.set_media(media);
+        self.layout.set_media(media);
+        self.rect = rect;
+        let (dimensions, appearance) = self.get_styles();
+        self.layout.set_parent_style(Some((dimensions, appearance)));
+        let max = self
+            .layout
+            .update(&Rect::from([rect.width(), rect.height()]), &dimensions);
+
+        // Let the scrolled window know how far the content reaches
+        if let Some(fixed) = &self.fixed {
+            fixed.set_size_request(max.x.max(0), max.y.max(0));
+        }
+        max
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn restyle(&mut self) {
+        self.node.invalidate();
+        self.layout.restyle();
+        self.apply_appearance();
+        self.request_layout();
+    }
+
+    fn request_layout(&mut self) {
+        if self.initialized {
+            self.update(self.rect);
+        }
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn show(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.present();
+        }
+    }
+
+    fn hide(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.set_visible(false);
+        }
+    }
+}
+
+/// Create the widgets of the components in `layout` and its nested layouts that don't have one
+/// yet.
+fn create_components(layout: &mut Layout<Fixed, Error>, fixed: &Fixed) -> Result<(), Error> {
+    for child in layout.children.iter() {
+        match child {
+            Child::Component(component) => {
+                Humboldt::create_component(&mut *component.borrow_mut(), fixed.clone())?;
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                create_components(container.layout(), fixed)?;
+                container.node().unmark(Dirty::CHILDREN);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{component, layout};
+
+    use super::*;
+
+    #[test]
+    fn build_lays_out_children() {
+        if init().is_err() {
+            eprintln!("Skipped: GTK needs a display, run under xvfb-run or broadway");
+            return;
+        }
+
+        let mut window = Window::builder()
+            .size(200, 100)
+            .layout(layout![
+                component::text!("Hello"),
+                component::text!("World")
+            ])
+            .build();
+        Humboldt::create_window(&mut window, 0).unwrap();
+
+        let rects: Vec<Rect> = window
+            .layout
+            .children
+            .iter()
+            .map(|child| match child {
+                Child::Component(component) => *component.borrow().rect(),
+                Child::Container(container) => *container.borrow().rect(),
+            })
+            .collect();
+
+        assert_eq!((rects[0].left, rects[0].top, rects[0].right), (0, 0, 200));
+        assert!(rects[0].height() > 0);
+        assert_eq!((rects[1].top, rects[1].right), (rects[0].bottom, 200));
+    }
+}
@@ -0,0 +1,52 @@
+use std::{cell::RefCell, sync::Arc};
+
+use native_core::{layout::Child, Backend};
+
+use crate::{
+    core::error::Error,
+    ui::{component::Text, Window},
+};
+
+/// The macOS backend.
+///
+/// Windows are laid out but not displayed until the Cocoa wrapper lands. This lets apps build
+/// and their layouts be exercised on macOS.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Braeburn;
+
+impl Backend for Braeburn {
+    type Data = ();
+    type Error = Error;
+    type Window = Window;
+
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<(), Error> {
+        let mut text =
+            Text::builder(text).classes(classes.iter().map(|c| format!(".{}", c)).collect());
+
+        if let Some(id) = id {
+            text = text.id(format!("#{}", id).as_str());
+        }
+
+        // Layouts hold their children in an `Arc` even though elements are not `Sync`
+        #[allow(clippy::arc_with_non_send_sync)]
+        Child::Component(Arc::new(RefCell::new(text.build())))
+    }
+
+    fn create_window(window: &mut Window, index: u32) -> Result<(), Error> {
+        window.set_index(index);
+        window.build()
+    }
+
+    fn run(_windows: Vec<Window>) -> Result<(), Error> {
+        Err(Error::Unsupported("Opening windows on macOS".to_string()))
+    }
+
+    fn tick(_windows: &mut [Window]) -> Result<bool, Error> {
+        Err(Error::Unsupported("Opening windows on macOS".to_string()))
+    }
+
+    fn message(title: &str, message: &str, _buttons: &str, _icon: &str) -> u8 {
+        eprintln!("[{}] {}", title, message);
+        2
+    }
+}
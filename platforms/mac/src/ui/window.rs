@@ -0,0 +1,217 @@
+use std::any::Any;
+
+use crate::{core::error::Error, Braeburn};
+
+use native_core::{
+    animation::{Clock, SystemClock},
+    layout::{Child, Layout},
+    prelude::{Container, Renderable},
+    window::WindowOptions,
+    Backend, Node, Point, Rect, THEME,
+};
+
+#[derive(Debug)]
+pub struct Window {
+    index: u32,
+    initialized: bool,
+    pub title: String,
+    node: Node,
+    pub rect: Rect,
+    pub layout: Layout<(), Error>,
+    clock: SystemClock,
+}
+
+impl Window {
+    /// Size the window from its styles. Monitors can't be queried until the Cocoa wrapper lands
+    /// so percentages are resolved against the requested size.
+    fn apply_styles(&mut self) {
+        let dimensions = self.get_styles().0;
+        let requested = Rect::from([
+            match self.rect.width() {
+                0 => 400,
+                width => width,
+            },
+            match self.rect.height() {
+                0 => 300,
+                height => height,
+            },
+        ]);
+        self.rect.right = dimensions
+            .width
+            .as_i32(requested.width(), requested.width());
+        self.rect.bottom = dimensions
+            .height
+            .as_i32(requested.height(), requested.height());
+    }
+}
+
+#[derive(Default)]
+pub struct WindowBuilder {
+    options: WindowOptions<(), Error>,
+}
+
+impl WindowBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    native_core::window_builder!((), Error);
+
+    pub fn build(self) -> Window {
+        let parts = self.options.into_parts();
+
+        Window {
+            index: parts.index,
+            initialized: false,
+            title: parts.title,
+            node: parts.node,
+            rect: parts.rect,
+            layout: parts.layout,
+            clock: SystemClock::default(),
+        }
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        WindowBuilder::default().build()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+
+    /// Create the children of the window and lay them out.
+    pub fn build(&mut self) -> Result<(), Error> {
+        if !self.initialized {
+            self.apply_styles();
+            self.init()?;
+            self.update(self.rect);
+            self.initialized = true;
+        }
+        Ok(())
+    }
+}
+
+impl Container<(), Error> for Window {
+    fn layout(&mut self) -> &mut Layout<(), Error> {
+        &mut self.layout
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        for child in self.layout.children.iter() {
+            match child {
+                Child::Component(component) => {
+                    let component = &mut *component.borrow_mut();
+                    Braeburn::create_component(component, ())?;
+                }
+                Child::Container(container) => {
+                    container.borrow_mut().init()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for Window {
+    fn update(&mut self, rect: Rect) -> Point {
+        self.animate(self.clock.now());
+        self.layout.set_scopes(&self.node.child_scopes());
+        self.layout.set_ancestors(&[self.keys()]);
+        let media = THEME.media(&rect, self.layout.scale());
+        self.node.set_media(media);
+        self.layout.set_media(media);
+        self.rect = rect;
+        let (dimensions, appearance) = self.get_styles();
+        self.layout.set_parent_style(Some((dimensions, appearance)));
+        self.layout
+            .update(&Rect::from([rect.width(), rect.height()]), &dimensions)
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn restyle(&mut self) {
+        self.node.invalidate();
+        self.layout.restyle();
+        self.request_layout();
+    }
+
+    fn request_layout(&mut self) {
+        if self.initialized {
+            self.update(self.rect);
+        }
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn show(&mut self) {}
+
+    fn hide(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{component, layout};
+
+    use super::*;
+
+    #[test]
+    fn build_lays_out_children() {
+        let mut window = Window::builder()
+            .size(200, 100)
+            .layout(layout![
+                component::text!("Hello"),
+                component::text!("World")
+            ])
+            .build();
+        Braeburn::create_window(&mut window, 0).unwrap();
+
+        let rects: Vec<Rect> = window
+            .layout
+            .children
+            .iter()
+            .map(|child| match child {
+                Child::Component(component) => *component.borrow().rect(),
+                Child::Container(container) => *container.borrow().rect(),
+            })
+            .collect();
+
+        assert_eq!(rects[0], Rect::new(0, 0, 200, 16));
+        assert_eq!(rects[1], Rect::new(0, 16, 200, 32));
+    }
+}
@@ -0,0 +1,74 @@
+use std::{cell::RefCell, sync::Arc};
+
+use native_core::{animation::FRAME, layout::Child, prelude::Container, Backend};
+
+use crate::{
+    core::{error::Error, event::Event, screen::Screen},
+    ui::{component::Text, Window},
+};
+
+/// A backend that draws windows as character cells in the terminal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Terminal;
+
+impl Backend for Terminal {
+    type Data = ();
+    type Error = Error;
+    type Window = Window;
+
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<(), Error> {
+        let mut text =
+            Text::builder(text).classes(classes.iter().map(|c| format!(".{}", c)).collect());
+
+        if let Some(id) = id {
+            text = text.id(format!("#{}", id).as_str());
+        }
+
+        // Layouts hold their children in an `Arc` even though elements are not `Sync`
+        #[allow(clippy::arc_with_non_send_sync)]
+        Child::Component(Arc::new(RefCell::new(text.build())))
+    }
+
+    fn create_window(window: &mut Window, index: u32) -> Result<(), Error> {
+        window.set_index(index);
+        window.build()
+    }
+
+    /// The terminal shows a single window at a time so the windows are run one after the
+    /// other, each filling the terminal until it is closed.
+    fn run(mut windows: Vec<Window>) -> Result<(), Error> {
+        let mut screen = Screen::enter()?;
+        for window in windows.iter_mut() {
+            window.build()?;
+            let (columns, rows) = Screen::size()?;
+            window.send(Event::Resize(columns as i32, rows as i32));
+
+            while window.tick()? {
+                screen.draw(window.buffer())?;
+                // Keep drawing frames while something animates
+                let event = match window.is_animating() {
+                    true => screen.poll(FRAME)?,
+                    false => Some(screen.read()?),
+                };
+                if let Some(event) = event {
+                    window.send(event);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Process the queued events of each window without touching the terminal.
+    fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+        let mut open = false;
+        for window in windows.iter_mut() {
+            open |= window.tick()?;
+        }
+        Ok(open)
+    }
+
+    fn message(title: &str, message: &str, _buttons: &str, _icon: &str) -> u8 {
+        eprintln!("[{}] {}", title, message);
+        2
+    }
+}
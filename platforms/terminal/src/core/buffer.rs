@@ -0,0 +1,163 @@
+use native_core::Rect;
+use style::color::Color;
+
+/// A single character cell. `None` colors use the terminal's defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            symbol: ' ',
+            foreground: None,
+            background: None,
+        }
+    }
+}
+
+/// A grid of character cells stored row by row from the top left.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// A buffer of blank cells.
+    pub fn new(width: u16, height: u16) -> Self {
+        Buffer {
+            width,
+            height,
+            cells: vec![Cell::default(); width as usize * height as usize],
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::from([self.width as i32, self.height as i32])
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// The cell at a column and row, `None` outside of the buffer.
+    pub fn cell(&self, x: i32, y: i32) -> Option<&Cell> {
+        match self.bounds().contains((x, y).into()) {
+            true => self
+                .cells
+                .get(y as usize * self.width as usize + x as usize),
+            false => None,
+        }
+    }
+
+    pub fn cell_mut(&mut self, x: i32, y: i32) -> Option<&mut Cell> {
+        match self.bounds().contains((x, y).into()) {
+            true => self
+                .cells
+                .get_mut(y as usize * self.width as usize + x as usize),
+            false => None,
+        }
+    }
+
+    /// The characters of a row.
+    pub fn line(&self, y: i32) -> String {
+        (0..self.width as i32)
+            .filter_map(|x| self.cell(x, y))
+            .map(|cell| cell.symbol)
+            .collect()
+    }
+
+    /// The characters of every row, top to bottom.
+    pub fn lines(&self) -> Vec<String> {
+        (0..self.height as i32).map(|y| self.line(y)).collect()
+    }
+
+    /// Clear the cells of a rect and set their background.
+    pub fn fill_rect(&mut self, rect: Rect, clip: Rect, color: Color) {
+        let background = visible(color);
+        if background.is_none() {
+            return;
+        }
+        self.each(rect, clip, |cell| {
+            *cell = Cell {
+                background,
+                ..Default::default()
+            }
+        });
+    }
+
+    /// Outline a rect with box drawing characters.
+    pub fn stroke_rect(&mut self, rect: Rect, clip: Rect, color: Color) {
+        if rect.is_empty() {
+            return;
+        }
+
+        let (right, bottom) = (rect.right - 1, rect.bottom - 1);
+        for y in rect.top..rect.bottom {
+            for x in rect.left..rect.right {
+                let symbol = match (x, y) {
+                    _ if x == rect.left && y == rect.top => '┌',
+                    _ if x == right && y == rect.top => '┐',
+                    _ if x == rect.left && y == bottom => '└',
+                    _ if x == right && y == bottom => '┘',
+                    _ if y == rect.top || y == bottom => '─',
+                    _ if x == rect.left || x == right => '│',
+                    _ => continue,
+                };
+                self.set(x, y, clip, symbol, Some(color));
+            }
+        }
+    }
+
+    /// Write a line of text starting at a cell. Characters outside of `clip` are dropped.
+    pub fn print(&mut self, x: i32, y: i32, text: &str, clip: Rect, color: Option<Color>) {
+        for (i, symbol) in text.chars().enumerate() {
+            self.set(x + i as i32, y, clip, symbol, color);
+        }
+    }
+
+    fn set(&mut self, x: i32, y: i32, clip: Rect, symbol: char, color: Option<Color>) {
+        if clip.contains((x, y).into()) {
+            if let Some(cell) = self.cell_mut(x, y) {
+                cell.symbol = symbol;
+                cell.foreground = color.and_then(visible);
+            }
+        }
+    }
+
+    fn each<F: FnMut(&mut Cell)>(&mut self, rect: Rect, clip: Rect, mut f: F) {
+        if let Some(area) = rect
+            .intersect(&clip)
+            .and_then(|rect| rect.intersect(&self.bounds()))
+        {
+            for y in area.top..area.bottom {
+                for x in area.left..area.right {
+                    if let Some(cell) = self.cell_mut(x, y) {
+                        f(cell);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fully transparent colors leave the terminal default in place.
+fn visible(color: Color) -> Option<Color> {
+    match color.alpha > 0. {
+        true => Some(color),
+        false => None,
+    }
+}
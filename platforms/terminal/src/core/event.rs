@@ -0,0 +1,38 @@
+use native_core::Point;
+
+/// Input read from the terminal or injected into a window. Positions are character cells
+/// from the top left of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// The left button was pressed over a cell. The elements hit become active and the
+    /// innermost one is focused.
+    Click(Point),
+    /// The left button was released.
+    Release,
+    /// The mouse moved to a cell, updating the hovered elements.
+    MouseMove(Point),
+    KeyPress(Key),
+    /// Mouse wheel at a cell, in rows. Positive values scroll down.
+    Scroll(Point, i32),
+    /// The terminal changed to a new number of columns and rows.
+    Resize(i32, i32),
+    /// Ask the window to close. The quit hook can keep it open.
+    Close,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Escape,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
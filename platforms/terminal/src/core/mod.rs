@@ -0,0 +1,34 @@
+pub mod buffer;
+pub mod error;
+pub mod event;
+pub mod render;
+pub mod screen;
+
+use native_core::{Point, Rect};
+
+/// Width of a character cell in layout pixels.
+pub const CELL_WIDTH: i32 = 8;
+/// Height of a character cell in layout pixels. Terminal cells are about twice as tall as wide.
+pub const CELL_HEIGHT: i32 = 16;
+
+fn round_div(value: i32, size: i32) -> i32 {
+    (value + size / 2).div_euclid(size)
+}
+
+/// Convert a rect in layout pixels to the character cells it covers the most of.
+pub fn to_cells(rect: Rect) -> Rect {
+    Rect::new(
+        round_div(rect.left, CELL_WIDTH),
+        round_div(rect.top, CELL_HEIGHT),
+        round_div(rect.right, CELL_WIDTH),
+        round_div(rect.bottom, CELL_HEIGHT),
+    )
+}
+
+/// The layout pixel at the center of a character cell.
+pub fn cell_center(cell: Point) -> Point {
+    Point::new(
+        cell.x * CELL_WIDTH + CELL_WIDTH / 2,
+        cell.y * CELL_HEIGHT + CELL_HEIGHT / 2,
+    )
+}
@@ -0,0 +1,175 @@
+//! Paint a laid out window into a [`Buffer`] of character cells.
+//!
+//! Mirrors `native_core::raster::render`: rects are converted from layout pixels to the cells
+//! they cover, backgrounds fill cells, borders are drawn with box drawing characters and text
+//! is centered in the content box.
+
+use style::{color::Color, Appearance, Dimensions, Overflow, Position, Visibility};
+
+use native_core::{
+    layout::{Child, Layout},
+    prelude::Container,
+    raster::Content,
+    Point, Rect,
+};
+
+use super::{buffer::Buffer, to_cells, CELL_HEIGHT, CELL_WIDTH};
+
+/// Render a window that has been laid out. Elements are painted from the bottom of the
+/// stacking order up and containers with `overflow: hidden` or `overflow: scroll` clip
+/// their content.
+pub fn render<Data, Error, W>(window: &mut W) -> Buffer
+where
+    W: Container<Data, Error> + ?Sized,
+{
+    let size = window.rect().size();
+    let mut buffer = Buffer::new(
+        (size.width / CELL_WIDTH).max(0) as u16,
+        (size.height / CELL_HEIGHT).max(0) as u16,
+    );
+
+    let bounds = Rect::from([size.width, size.height]);
+    let (dimensions, appearance) = window.get_styles();
+    paint(
+        &mut buffer,
+        bounds,
+        bounds,
+        &dimensions,
+        &appearance,
+        &Content::None,
+    );
+
+    let scroll = window.node().scroll();
+    paint_layout(
+        &mut buffer,
+        window.layout(),
+        Point::default() - scroll,
+        bounds,
+    );
+    buffer
+}
+
+fn paint_layout<Data, Error>(
+    buffer: &mut Buffer,
+    layout: &mut Layout<Data, Error>,
+    offset: Point,
+    clip: Rect,
+) {
+    let mut order: Vec<(i32, bool, usize)> = layout
+        .children
+        .iter()
+        .enumerate()
+        .map(|(index, child)| {
+            let dimensions = match child {
+                Child::Component(component) => component.borrow().get_styles().0,
+                Child::Container(container) => container.borrow().get_styles().0,
+            };
+            (
+                dimensions.z_index.unwrap_or(0),
+                dimensions.position == Position::Absolute,
+                index,
+            )
+        })
+        .collect();
+    order.sort();
+
+    for (_, _, index) in order {
+        match &layout.children[index] {
+            Child::Component(component) => {
+                let component = component.borrow();
+                let rect = component.rect().translate(offset.x, offset.y);
+                let (dimensions, appearance) = component.get_styles();
+                paint(
+                    buffer,
+                    rect,
+                    clip,
+                    &dimensions,
+                    &appearance,
+                    &component.content(),
+                );
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                let rect = container.rect().translate(offset.x, offset.y);
+                let (dimensions, appearance) = container.get_styles();
+                paint(
+                    buffer,
+                    rect,
+                    clip,
+                    &dimensions,
+                    &appearance,
+                    &container.content(),
+                );
+
+                let mut inner = clip;
+                if matches!(dimensions.overflow_x, Overflow::Hidden | Overflow::Scroll) {
+                    inner.left = inner.left.max(rect.left);
+                    inner.right = inner.right.min(rect.right);
+                }
+                if matches!(dimensions.overflow_y, Overflow::Hidden | Overflow::Scroll) {
+                    inner.top = inner.top.max(rect.top);
+                    inner.bottom = inner.bottom.min(rect.bottom);
+                }
+
+                if !inner.is_empty() {
+                    let offset = rect.origin() - container.node().scroll();
+                    paint_layout(buffer, container.layout(), offset, inner);
+                }
+            }
+        }
+    }
+}
+
+/// Paint the background, border and content of a single element.
+fn paint(
+    buffer: &mut Buffer,
+    rect: Rect,
+    clip: Rect,
+    dimensions: &Dimensions,
+    appearance: &Appearance,
+    content: &Content,
+) {
+    if appearance.visibility == Visibility::Hidden {
+        return;
+    }
+    let cells = to_cells(rect);
+    let clip = to_cells(clip);
+
+    if let Some(background) = appearance.background_color {
+        buffer.fill_rect(cells, clip, background);
+    }
+
+    // Any border is drawn one cell wide, inside the rect like the other renderers
+    let border = dimensions.border.calc(rect.width(), rect.height());
+    let mut inner = to_cells(
+        rect.deflate(&border)
+            .deflate(&dimensions.padding.calc(rect.width(), rect.height())),
+    );
+    if border != Default::default() {
+        let color = appearance
+            .border_color
+            .or(appearance.color)
+            .unwrap_or(Color::new(0, 0, 0, 1.));
+        buffer.stroke_rect(cells, clip, color);
+
+        inner = inner
+            .intersect(&Rect::new(
+                cells.left + 1,
+                cells.top + 1,
+                cells.right - 1,
+                cells.bottom - 1,
+            ))
+            .unwrap_or_default();
+    }
+
+    if let Content::Text(text) = content {
+        if inner.is_empty() {
+            return;
+        }
+        // Centered like the native text controls, keeping the start visible if it overflows
+        let width = text.chars().count() as i32;
+        let x = inner.left + ((inner.width() - width) / 2).max(0);
+        let y = inner.top + (inner.height() - 1) / 2;
+        buffer.print(x, y, text, clip, appearance.color);
+    }
+}
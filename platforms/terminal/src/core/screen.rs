@@ -0,0 +1,188 @@
+//! The real terminal, driven with crossterm.
+
+use std::{
+    io::{self, Stdout, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor, event as term, execute, queue,
+    style::{Color as TermColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use native_core::Point;
+use style::color::Color;
+
+use super::{
+    buffer::Buffer,
+    event::{Event, Key},
+};
+
+/// Exclusive use of the terminal. The alternate screen and raw mode are entered when created
+/// and the terminal is restored when dropped.
+pub struct Screen {
+    stdout: Stdout,
+}
+
+impl Screen {
+    pub fn enter() -> io::Result<Screen> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            term::EnableMouseCapture,
+            cursor::Hide
+        )?;
+        Ok(Screen { stdout })
+    }
+
+    /// Number of columns and rows of the terminal.
+    pub fn size() -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    /// Draw every cell of the buffer from the top left of the terminal.
+    pub fn draw(&mut self, buffer: &Buffer) -> io::Result<()> {
+        let mut colors = (None, None);
+        for y in 0..buffer.height() {
+            queue!(self.stdout, cursor::MoveTo(0, y))?;
+            for x in 0..buffer.width() {
+                let cell = buffer.cell(x as i32, y as i32).copied().unwrap_or_default();
+
+                if colors.0 != Some(cell.foreground) {
+                    queue!(self.stdout, SetForegroundColor(color(cell.foreground)))?;
+                }
+                if colors.1 != Some(cell.background) {
+                    queue!(self.stdout, SetBackgroundColor(color(cell.background)))?;
+                }
+                colors = (Some(cell.foreground), Some(cell.background));
+
+                queue!(self.stdout, Print(cell.symbol))?;
+            }
+        }
+        queue!(self.stdout, ResetColor)?;
+        self.stdout.flush()
+    }
+
+    /// Wait up to `timeout` for the next terminal event that a window handles, `None` if there
+    /// was none in time.
+    pub fn poll(&mut self, timeout: Duration) -> io::Result<Option<Event>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if !term::poll(left)? {
+                return Ok(None);
+            }
+            if let Some(event) = translate(term::read()?) {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    /// Wait for the next terminal event that a window handles.
+    pub fn read(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(event) = translate(term::read()?) {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(
+            self.stdout,
+            ResetColor,
+            cursor::Show,
+            term::DisableMouseCapture,
+            LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn color(color: Option<Color>) -> TermColor {
+    match color {
+        Some(color) => TermColor::Rgb {
+            r: color.red,
+            g: color.green,
+            b: color.blue,
+        },
+        None => TermColor::Reset,
+    }
+}
+
+/// Convert a crossterm event to a window event. `Ctrl+C` closes the window since raw mode
+/// stops it from interrupting the process.
+pub fn translate(event: term::Event) -> Option<Event> {
+    match event {
+        term::Event::Key(key) if key.kind != term::KeyEventKind::Release => {
+            if key.modifiers.contains(term::KeyModifiers::CONTROL)
+                && key.code == term::KeyCode::Char('c')
+            {
+                return Some(Event::Close);
+            }
+
+            Some(Event::KeyPress(match key.code {
+                term::KeyCode::Char(c) => Key::Char(c),
+                term::KeyCode::Enter => Key::Enter,
+                term::KeyCode::Tab => Key::Tab,
+                term::KeyCode::Esc => Key::Escape,
+                term::KeyCode::Backspace => Key::Backspace,
+                term::KeyCode::Up => Key::Up,
+                term::KeyCode::Down => Key::Down,
+                term::KeyCode::Left => Key::Left,
+                term::KeyCode::Right => Key::Right,
+                term::KeyCode::PageUp => Key::PageUp,
+                term::KeyCode::PageDown => Key::PageDown,
+                term::KeyCode::Home => Key::Home,
+                term::KeyCode::End => Key::End,
+                _ => return None,
+            }))
+        }
+        term::Event::Mouse(mouse) => {
+            let cell = Point::new(mouse.column as i32, mouse.row as i32);
+            match mouse.kind {
+                term::MouseEventKind::Down(term::MouseButton::Left) => Some(Event::Click(cell)),
+                term::MouseEventKind::Up(term::MouseButton::Left) => Some(Event::Release),
+                term::MouseEventKind::Moved => Some(Event::MouseMove(cell)),
+                term::MouseEventKind::ScrollDown => Some(Event::Scroll(cell, 1)),
+                term::MouseEventKind::ScrollUp => Some(Event::Scroll(cell, -1)),
+                _ => None,
+            }
+        }
+        term::Event::Resize(columns, rows) => Some(Event::Resize(columns as i32, rows as i32)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translate_input() {
+        let key = |code, modifiers| term::Event::Key(term::KeyEvent::new(code, modifiers));
+
+        assert_eq!(
+            translate(key(term::KeyCode::Char('c'), term::KeyModifiers::CONTROL)),
+            Some(Event::Close)
+        );
+        assert_eq!(
+            translate(key(term::KeyCode::PageDown, term::KeyModifiers::NONE)),
+            Some(Event::KeyPress(Key::PageDown))
+        );
+        assert_eq!(
+            translate(term::Event::Mouse(term::MouseEvent {
+                kind: term::MouseEventKind::ScrollUp,
+                column: 3,
+                row: 4,
+                modifiers: term::KeyModifiers::NONE,
+            })),
+            Some(Event::Scroll(Point::new(3, 4), -1))
+        );
+        assert_eq!(translate(term::Event::FocusGained), None);
+    }
+}
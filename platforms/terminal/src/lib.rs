@@ -0,0 +1,14 @@
+use proc_macro_hack::proc_macro_hack;
+
+mod backend;
+
+pub mod core;
+pub mod prelude;
+pub mod ui;
+
+pub use backend::Terminal;
+pub use native_core;
+pub use style;
+
+#[proc_macro_hack]
+pub use macros::styles;
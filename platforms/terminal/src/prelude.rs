@@ -0,0 +1,13 @@
+pub mod component {
+    /// Creates a text element, `text!("Hello", "title", ["large"])` with an optional id and
+    /// classes, see [`native_core::text!`].
+    #[macro_export]
+    macro_rules! text {
+        ($($args: tt)*) => {
+            $crate::native_core::text!($crate::Terminal; $($args)*)
+        };
+    }
+    pub use text;
+}
+
+pub use native_core::layout;
@@ -0,0 +1,3 @@
+mod text;
+
+pub use text::Text;
@@ -0,0 +1,157 @@
+use std::{any::Any, borrow::Cow, collections::HashSet};
+
+use style::Unit;
+
+use crate::core::{error::Error, CELL_HEIGHT, CELL_WIDTH};
+
+use native_core::{
+    prelude::{Component, Renderable},
+    raster::Content,
+    Dirty, Node, Point, Rect,
+};
+
+pub struct TextBuilder {
+    pub text: String,
+    pub rect: Rect,
+    pub id: String,
+    pub classes: HashSet<String>,
+}
+
+impl TextBuilder {
+    pub fn new(text: &str) -> TextBuilder {
+        TextBuilder {
+            text: String::from(text),
+            rect: Rect::default(),
+            id: String::new(),
+            classes: HashSet::from(["text".to_string()]),
+        }
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = String::from(text);
+        self
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        self.rect.right = width;
+        self.rect.bottom = height;
+        self
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = String::from(id);
+        self
+    }
+
+    pub fn classes(mut self, classes: Vec<String>) -> Self {
+        self.classes.extend(classes);
+        self
+    }
+
+    pub fn class(mut self, class: &str) -> Self {
+        self.classes.insert(String::from(class));
+        self
+    }
+
+    pub fn build(self) -> Text {
+        Text {
+            text: self.text,
+            rect: self.rect,
+            default_rect: Rect::default(),
+            node: Node::new(self.id, self.classes),
+            initialized: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Text {
+    pub text: String,
+    pub rect: Rect,
+    pub default_rect: Rect,
+    pub node: Node,
+    pub initialized: bool,
+}
+
+impl Text {
+    pub fn new(text: &str) -> Self {
+        TextBuilder::new(text).build()
+    }
+
+    pub fn builder(text: &str) -> TextBuilder {
+        TextBuilder::new(text)
+    }
+
+    /// Replace the displayed text, measuring it again and scheduling a relayout.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = String::from(text);
+        self.measure();
+        self.node.mark(Dirty::CONTENT);
+        self.request_layout();
+    }
+
+    /// Every character takes up one cell.
+    fn measure(&mut self) {
+        self.default_rect =
+            Rect::from([self.text.chars().count() as i32 * CELL_WIDTH, CELL_HEIGHT]);
+    }
+}
+
+impl Component<(), Error> for Text {
+    fn create(&mut self, _data: ()) -> Result<(), Error> {
+        if !self.initialized {
+            self.measure();
+            let dimensions = self.get_styles().0;
+            match dimensions.width {
+                Unit::PX(width) => self.rect.right = width as i32,
+                _ => self.rect.right = self.default_rect.right,
+            }
+            match dimensions.height {
+                Unit::PX(height) => self.rect.bottom = height as i32,
+                _ => self.rect.bottom = self.default_rect.bottom,
+            }
+
+            self.initialized = true;
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for Text {
+    fn show(&mut self) {}
+
+    fn hide(&mut self) {}
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn update(&mut self, rect: Rect) -> Point {
+        self.rect = rect;
+        Point::new(self.rect.right, self.rect.bottom)
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.default_rect
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn content(&self) -> Content<'_> {
+        Content::Text(Cow::Borrowed(&self.text))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
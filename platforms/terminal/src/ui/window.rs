@@ -0,0 +1,500 @@
+use std::{any::Any, collections::VecDeque};
+
+use style::Overflow;
+
+use crate::{
+    core::{
+        buffer::Buffer,
+        cell_center,
+        error::Error,
+        event::{Event, Key},
+        render::render,
+        screen::Screen,
+        CELL_HEIGHT, CELL_WIDTH,
+    },
+    Terminal,
+};
+
+use native_core::{
+    animation::{Clock, SystemClock},
+    layout::{Child, Layout},
+    prelude::{Container, Renderable},
+    window::WindowOptions,
+    Backend, Dirty, Node, NodeId, Point, Rect, Size2D, State, THEME,
+};
+
+#[derive(Default, Debug)]
+struct Hooks {
+    quit: Option<fn(&mut Window) -> bool>,
+    click: Option<fn(&mut Window, &[NodeId])>,
+    key: Option<fn(&mut Window, Key)>,
+}
+
+/// A window filling the terminal. Its rect is in layout pixels, every character cell is
+/// [`CELL_WIDTH`] by [`CELL_HEIGHT`] of them.
+#[derive(Debug)]
+pub struct Window {
+    index: u32,
+    initialized: bool,
+    open: bool,
+    pub title: String,
+    node: Node,
+    pub rect: Rect,
+    pub layout: Layout<(), Error>,
+    /// Furthest point the content reaches, used to limit scrolling.
+    content: Point,
+    buffer: Buffer,
+    events: VecDeque<Event>,
+    last_hit: Vec<NodeId>,
+    hooks: Hooks,
+    clock: Box<dyn Clock>,
+}
+
+impl Window {
+    /// Size the window from its styles. Percentages are resolved against the size of the
+    /// terminal, or the requested size when there is no terminal.
+    fn apply_styles(&mut self) {
+        let dimensions = self.get_styles().0;
+        let requested = self.rect;
+        let viewport = Screen::size()
+            .map(|(columns, rows)| {
+                Rect::from([columns as i32 * CELL_WIDTH, rows as i32 * CELL_HEIGHT])
+            })
+            .unwrap_or(requested);
+        self.rect.right = dimensions.width.as_i32(viewport.width(), requested.width());
+        self.rect.bottom = dimensions
+            .height
+            .as_i32(viewport.height(), requested.height());
+    }
+}
+
+pub struct WindowBuilder {
+    options: WindowOptions<(), Error>,
+    hooks: Hooks,
+    clock: Box<dyn Clock>,
+}
+
+impl Default for WindowBuilder {
+    fn default() -> Self {
+        WindowBuilder {
+            options: WindowOptions {
+                // A standard 80 by 24 terminal
+                rect: Rect::from([80 * CELL_WIDTH, 24 * CELL_HEIGHT]),
+                ..Default::default()
+            },
+            hooks: Hooks::default(),
+            clock: Box::new(SystemClock::default()),
+        }
+    }
+}
+
+impl WindowBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    native_core::window_builder!((), Error);
+
+    /// Size of the window in columns and rows. Replaced by the size of the terminal when run.
+    pub fn cells(self, columns: i32, rows: i32) -> Self {
+        self.size(columns * CELL_WIDTH, rows * CELL_HEIGHT)
+    }
+
+    /// Called when the window is asked to close. Return `false` to keep it open.
+    pub fn on_quit(mut self, callback: fn(&mut Window) -> bool) -> Self {
+        self.hooks.quit = Some(callback);
+        self
+    }
+
+    /// Called with the node ids under the cursor, outermost first, when the window is clicked.
+    pub fn on_click(mut self, callback: fn(&mut Window, &[NodeId])) -> Self {
+        self.hooks.click = Some(callback);
+        self
+    }
+
+    /// Called for every key press before the window scrolls for the arrow and page keys.
+    pub fn on_key(mut self, callback: fn(&mut Window, Key)) -> Self {
+        self.hooks.key = Some(callback);
+        self
+    }
+
+    /// The clock transitions and animations are played against, a
+    /// [`ManualClock`](native_core::animation::ManualClock) to step through frames.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn build(self) -> Window {
+        let parts = self.options.into_parts();
+
+        Window {
+            index: parts.index,
+            initialized: false,
+            open: false,
+            title: parts.title,
+            node: parts.node,
+            rect: parts.rect,
+            layout: parts.layout,
+            content: Point::default(),
+            buffer: Buffer::new(0, 0),
+            events: VecDeque::new(),
+            last_hit: Vec::new(),
+            hooks: self.hooks,
+            clock: self.clock,
+        }
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        WindowBuilder::default().build()
+    }
+}
+
+impl Window {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builder() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: u32) {
+        self.index = index;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The node ids hit by the last click, outermost first.
+    pub fn last_hit(&self) -> &[NodeId] {
+        &self.last_hit
+    }
+
+    /// The cells the window was last rendered to.
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Create the children of the window, lay them out and render them.
+    pub fn build(&mut self) -> Result<(), Error> {
+        if !self.initialized {
+            self.apply_styles();
+            self.init()?;
+            self.update(self.rect);
+            self.node.clean();
+            self.buffer = render(self);
+            self.initialized = true;
+            self.open = true;
+        }
+        Ok(())
+    }
+
+    /// Queue an event to be processed on the next tick.
+    pub fn send(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    /// Process the queued events in order, lay out anything that changed and render the
+    /// window again. Returns whether the window is still open.
+    pub fn tick(&mut self) -> Result<bool, Error> {
+        while let Some(event) = self.events.pop_front() {
+            if !self.open {
+                self.events.clear();
+                break;
+            }
+
+            match event {
+                Event::Resize(columns, rows) => {
+                    self.rect = Rect::from([columns * CELL_WIDTH, rows * CELL_HEIGHT]);
+                    self.update(self.rect);
+                    // Keep the scroll position within the new bounds
+                    self.scroll_window(Point::default());
+                }
+                Event::Click(cell) => {
+                    self.last_hit = self.hit_test(cell_center(cell));
+                    let path = self.last_hit.clone();
+                    self.update_state(State::HOVER, &path);
+                    self.update_state(State::ACTIVE, &path);
+                    self.update_state(State::FOCUS, path.last().copied().as_slice());
+                    if let Some(on_click) = self.hooks.click {
+                        let path = self.last_hit.clone();
+                        on_click(self, &path);
+                    }
+                }
+                Event::KeyPress(key) => {
+                    if let Some(on_key) = self.hooks.key {
+                        on_key(self, key);
+                    }
+
+                    let page = self.rect.height() - CELL_HEIGHT;
+                    match key {
+                        Key::Up => self.scroll_window(Point::new(0, -CELL_HEIGHT)),
+                        Key::Down => self.scroll_window(Point::new(0, CELL_HEIGHT)),
+                        Key::Left => self.scroll_window(Point::new(-CELL_WIDTH, 0)),
+                        Key::Right => self.scroll_window(Point::new(CELL_WIDTH, 0)),
+                        Key::PageUp => self.scroll_window(Point::new(0, -page)),
+                        Key::PageDown => self.scroll_window(Point::new(0, page)),
+                        Key::Home => self.scroll_window(Point::new(0, i32::MIN / 2)),
+                        Key::End => self.scroll_window(Point::new(0, i32::MAX / 2)),
+                        _ => false,
+                    };
+                }
+                Event::Release => {
+                    self.update_state(State::ACTIVE, &[]);
+                }
+                Event::MouseMove(cell) => {
+                    let path = self.hit_test(cell_center(cell));
+                    self.update_state(State::HOVER, &path);
+                }
+                Event::Scroll(cell, rows) => {
+                    let delta = Point::new(0, rows * CELL_HEIGHT);
+                    let point = cell_center(cell) + self.node.scroll();
+                    if !scroll_at(&mut self.layout, point, delta) {
+                        self.scroll_window(delta);
+                    }
+                }
+                Event::Close => {
+                    self.open = match self.hooks.quit {
+                        Some(on_quit) => !on_quit(self),
+                        None => false,
+                    };
+                }
+            }
+        }
+
+        if self.open {
+            self.animate(self.clock.now());
+        }
+        if self.open && (self.node.is_dirty() || self.layout.is_dirty()) {
+            if self.node.dirty().contains(Dirty::CHILDREN) {
+                // Components added after the window was built still need to be created
+                self.init()?;
+            }
+            self.update(self.rect);
+            self.node.clean();
+        }
+
+        if self.open {
+            self.buffer = render(self);
+        }
+        Ok(self.open)
+    }
+
+    /// Scroll the whole window, keeping the content within view.
+    fn scroll_window(&mut self, delta: Point) -> bool {
+        scroll_node(&self.node, delta, self.rect.size(), self.content)
+    }
+}
+
+/// Move the scroll position of a node, clamped so the content still fills the viewport.
+/// Returns whether the position changed.
+fn scroll_node(node: &Node, delta: Point, viewport: Size2D, content: Point) -> bool {
+    let max = Point::new(
+        (content.x - viewport.width).max(0),
+        (content.y - viewport.height).max(0),
+    );
+    let current = node.scroll();
+    let next = Point::new(
+        current.x.saturating_add(delta.x).clamp(0, max.x),
+        current.y.saturating_add(delta.y).clamp(0, max.y),
+    );
+    node.set_scroll(next);
+    next != current
+}
+
+/// Furthest point the children of a layout reach, relative to its container.
+fn extent<Data, Error>(layout: &Layout<Data, Error>) -> Point {
+    layout
+        .children
+        .iter()
+        .map(|child| match child {
+            Child::Component(component) => *component.borrow().rect(),
+            Child::Container(container) => *container.borrow().rect(),
+        })
+        .fold(Point::default(), |max, rect| {
+            Point::new(max.x.max(rect.right), max.y.max(rect.bottom))
+        })
+}
+
+/// Scroll the innermost `overflow: scroll` container under a point relative to the layout.
+/// Returns whether a container scrolled.
+fn scroll_at<Data, Error>(layout: &mut Layout<Data, Error>, point: Point, delta: Point) -> bool {
+    for child in layout.children.iter().rev() {
+        if let Child::Container(container) = child {
+            let container = &mut *container.borrow_mut();
+            let rect = *container.rect();
+            if !rect.contains(point) {
+                continue;
+            }
+
+            let inner = point - rect.origin() + container.node().scroll();
+            if scroll_at(container.layout(), inner, delta) {
+                return true;
+            }
+
+            let dimensions = container.get_styles().0;
+            let delta = Point::new(
+                match dimensions.overflow_x {
+                    Overflow::Scroll => delta.x,
+                    _ => 0,
+                },
+                match dimensions.overflow_y {
+                    Overflow::Scroll => delta.y,
+                    _ => 0,
+                },
+            );
+            if delta != Point::default() {
+                let content = extent(container.layout());
+                return scroll_node(container.node(), delta, rect.size(), content);
+            }
+        }
+    }
+    false
+}
+
+impl Container<(), Error> for Window {
+    fn layout(&mut self) -> &mut Layout<(), Error> {
+        &mut self.layout
+    }
+
+    fn init(&mut self) -> Result<(), Error> {
+        for child in self.layout.children.iter() {
+            match child {
+                Child::Component(component) => {
+                    let component = &mut *component.borrow_mut();
+                    Terminal::create_component(component, ())?;
+                }
+                Child::Container(container) => {
+                    container.borrow_mut().init()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Renderable for Window {
+    fn update(&mut self, rect: Rect) -> Point {
+        self.animate(self.clock.now());
+        self.layout.set_scopes(&self.node.child_scopes());
+        self.layout.set_ancestors(&[self.keys()]);
+        let media = THEME.media(&rect, self.layout.scale());
+        self.node.set_media(media);
+        self.layout.set_media(media);
+        self.rect = rect;
+        let (dimensions, appearance) = self.get_styles();
+        self.layout.set_parent_style(Some((dimensions, appearance)));
+        self.content = self
+            .layout
+            .update(&Rect::from([rect.width(), rect.height()]), &dimensions);
+        self.content
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn restyle(&mut self) {
+        self.node.invalidate();
+        self.layout.restyle();
+    }
+
+    fn rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn default_rect(&self) -> &Rect {
+        &self.rect
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn show(&mut self) {}
+
+    fn hide(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::{component, layout},
+        ui::component::Text,
+    };
+
+    use super::*;
+
+    #[test]
+    fn render_reads_back_cells() {
+        let mut window = Window::builder()
+            .cells(12, 3)
+            .layout(layout![
+                component::text!("Hello", "first"),
+                component::text!("World", "second")
+            ])
+            .build();
+        Terminal::create_window(&mut window, 0).unwrap();
+        {
+            let first = window.find_by_id("first").unwrap();
+            let mut first = first.downcast_mut::<Text>().unwrap();
+            first.set_style("background-color", "#00f").unwrap();
+            first.set_style("width", "48px").unwrap();
+        }
+        window.tick().unwrap();
+
+        assert_eq!(
+            window.buffer().lines(),
+            vec!["Hello       ", "   World    ", "            "]
+        );
+        let background = window.buffer().cell(5, 0).unwrap().background.unwrap();
+        assert_eq!(
+            (background.red, background.green, background.blue),
+            (0, 0, 255)
+        );
+        assert_eq!(window.buffer().cell(6, 0).unwrap().background, None);
+    }
+
+    #[test]
+    fn keys_and_wheel_scroll_overflowing_content() {
+        let mut window = Window::builder()
+            .cells(5, 2)
+            .layout(layout![
+                component::text!("one"),
+                component::text!("two"),
+                component::text!("three")
+            ])
+            .build();
+        Terminal::create_window(&mut window, 0).unwrap();
+        assert_eq!(window.buffer().lines(), vec![" one ", " two "]);
+
+        window.send(Event::KeyPress(Key::Down));
+        window.tick().unwrap();
+        assert_eq!(window.buffer().lines(), vec![" two ", "three"]);
+
+        // Scrolling stops at the end of the content
+        window.send(Event::Scroll(Point::new(0, 0), 3));
+        window.tick().unwrap();
+        assert_eq!(window.node.scroll(), Point::new(0, CELL_HEIGHT));
+
+        window.send(Event::KeyPress(Key::Home));
+        window.tick().unwrap();
+        assert_eq!(window.buffer().line(0), " one ");
+    }
+}
@@ -1,6 +1,6 @@
 extern crate skylight;
 
-use native_core::STYLESHEET;
+use native_core::THEME;
 use skylight::{
     styles,
     core::constants::HS,
@@ -12,7 +12,7 @@ use skylight::{
 };
 
 fn main() {
-    STYLESHEET.0.write().unwrap().dup(styles!(
+    THEME.set_style(styles!(
         window {
             padding: 2px;
         }
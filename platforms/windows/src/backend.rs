@@ -0,0 +1,55 @@
+use std::{cell::RefCell, sync::Arc};
+
+use native_core::{layout::Child, Backend};
+use windows::Win32::Foundation::{HMODULE, HWND};
+
+use crate::{
+    core::error::Error,
+    ui::{self, component::Text, popup::MessageBox, Window},
+};
+
+/// The Win32 backend.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Skylight;
+
+impl Backend for Skylight {
+    type Data = (HWND, HMODULE);
+    type Error = Error;
+    type Window = Window;
+
+    fn text(text: &str, id: Option<&str>, classes: Vec<&str>) -> Child<(HWND, HMODULE), Error> {
+        let mut text =
+            Text::builder(text).classes(classes.iter().map(|c| format!(".{}", c)).collect());
+
+        if let Some(id) = id {
+            text = text.id(format!("#{}", id).as_str());
+        }
+
+        Child::Component(Arc::new(RefCell::new(text.build())))
+    }
+
+    fn create_window(window: &mut Window, index: u32) -> Result<(), Error> {
+        window.set_index(index);
+        window.build()?;
+        window.alive = true;
+        Ok(())
+    }
+
+    fn run(windows: Vec<Window>) -> Result<(), Error> {
+        ui::run(windows)
+    }
+
+    fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+        ui::tick(windows)
+    }
+
+    fn message(title: &str, message: &str, buttons: &str, icon: &str) -> u8 {
+        MessageBox::is_success(MessageBox::new(
+            None,
+            title,
+            message,
+            buttons.into(),
+            icon.into(),
+        ))
+    }
+}
@@ -56,9 +56,11 @@ pub mod HS {
 pub mod WM {
     //! Window Message
     //! Direct mapping of window message constants from the windows api
+    use windows::Win32::UI::Controls::WM_MOUSELEAVE;
     use windows::Win32::UI::WindowsAndMessaging::{
-        WM_CLOSE, WM_CREATE, WM_DESTROY, WM_ERASEBKGND, WM_HSCROLL, WM_MOUSEHWHEEL, WM_MOUSEWHEEL,
-        WM_NCPAINT, WM_PAINT, WM_SIZE, WM_VSCROLL,
+        WM_CLOSE, WM_CREATE, WM_DESTROY, WM_DPICHANGED, WM_ERASEBKGND, WM_HSCROLL, WM_LBUTTONDOWN,
+        WM_LBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_NCPAINT, WM_PAINT, WM_SIZE,
+        WM_TIMER, WM_VSCROLL,
     };
 
     pub const CREATE: u32 = WM_CREATE;
@@ -73,6 +75,12 @@ pub mod WM {
     pub const HSCROLL: u32 = WM_HSCROLL;
     pub const MOUSEWHEEL: u32 = WM_MOUSEWHEEL;
     pub const MOUSEHWHEEL: u32 = WM_MOUSEHWHEEL;
+    pub const DPICHANGED: u32 = WM_DPICHANGED;
+    pub const MOUSEMOVE: u32 = WM_MOUSEMOVE;
+    pub const LBUTTONDOWN: u32 = WM_LBUTTONDOWN;
+    pub const LBUTTONUP: u32 = WM_LBUTTONUP;
+    pub const TIMER: u32 = WM_TIMER;
+    pub const MOUSELEAVE: u32 = WM_MOUSELEAVE;
 
     pub fn preview(message: u32) -> &'static str {
         match message {
@@ -85,6 +93,12 @@ pub mod WM {
             DESTROY => "DESTROY",
             VSCROLL => "VSCROLL",
             HSCROLL => "HSCROLL",
+            DPICHANGED => "DPICHANGED",
+            MOUSEMOVE => "MOUSEMOVE",
+            LBUTTONDOWN => "LBUTTONDOWN",
+            LBUTTONUP => "LBUTTONUP",
+            TIMER => "TIMER",
+            MOUSELEAVE => "MOUSELEAVE",
             _ => "UNKOWN",
         }
     }
@@ -8,10 +8,14 @@ use std::fmt::Debug;
 use native_core::Rect;
 
 use windows::Win32::{
-    Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
-    Graphics::Gdi::{GetDC, GetTextMetricsW, TEXTMETRICW},
+    Foundation::{HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
+    Graphics::Gdi::{
+        GetDC, GetMonitorInfoW, GetTextMetricsW, MonitorFromPoint, MONITORINFO,
+        MONITOR_DEFAULTTOPRIMARY, TEXTMETRICW,
+    },
     UI::{
         Controls::STATE_SYSTEM_INVISIBLE,
+        HiDpi::{GetDpiForSystem, GetDpiForWindow},
         WindowsAndMessaging::{
             DefWindowProcW, GetWindowLongPtrW, GetWindowLongW, SetWindowLongPtrW, CREATESTRUCTW,
             GWLP_USERDATA, GWL_STYLE,
@@ -36,6 +40,42 @@ pub const fn hiword(x: u32) -> u32 {
     (x >> 16) & 0xFFFF
 }
 
+/// DPI windows treat as a scale of 1, a logical pixel.
+pub const BASE_DPI: f32 = 96.;
+
+/// Device pixels per logical pixel for the monitor the window is on.
+pub fn scale_factor(handle: HWND) -> f32 {
+    match unsafe { GetDpiForWindow(handle) } {
+        0 => system_scale_factor(),
+        dpi => dpi as f32 / BASE_DPI,
+    }
+}
+
+/// Device pixels per logical pixel for the primary monitor.
+pub fn system_scale_factor() -> f32 {
+    match unsafe { GetDpiForSystem() } {
+        0 => 1.,
+        dpi => dpi as f32 / BASE_DPI,
+    }
+}
+
+/// Usable area of the primary monitor, excluding the taskbar, in device pixels.
+pub fn work_area() -> Rect {
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+
+    unsafe {
+        let monitor = MonitorFromPoint(POINT::default(), MONITOR_DEFAULTTOPRIMARY);
+        if !GetMonitorInfoW(monitor, &mut info as *mut MONITORINFO).as_bool() {
+            return Rect::new(0, 0, 1900, 1000);
+        }
+    }
+
+    to_Rect(info.rcWork)
+}
+
 pub struct CharInfo {
     pub width: i32,
     pub upper: i32,
@@ -1,7 +1,7 @@
 use std::mem::size_of;
 
 use crate::core::constants::SB;
-use native_core::Rect;
+use native_core::{Point, Rect};
 use style::{Dimensions, Overflow};
 use windows::Win32::{
     Foundation::{HWND, WPARAM},
@@ -13,7 +13,7 @@ use windows::Win32::{
     },
 };
 
-use super::{loword, CharInfo};
+use super::{loword, scale_factor, CharInfo};
 
 pub fn get_scroll_info(handle: HWND, direction: SB::CONSTANTS) -> SCROLLINFO {
     let mut si = SCROLLINFO::default();
@@ -121,11 +121,15 @@ pub fn hscroll(handle: HWND, wparam: WPARAM) {
     }
 }
 
-pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, point: &(i32, i32)) {
+pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, point: &Point) {
     let ci = CharInfo::new(handle);
-    let padding = dimensions.padding.calc(rect.width(), rect.height());
+    let scale = scale_factor(handle);
+    let padding = dimensions
+        .padding
+        .calc(rect.width(), rect.height())
+        .scale(scale);
 
-    let point = (point.0 + padding.3, point.1 + padding.2);
+    let point = point.translate(padding.left, padding.bottom);
 
     match dimensions.overflow_x {
         Overflow::Scroll => {
@@ -133,7 +137,7 @@ pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, poin
 
             si.fMask = SIF_RANGE | SIF_PAGE;
             si.nMin = 0;
-            si.nMax = point.0 / ci.width;
+            si.nMax = point.x / ci.width;
             si.nPage = (rect.width() / ci.width) as u32;
             unsafe { SetScrollInfo(handle, SB::HORZ, &si, true) };
 
@@ -141,12 +145,12 @@ pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, poin
                 ShowScrollBar(handle, SB::HORZ, true);
             }
         }
-        Overflow::Auto if point.0 > rect.right => {
+        Overflow::Auto if point.x > rect.right => {
             let mut si = get_scroll_info(handle, SB::HORZ);
 
             si.fMask = SIF_RANGE | SIF_PAGE;
             si.nMin = 0;
-            si.nMax = point.0 / ci.width;
+            si.nMax = point.x / ci.width;
             si.nPage = (rect.width() / ci.width) as u32;
             unsafe { SetScrollInfo(handle, SB::HORZ, &si, true) };
 
@@ -158,12 +162,12 @@ pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, poin
     }
 
     match dimensions.overflow_y {
-        Overflow::Auto if point.1 > rect.bottom => {
+        Overflow::Auto if point.y > rect.bottom => {
             let mut si = get_scroll_info(handle, SB::VERT);
 
             si.fMask = SIF_RANGE | SIF_PAGE;
             si.nMin = 0;
-            si.nMax = point.1 / ci.height;
+            si.nMax = point.y / ci.height;
             si.nPage = (rect.height() / ci.height) as u32;
             unsafe { SetScrollInfo(handle, SB::VERT, &si, true) };
 
@@ -176,7 +180,7 @@ pub fn resize_scrollbars(handle: HWND, rect: &Rect, dimensions: Dimensions, poin
 
             si.fMask = SIF_RANGE | SIF_PAGE;
             si.nMin = 0;
-            si.nMax = point.1 / ci.height;
+            si.nMax = point.y / ci.height;
             si.nPage = (rect.height() / ci.height) as u32;
             unsafe { SetScrollInfo(handle, SB::VERT, &si, true) };
 
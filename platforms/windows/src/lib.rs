@@ -1,9 +1,14 @@
 use proc_macro_hack::proc_macro_hack;
 
+mod backend;
+
 pub mod ui;
 pub mod core;
 pub mod prelude;
 
+pub use backend::Skylight;
+
+pub use native_core;
 pub use style;
 
 #[proc_macro_hack]
@@ -27,13 +27,19 @@ pub fn text_size(parent: HWND, text: String) -> Rect {
     to_Rect(rect)
 }
 
-pub fn padding_rect(control: &impl Renderable, rect: &mut RECT) {
-    // Top, right, bottom, left
-    let ns = control.rect();
-    let padding = control.get_styles().0.padding.calc(ns.width(), ns.height());
+/// Shrink `rect` by the control's padding. Padding is resolved in logical pixels and
+/// converted to device pixels with `scale`.
+pub fn padding_rect(control: &impl Renderable, rect: &mut RECT, scale: f32) {
+    let ns = control.rect().scale(1. / scale);
+    let padding = control
+        .get_styles()
+        .0
+        .padding
+        .calc(ns.width(), ns.height())
+        .scale(scale);
 
-    rect.top += padding.0;
-    rect.right -= padding.1;
-    rect.bottom -= padding.2;
-    rect.left += padding.3;
+    rect.top += padding.top;
+    rect.right -= padding.right;
+    rect.bottom -= padding.bottom;
+    rect.left += padding.left;
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::{any::Any, borrow::Cow, collections::HashSet};
 
 use style::{Appearance, Dimensions, Unit};
 use windows::{
@@ -6,11 +6,13 @@ use windows::{
     Win32::{
         Foundation::{HMODULE, HWND, LPARAM, RECT, WPARAM},
         Graphics::Gdi::{
-            BeginPaint, DrawTextW, EndPaint, GetDC, SetBkMode, PAINTSTRUCT, TRANSPARENT,
+            BeginPaint, DrawTextW, EndPaint, GetDC, InvalidateRect, SetBkMode, PAINTSTRUCT,
+            TRANSPARENT,
         },
         UI::WindowsAndMessaging::{
-            CreateWindowExW, GetClientRect, SendMessageW, SetWindowLongPtrW, SetWindowPos,
-            ShowWindow, GWL_WNDPROC, SWP_SHOWWINDOW, SW_HIDE, SW_SHOW,
+            CreateWindowExW, GetClientRect, GetParent, PostMessageW, SendMessageW,
+            SetWindowLongPtrW, SetWindowPos, ShowWindow, GWL_WNDPROC, SWP_SHOWWINDOW, SW_HIDE,
+            SW_SHOW,
         },
     },
 };
@@ -18,13 +20,15 @@ use windows::{
 use crate::core::{
     constants::{DT, WM, WS},
     error::Error,
+    scale_factor,
     scroll::{init_scroll, resize_scrollbars},
     to_RECT, wndproc, Proc, ProcResult,
 };
 
 use native_core::{
     prelude::{Component, Renderable},
-    Rect,
+    raster::Content,
+    Dirty, Node, Point, Rect,
 };
 
 use super::helpers::{padding_rect, text_size};
@@ -83,8 +87,7 @@ impl TextBuilder {
             text: self.text,
             rect: self.rect,
             default_rect: Rect::default(),
-            id: self.id,
-            classes: self.classes,
+            node: Node::new(self.id, self.classes),
             initialized: false,
         }
     }
@@ -96,8 +99,7 @@ pub struct Text {
     pub text: HSTRING,
     pub rect: Rect,
     pub default_rect: Rect,
-    pub id: String,
-    pub classes: HashSet<String>,
+    pub node: Node,
     pub initialized: bool,
 }
 
@@ -112,7 +114,7 @@ impl Proc for Text {
                 let mut rect: RECT = to_RECT(self.rect);
                 GetClientRect(hwnd, &mut rect as *mut RECT);
 
-                padding_rect(self, &mut rect);
+                padding_rect(self, &mut rect, scale_factor(self.handle));
 
                 if rect.right > rect.left && rect.bottom > rect.top {
                     let mut ps = PAINTSTRUCT {
@@ -152,8 +154,7 @@ impl Text {
             text: HSTRING::from(text),
             rect: Rect::default(),
             default_rect: Rect::default(),
-            id: String::new(),
-            classes: HashSet::from(["text".to_string()]),
+            node: Node::new(String::new(), HashSet::from(["text".to_string()])),
             initialized: false,
         }
     }
@@ -161,6 +162,22 @@ impl Text {
     pub fn builder(text: &str) -> TextBuilder {
         TextBuilder::new(text)
     }
+
+    /// Replace the displayed text, measuring it again and scheduling a relayout.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = HSTRING::from(text);
+        self.measure();
+        self.node.mark(Dirty::CONTENT);
+        self.request_layout();
+    }
+
+    /// Measure the text, storing its natural size in logical pixels.
+    fn measure(&mut self) {
+        if self.handle.0 != 0 {
+            self.default_rect = text_size(self.handle, self.text.to_string_lossy())
+                .scale(1. / scale_factor(self.handle));
+        }
+    }
 }
 
 impl Component<(HWND, HMODULE), Error> for Text {
@@ -196,18 +213,18 @@ impl Component<(HWND, HMODULE), Error> for Text {
                 return Err("Failed to create new Text".into());
             }
 
-            self.default_rect = text_size(self.handle, self.text.to_string_lossy());
+            self.measure();
             let (dimensions, appearance) = self.get_styles();
             match dimensions.width {
                 Unit::PX(width) => self.rect.right = width as i32,
                 _ => self.rect.right = self.default_rect.right,
             }
-            match dimensions.width {
+            match dimensions.height {
                 Unit::PX(height) => self.rect.bottom = height as i32,
                 _ => self.rect.bottom = self.default_rect.bottom,
             }
 
-            self.update(self.rect);
+            self.update(self.rect.scale(scale_factor(self.handle)));
             self.initialized = true;
         }
         Ok(())
@@ -231,7 +248,7 @@ impl Renderable for Text {
         &self.rect
     }
 
-    fn update(&mut self, rect: Rect) -> (i32, i32) {
+    fn update(&mut self, rect: Rect) -> Point {
         self.rect = rect;
 
         unsafe {
@@ -246,18 +263,46 @@ impl Renderable for Text {
             );
         }
 
-        (self.rect.right.clone(), self.rect.bottom.clone())
+        Point::new(self.rect.right, self.rect.bottom)
     }
 
     fn default_rect(&self) -> &Rect {
         &self.default_rect
     }
 
-    fn classes(&self) -> &HashSet<String> {
-        &self.classes
+    fn node(&self) -> &Node {
+        &self.node
     }
 
-    fn id(&self) -> &String {
-        &self.id
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
+    }
+
+    fn rescale(&mut self, _scale: f32) {
+        self.measure();
+        self.node.mark(Dirty::CONTENT);
+    }
+
+    fn request_layout(&mut self) {
+        if self.handle.0 == 0 {
+            return;
+        }
+
+        unsafe {
+            InvalidateRect(self.handle, None, true);
+            PostMessageW(GetParent(self.handle), WM::SIZE, WPARAM(0), LPARAM(0));
+        }
+    }
+
+    fn content(&self) -> Content<'_> {
+        Content::Text(Cow::Owned(self.text.to_string_lossy()))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
     }
 }
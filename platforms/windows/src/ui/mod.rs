@@ -1,5 +1,7 @@
 use native_core::prelude::Renderable;
-use windows::Win32::UI::WindowsAndMessaging::{DispatchMessageA, GetMessageA, MSG};
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageA, GetMessageA, PeekMessageA, MSG, PM_REMOVE,
+};
 mod window;
 mod brush;
 
@@ -22,8 +24,28 @@ pub fn run(mut windows: Vec<Window>) -> Result<(), Error> {
 
         while windows.iter().any(|e| e.alive) {
             GetMessageA(&mut message, None, 0, 0);
+            for window in windows.iter_mut() {
+                window.create_children()?;
+                window.forward_pointer(&message);
+            }
             DispatchMessageA(&message);
         }
     }
     Ok(())
 }
+
+/// Dispatch the messages that are already queued without waiting for new ones.
+/// The windows are expected to be built already.
+pub fn tick(windows: &mut [Window]) -> Result<bool, Error> {
+    unsafe {
+        let mut message = MSG::default();
+        while PeekMessageA(&mut message, None, 0, 0, PM_REMOVE).as_bool() {
+            for window in windows.iter_mut() {
+                window.create_children()?;
+                window.forward_pointer(&message);
+            }
+            DispatchMessageA(&message);
+        }
+    }
+    Ok(windows.iter().any(|e| e.alive))
+}
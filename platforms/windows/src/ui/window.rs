@@ -1,10 +1,12 @@
-use std::collections::HashSet;
+use std::{any::Any, collections::HashSet};
 
 use windows::{
     core::{HSTRING, PCWSTR},
     Win32::Foundation::*,
     Win32::Graphics::Gdi::*,
     Win32::System::LibraryLoader::GetModuleHandleW,
+    Win32::UI::HiDpi::{SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2},
+    Win32::UI::Input::KeyboardAndMouse::{TrackMouseEvent, TME_LEAVE, TRACKMOUSEEVENT},
     Win32::UI::WindowsAndMessaging::*,
 };
 
@@ -13,19 +15,23 @@ pub use windows::{s as pcstr, w as pwstr};
 
 use crate::{
     core::{
-        constants::{CS, WM, WS},
+        constants::{CS, SB, WM, WS},
         error::{Error, WinError},
         image::icon,
-        scroll::{init_scroll, resize_scrollbars},
-        to_RECT, to_Rect, wndproc, Proc, ProcResult,
+        scroll::{get_scroll_info, init_scroll, resize_scrollbars},
+        hiword, loword, scale_factor, system_scale_factor, to_RECT, to_Rect, work_area, wndproc,
+        CharInfo, Proc, ProcResult, BASE_DPI,
     },
     ui::Brush,
+    Skylight,
 };
 
 use native_core::{
+    animation::{Clock, SystemClock, FRAME},
     layout::{Child, Layout},
     prelude::{Renderable, Container},
-    Rect,
+    window::WindowOptions,
+    Backend, Dirty, Node, Point, Rect, State, THEME,
 };
 
 pub enum HookType {
@@ -55,15 +61,14 @@ impl Default for WindowStyles {
 #[derive(Debug)]
 pub struct Window {
     index: u32,
-    max_point: (i32, i32),
+    max_point: Point,
     initialized: bool,
     pub alive: bool,
     pub icon: Option<&'static str>,
 
     pub title: HSTRING,
     pub background: HBRUSH,
-    id: String,
-    classes: HashSet<String>,
+    node: Node,
 
     pub handle: HWND,
     pub instance: HMODULE,
@@ -73,11 +78,18 @@ pub struct Window {
 
     pub layout: Layout<(HWND, HMODULE), Error>,
     hooks: Hooks,
+    clock: SystemClock,
 }
 
 impl Proc for Window {
-    fn proc(&mut self, _handle: HWND, msg: u32, wparam: WPARAM, _lparam: LPARAM) -> ProcResult {
+    fn proc(&mut self, _handle: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> ProcResult {
         if self.scroll(self.handle, msg, wparam) {
+            // Track the scroll position in pixels so hit testing can offset the content
+            let ci = CharInfo::new(self.handle);
+            self.node.set_scroll(Point::new(
+                get_scroll_info(self.handle, SB::HORZ).nPos * ci.width,
+                get_scroll_info(self.handle, SB::VERT).nPos * ci.height,
+            ));
             return ProcResult::Success;
         }
 
@@ -101,6 +113,79 @@ impl Proc for Window {
                 ProcResult::Success
             }
 
+            // Moved to a monitor with a different DPI
+            WM::DPICHANGED => {
+                // Windows suggests a rect that keeps the window the same logical size
+                let suggested = unsafe { *(lparam.0 as *const RECT) };
+                unsafe {
+                    SetWindowPos(
+                        self.handle,
+                        None,
+                        suggested.left,
+                        suggested.top,
+                        suggested.right - suggested.left,
+                        suggested.bottom - suggested.top,
+                        SWP_NOZORDER | SWP_NOACTIVATE,
+                    );
+                }
+                self.set_scale(hiword(wparam.0 as u32) as f32 / BASE_DPI);
+                ProcResult::Success
+            }
+
+            // Pointer input drives the :hover, :active and :focus states of the elements. Input
+            // over child controls is forwarded here by the message loop in client coordinates.
+            WM::MOUSEMOVE | WM::LBUTTONDOWN | WM::LBUTTONUP => {
+                let point = Point::new(
+                    loword(lparam.0 as u32) as i16 as i32,
+                    hiword(lparam.0 as u32) as i16 as i32,
+                );
+                if msg == WM::MOUSEMOVE {
+                    track_leave(self.handle);
+                }
+                let path = self.hit_test(point);
+                let mut changed = self.update_state(State::HOVER, &path);
+                match msg {
+                    WM::LBUTTONDOWN => {
+                        changed |= self.update_state(State::ACTIVE, &path);
+                        changed |= self.update_state(State::FOCUS, path.last().copied().as_slice());
+                    }
+                    WM::LBUTTONUP => changed |= self.update_state(State::ACTIVE, &[]),
+                    _ => (),
+                }
+                if changed {
+                    self.request_layout();
+                }
+                ProcResult::Default
+            }
+
+            // The pointer left the window or one of its child controls. Moving between the
+            // window and its children keeps the hover, it only clears once the pointer is over
+            // another window.
+            WM::MOUSELEAVE => {
+                let mut cursor = POINT::default();
+                let inside = unsafe {
+                    GetCursorPos(&mut cursor);
+                    let hovered = WindowFromPoint(cursor);
+                    hovered == self.handle || IsChild(self.handle, hovered).as_bool()
+                };
+                if !inside && self.update_state(State::HOVER, &[]) {
+                    self.request_layout();
+                }
+                ProcResult::Success
+            }
+
+            // Next frame of the running transitions and animations
+            WM::TIMER => {
+                if self.is_animating() {
+                    self.request_layout();
+                } else {
+                    unsafe {
+                        KillTimer(self.handle, 1);
+                    }
+                }
+                ProcResult::Success
+            }
+
             // Redraw base window. This is just redrawing the background color
             WM::ERASEBKGND | WM::PAINT => unsafe {
                 // Redraw the window background when an erase background event occurs
@@ -138,22 +223,29 @@ impl Proc for Window {
 }
 
 impl Window {
+    /// Size the window from its styles. Sizes are resolved in logical pixels against the work
+    /// area of the primary monitor and then converted to device pixels.
     fn apply_styles(&mut self) -> Result<(), Error> {
         let (dimensions, appearance) = self.get_styles();
-        self.rect.right = dimensions.width.as_i32(
-            1900,
+        let scale = system_scale_factor();
+        let screen = work_area().scale(1. / scale);
+
+        let width = dimensions.width.as_i32(
+            screen.width(),
             match self.rect.width() {
                 0 => 400,
                 _ => self.rect.width(),
             },
         );
-        self.rect.bottom = dimensions.height.as_i32(
-            1000,
-            match self.rect.width() {
+        let height = dimensions.height.as_i32(
+            screen.height(),
+            match self.rect.height() {
                 0 => 300,
                 _ => self.rect.height(),
             },
         );
+        self.rect = Rect::from([width, height]).scale(scale);
+        self.layout.set_scale(scale);
 
         self.background = match appearance.background_color {
             Some(color) => Brush::solid(color),
@@ -166,78 +258,33 @@ impl Window {
 }
 
 pub struct WindowBuilder {
-    index: u32,
-    id: String,
-    classes: HashSet<String>,
-    title: HSTRING,
+    options: WindowOptions<(HWND, HMODULE), Error>,
     background: HBRUSH,
-    rect: Rect,
     class: HSTRING,
     styles: WindowStyles,
     icon: Option<&'static str>,
-    layout: Layout<(HWND, HMODULE), Error>,
     hooks: Hooks,
 }
 
 impl WindowBuilder {
     pub fn new() -> Self {
         WindowBuilder {
-            index: 0,
-            id: String::new(),
-            classes: HashSet::from(["window".to_string()]),
-            title: HSTRING::new(),
-            rect: Rect::from([400, 300]),
+            options: WindowOptions::default(),
             class: HSTRING::new(),
             styles: WindowStyles::default(),
             background: unsafe { CreateSolidBrush(COLORREF(hex("FFF").into())) },
             icon: None,
-            layout: Layout::new(),
             hooks: Hooks::default(),
         }
     }
 
-    pub fn index(mut self, index: u32) -> Self {
-        self.index = index;
-        self
-    }
+    native_core::window_builder!((HWND, HMODULE), Error);
 
     pub fn icon(mut self, path: &'static str) -> Self {
         self.icon = Some(path);
         self
     }
 
-    pub fn size(mut self, width: i32, height: i32) -> Self {
-        self.rect.right = width;
-        self.rect.bottom = height;
-        self
-    }
-
-    pub fn title(mut self, title: &str) -> Self {
-        self.title = HSTRING::from(title);
-        self
-    }
-
-    pub fn classes(mut self, classes: Vec<&str>) -> Self {
-        self.classes.extend(
-            classes
-                .iter()
-                .map(|c| match c.starts_with(".") {
-                    true => c.to_string(),
-                    false => format!(".{}", c),
-                })
-                .collect::<Vec<String>>(),
-        );
-        self
-    }
-
-    pub fn class(mut self, class: &str) -> Self {
-        self.classes.insert(match class.starts_with("#") {
-            true => class.to_string(),
-            false => format!(".{}", class),
-        });
-        self
-    }
-
     pub fn background(mut self, brush: HBRUSH) -> Self {
         self.background = brush;
         self
@@ -251,38 +298,26 @@ impl WindowBuilder {
         self
     }
 
-    pub fn layout(mut self, layout: Layout<(HWND, HMODULE), Error>) -> Self {
-        self.layout = layout;
-        self
-    }
-
-    pub fn id(mut self, id: &str) -> Self {
-        self.id = match id.starts_with("#") {
-            true => id.to_string(),
-            false if id.trim() != "" => format!("#{}", id),
-            _ => String::new(),
-        };
-        self
-    }
-
     pub fn build(self) -> Window {
+        let parts = self.options.into_parts();
+
         Window {
-            index: self.index,
+            index: parts.index,
             initialized: false,
-            id: self.id,
-            classes: self.classes,
-            max_point: (self.rect.width(), self.rect.height()),
+            node: parts.node,
+            max_point: Point::new(parts.rect.width(), parts.rect.height()),
             background: self.background,
             handle: HWND(0),
             instance: HMODULE(0),
-            title: self.title,
+            title: HSTRING::from(parts.title),
             class: self.class,
             styles: self.styles,
             alive: false,
             icon: self.icon,
-            rect: self.rect,
-            layout: self.layout,
+            rect: parts.rect,
+            layout: parts.layout,
             hooks: self.hooks,
+            clock: SystemClock::default(),
         }
     }
 
@@ -300,9 +335,8 @@ impl Window {
         Window {
             index: 0,
             initialized: false,
-            id: String::new(),
-            classes: HashSet::from(["window".to_string()]),
-            max_point: (0, 0),
+            node: Node::new(String::new(), HashSet::from(["window".to_string()])),
+            max_point: Point::default(),
             title: HSTRING::new(),
             background: unsafe { CreateSolidBrush(COLORREF(hex("FFF").into())) },
             class: HSTRING::new(),
@@ -314,11 +348,25 @@ impl Window {
             icon: None,
             rect: Rect::new(0, 0, 400, 300),
             layout: Layout::new(),
+            clock: SystemClock::default(),
+        }
+    }
+
+    /// Change the scale factor the window's content is laid out with.
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale != self.layout.scale() {
+            self.layout.set_scale(scale);
+            self.restyle();
         }
     }
 
     pub fn build(&mut self) -> Result<(), Error> {
         if !self.initialized {
+            unsafe {
+                // Opt out of bitmap stretching so text and controls stay sharp on high DPI
+                // monitors. Layout handles the scaling instead.
+                SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+            }
             self.apply_styles().unwrap();
             self.init().unwrap();
             self.show();
@@ -327,6 +375,46 @@ impl Window {
         Ok(())
     }
 
+    /// Child controls receive the pointer input over them, so pass it on to the window to hit
+    /// test the layout, with the point mapped to the client area of the window. Messages of
+    /// other windows are ignored.
+    pub(crate) fn forward_pointer(&self, message: &MSG) {
+        if message.hwnd == self.handle || !unsafe { IsChild(self.handle, message.hwnd) }.as_bool() {
+            return;
+        }
+        match message.message {
+            WM::MOUSEMOVE | WM::LBUTTONDOWN | WM::LBUTTONUP => unsafe {
+                let mut point = [POINT {
+                    x: loword(message.lParam.0 as u32) as i16 as i32,
+                    y: hiword(message.lParam.0 as u32) as i16 as i32,
+                }];
+                MapWindowPoints(message.hwnd, self.handle, &mut point);
+                if message.message == WM::MOUSEMOVE {
+                    track_leave(message.hwnd);
+                }
+                let lparam = (point[0].x as u16 as u32) | ((point[0].y as u16 as u32) << 16);
+                SendMessageA(self.handle, message.message, message.wParam, LPARAM(lparam as isize));
+            },
+            WM::MOUSELEAVE => unsafe {
+                SendMessageA(self.handle, WM::MOUSELEAVE, WPARAM(0), LPARAM(0));
+            },
+            _ => (),
+        }
+    }
+
+    /// Create the controls of components added after the window was created, nested layouts
+    /// included. Returns the error of the first one that fails.
+    pub fn create_children(&mut self) -> Result<(), Error> {
+        let added = self.layout.is_dirty() || self.node.dirty().contains(Dirty::CHILDREN);
+        if !self.initialized || !added {
+            return Ok(());
+        }
+
+        create_components(&mut self.layout, (self.handle, self.instance))?;
+        self.node.unmark(Dirty::CHILDREN);
+        Ok(())
+    }
+
     pub fn builder() -> WindowBuilder {
         WindowBuilder::new()
     }
@@ -340,6 +428,8 @@ impl Window {
 
             while self.alive {
                 GetMessageA(&mut message, self.handle, 0, 0);
+                self.create_children()?;
+                self.forward_pointer(&message);
                 DispatchMessageA(&message);
             }
         }
@@ -404,12 +494,13 @@ impl Container<(HWND, HMODULE), Error> for Window {
             if self.handle.0 == 0 {
                 return Err("Failed to create new window".into());
             }
+            self.layout.set_scale(scale_factor(self.handle));
 
             for child in self.layout.children.iter() {
                 match child {
                     Child::Component(component) => {
                         let component = &mut *component.borrow_mut();
-                        component.create((self.handle.clone(), self.instance.clone()))?;
+                        Skylight::create_component(component, (self.handle, self.instance))?;
                     }
                     Child::Container(container) => {
                         container.borrow_mut().init()?;
@@ -418,23 +509,67 @@ impl Container<(HWND, HMODULE), Error> for Window {
             }
         }
 
-        self.max_point = (self.rect.width(), self.rect.height());
+        self.max_point = Point::new(self.rect.width(), self.rect.height());
         Ok(())
     }
 }
 
 impl Renderable for Window {
-    fn update(&mut self, rect: Rect) -> (i32, i32) {
-        let dimensions = self.get_styles().0.clone();
-        self.layout.update(&rect, &dimensions)
+    fn update(&mut self, rect: Rect) -> Point {
+        self.animate(self.clock.now());
+        self.layout.set_scopes(&self.node.child_scopes());
+        self.layout.set_ancestors(&[self.keys()]);
+        let media = THEME.media(&rect, self.layout.scale());
+        self.node.set_media(media);
+        self.layout.set_media(media);
+        let (dimensions, appearance) = self.get_styles();
+        self.layout.set_parent_style(Some((dimensions, appearance)));
+        let scale = self.layout.scale();
+        let max = self
+            .layout
+            .update(&rect.scale(1. / scale), &dimensions)
+            .scale(scale);
+
+        // Keep a frame timer running while something animates
+        if self.handle.0 != 0 {
+            unsafe {
+                if self.is_animating() {
+                    SetTimer(self.handle, 1, FRAME.as_millis() as u32, None);
+                } else {
+                    KillTimer(self.handle, 1);
+                }
+            }
+        }
+        max
+    }
+
+    fn node(&self) -> &Node {
+        &self.node
+    }
+
+    fn node_mut(&mut self) -> &mut Node {
+        &mut self.node
     }
 
-    fn id(&self) -> &String {
-        &self.id
+    fn restyle(&mut self) {
+        self.node.invalidate();
+        self.layout.restyle();
+
+        if let Some(color) = self.get_styles().1.background_color {
+            self.background = Brush::solid(color);
+        }
+        self.request_layout();
     }
 
-    fn classes(&self) -> &HashSet<String> {
-        &self.classes
+    fn request_layout(&mut self) {
+        if self.handle.0 == 0 {
+            return;
+        }
+
+        unsafe {
+            InvalidateRect(self.handle, None, true);
+            PostMessageW(self.handle, WM::SIZE, WPARAM(0), LPARAM(0));
+        }
     }
 
     fn rect(&self) -> &Rect {
@@ -445,6 +580,14 @@ impl Renderable for Window {
         &self.rect
     }
 
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
     fn show(&mut self) {
         unsafe {
             ShowWindow(self.handle, SW_SHOW);
@@ -457,3 +600,37 @@ impl Renderable for Window {
         }
     }
 }
+
+/// Create the controls of the components in `layout` and its nested layouts that don't have
+/// one yet.
+fn create_components(
+    layout: &mut Layout<(HWND, HMODULE), Error>,
+    parent: (HWND, HMODULE),
+) -> Result<(), Error> {
+    for child in layout.children.iter() {
+        match child {
+            Child::Component(component) => {
+                Skylight::create_component(&mut *component.borrow_mut(), parent)?;
+            }
+            Child::Container(container) => {
+                let container = &mut *container.borrow_mut();
+                create_components(container.layout(), parent)?;
+                container.node().unmark(Dirty::CHILDREN);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ask for a `WM_MOUSELEAVE` once the pointer leaves `handle`.
+fn track_leave(handle: HWND) {
+    let mut event = TRACKMOUSEEVENT {
+        cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+        dwFlags: TME_LEAVE,
+        hwndTrack: handle,
+        dwHoverTime: 0,
+    };
+    unsafe {
+        TrackMouseEvent(&mut event);
+    }
+}
@@ -0,0 +1,287 @@
+//! Golden image tests for windows rendered with `native_core::raster`.
+//!
+//! ```ignore
+//! let mut window = Window::builder().size(200, 100).layout(layout![...]).build();
+//! Headless::create_window(&mut window, 0)?;
+//!
+//! assert_snapshot!(window, "settings_panel");
+//! // Allow every channel of a pixel to be off by up to 8
+//! assert_snapshot!(window, "settings_panel", 8);
+//! ```
+//!
+//! Snapshots are stored as `tests/snapshots/<name>.png` in the crate running the test. When one
+//! doesn't match, the rendered image is written next to it as `<name>.new.png` along with
+//! `<name>.diff.png` marking the pixels that differ in red. Run the tests with
+//! `UPDATE_SNAPSHOTS=1` to accept the rendered images as the new snapshots.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+pub use native_core::raster::{self, Image};
+
+/// Environment variable that makes snapshot assertions overwrite the stored images.
+pub const UPDATE_ENV: &str = "UPDATE_SNAPSHOTS";
+
+/// Render a window and compare it with the snapshot of the same name, panicking on mismatch.
+///
+/// An optional third argument sets the per channel tolerance, `0` by default.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($window: expr, $name: expr) => {
+        $crate::assert_snapshot!($window, $name, 0)
+    };
+    ($window: expr, $name: expr, $tolerance: expr) => {
+        if let Err(error) = $crate::Snapshot::new($name)
+            .dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"))
+            .tolerance($tolerance)
+            .check(&$crate::raster::render(&mut $window))
+        {
+            panic!("{}", error);
+        }
+    };
+}
+
+/// Whether snapshots should be overwritten instead of compared.
+pub fn should_update() -> bool {
+    env::var(UPDATE_ENV).is_ok_and(|value| !matches!(value.as_str(), "" | "0" | "false"))
+}
+
+/// A named golden image.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    name: String,
+    dir: PathBuf,
+    tolerance: u8,
+}
+
+impl Snapshot {
+    /// A snapshot stored in `tests/snapshots` relative to the working directory.
+    pub fn new(name: &str) -> Self {
+        Snapshot {
+            name: name.to_string(),
+            dir: PathBuf::from("tests/snapshots"),
+            tolerance: 0,
+        }
+    }
+
+    pub fn dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// How far each channel of a pixel may be from the snapshot before it counts as different.
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Path of the stored snapshot.
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(format!("{}.png", self.name))
+    }
+
+    /// Path the rendered image is written to when it doesn't match.
+    pub fn new_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.new.png", self.name))
+    }
+
+    /// Path the diff image is written to when the rendered image doesn't match.
+    pub fn diff_path(&self) -> PathBuf {
+        self.dir.join(format!("{}.diff.png", self.name))
+    }
+
+    /// Compare the image with the stored snapshot, or store it when [`UPDATE_ENV`] is set.
+    pub fn check(&self, image: &Image) -> Result<(), String> {
+        match should_update() {
+            true => self.bless(image),
+            false => self.verify(image),
+        }
+    }
+
+    /// Compare the image with the stored snapshot, writing the new and diff images on mismatch.
+    pub fn verify(&self, image: &Image) -> Result<(), String> {
+        let path = self.path();
+        if !path.exists() {
+            self.write(&self.new_path(), image)?;
+            return Err(format!(
+                "Snapshot `{}` does not exist. Rendered image written to {}, run with {}=1 to accept it",
+                self.name,
+                self.new_path().display(),
+                UPDATE_ENV
+            ));
+        }
+
+        let expected = Image::open_png(&path)?;
+        match compare(image, &expected, self.tolerance) {
+            None => {
+                self.clean();
+                Ok(())
+            }
+            Some(diff) => {
+                self.write(&self.new_path(), image)?;
+                self.write(&self.diff_path(), &diff.image)?;
+                Err(format!(
+                    "Snapshot `{}` does not match: {}. Diff written to {}, run with {}=1 to accept the new image",
+                    self.name,
+                    diff,
+                    self.diff_path().display(),
+                    UPDATE_ENV
+                ))
+            }
+        }
+    }
+
+    /// Store the image as the snapshot.
+    pub fn bless(&self, image: &Image) -> Result<(), String> {
+        self.write(&self.path(), image)?;
+        self.clean();
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, image: &Image) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| image.save_png(path))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Remove images left over from a previous mismatch.
+    fn clean(&self) {
+        let _ = fs::remove_file(self.new_path());
+        let _ = fs::remove_file(self.diff_path());
+    }
+}
+
+/// How a rendered image differs from its snapshot.
+#[derive(Debug, Clone)]
+pub struct Diff {
+    /// Pixels outside of the tolerance, counting every pixel if the sizes differ.
+    pub pixels: usize,
+    /// Largest difference of a single channel.
+    pub max_difference: u8,
+    /// The snapshot faded out with the differing pixels in red.
+    pub image: Image,
+    size: Option<((u32, u32), (u32, u32))>,
+}
+
+impl std::fmt::Display for Diff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.size {
+            Some((actual, expected)) => write!(
+                f,
+                "rendered {}x{} but the snapshot is {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            None => write!(
+                f,
+                "{} of {} pixels differ by up to {}",
+                self.pixels,
+                self.image.width() * self.image.height(),
+                self.max_difference
+            ),
+        }
+    }
+}
+
+/// Compare two images pixel by pixel. Returns `None` if every channel of every pixel is within
+/// `tolerance` of the expected image.
+pub fn compare(actual: &Image, expected: &Image, tolerance: u8) -> Option<Diff> {
+    let width = actual.width().max(expected.width());
+    let height = actual.height().max(expected.height());
+    let mut image = Image::new(width, height);
+    let mut pixels = 0;
+    let mut max_difference = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let a = actual.pixel(x, y);
+            let e = expected.pixel(x, y);
+            let difference = a
+                .iter()
+                .zip(e.iter())
+                .map(|(a, e)| a.abs_diff(*e))
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+
+            let inside = x < actual.width().min(expected.width())
+                && y < actual.height().min(expected.height());
+            if !inside || difference > tolerance {
+                pixels += 1;
+                image.set_pixel(x, y, [255, 0, 0, 255]);
+            } else {
+                let luma = (e[0] as u32 * 299 + e[1] as u32 * 587 + e[2] as u32 * 114) / 1000;
+                let faded = (255 - (255 - luma) / 4) as u8;
+                image.set_pixel(x, y, [faded, faded, faded, 255]);
+            }
+        }
+    }
+
+    let size = match actual.size() == expected.size() {
+        true => None,
+        false => Some((
+            (actual.width(), actual.height()),
+            (expected.width(), expected.height()),
+        )),
+    };
+
+    match pixels {
+        0 => None,
+        _ => Some(Diff {
+            pixels,
+            max_difference,
+            image,
+            size,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, rgba: [u8; 4]) -> Image {
+        Image::from_rgba(width, height, rgba.repeat((width * height) as usize)).unwrap()
+    }
+
+    #[test]
+    fn compare_respects_tolerance() {
+        let expected = solid(4, 4, [100, 100, 100, 255]);
+        let mut actual = expected.clone();
+        actual.set_pixel(1, 2, [104, 100, 97, 255]);
+
+        assert!(compare(&actual, &expected, 4).is_none());
+
+        let diff = compare(&actual, &expected, 3).unwrap();
+        assert_eq!((diff.pixels, diff.max_difference), (1, 4));
+        assert_eq!(diff.image.pixel(1, 2), [255, 0, 0, 255]);
+        assert_ne!(diff.image.pixel(0, 0), [255, 0, 0, 255]);
+
+        let diff = compare(&solid(5, 4, [100, 100, 100, 255]), &expected, 255).unwrap();
+        assert_eq!(diff.pixels, 4);
+        assert_eq!(diff.to_string(), "rendered 5x4 but the snapshot is 4x4");
+    }
+
+    #[test]
+    fn check_writes_diff_on_mismatch() {
+        let dir = env::temp_dir().join(format!("snapshot-{}", std::process::id()));
+        let snapshot = Snapshot::new("square").dir(&dir);
+
+        snapshot.bless(&solid(3, 3, [0, 0, 0, 255])).unwrap();
+        assert!(snapshot.verify(&solid(3, 3, [0, 0, 0, 255])).is_ok());
+
+        let error = snapshot.verify(&solid(3, 3, [9, 0, 0, 255])).unwrap_err();
+        assert!(error.contains("9 of 9 pixels differ by up to 9"));
+        assert_eq!(
+            Image::open_png(snapshot.diff_path()).unwrap().pixel(0, 0),
+            [255, 0, 0, 255]
+        );
+        assert_eq!(
+            Image::open_png(snapshot.new_path()).unwrap(),
+            solid(3, 3, [9, 0, 0, 255])
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
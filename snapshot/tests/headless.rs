@@ -0,0 +1,29 @@
+use headless::{
+    prelude::{component, layout},
+    ui::{component::Text, Window},
+    Headless,
+};
+use native_core::{
+    prelude::{Container, Renderable},
+    Backend,
+};
+use snapshot::assert_snapshot;
+
+#[test]
+fn text_with_border() {
+    let mut window = Window::builder()
+        .size(120, 40)
+        .layout(layout![component::text!("Hi", "label")])
+        .build();
+    Headless::create_window(&mut window, 0).unwrap();
+    {
+        let label = window.find_by_id("label").unwrap();
+        let mut label = label.downcast_mut::<Text>().unwrap();
+        label.set_style("background-color", "#ddeeff").unwrap();
+        label.set_style("border", "2px solid #336").unwrap();
+        label.set_style("height", "24px").unwrap();
+    }
+    window.tick().unwrap();
+
+    assert_snapshot!(window, "text_with_border");
+}
@@ -1,106 +1,322 @@
-use native_core::STYLESHEET;
+use std::{marker::PhantomData, path::Path, thread, time::Duration};
+
+use native_core::{
+    prelude::Renderable,
+    reload::StyleWatcher,
+    THEME,
+};
 
 #[cfg(target_os = "windows")]
 pub use skylight;
 #[cfg(target_os = "windows")]
-pub use skylight::core::error::Error;
+pub use skylight::Skylight as Native;
+
+#[cfg(target_os = "macos")]
+pub use braeburn;
+#[cfg(target_os = "macos")]
+pub use braeburn::Braeburn as Native;
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub use humboldt;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub use humboldt::Humboldt as Native;
 
-use style::Stylesheet;
+use markup::{Document, MarkupBackend, MarkupError};
+use style::{ColorScheme, Stylesheet};
 
+#[cfg(feature = "headless")]
+pub use headless;
+#[cfg(feature = "terminal")]
+pub use terminal;
+
+pub mod markup;
 pub mod prelude;
 pub mod ui;
 pub use core;
+pub use native_core::Backend;
 pub use style;
 
-pub struct AppBuilder {
-    window_count: u32,
-    windows: Vec<ui::Window>,
+/// Time between ticks of the event loop in development mode.
+const FRAME: Duration = Duration::from_millis(16);
+
+/// Error of the native backend for the current platform.
+pub type Error = <Native as Backend>::Error;
+
+pub struct AppBuilder<B: Backend = Native> {
+    windows: Vec<B::Window>,
+    watcher: Option<StyleWatcher>,
+    style_errors: fn(&[String]),
+    backend: PhantomData<B>,
 }
 
-impl AppBuilder {
+impl<B: Backend> Default for AppBuilder<B> {
+    fn default() -> Self {
+        AppBuilder::new()
+    }
+}
+
+impl<B: Backend> AppBuilder<B> {
+    pub fn new() -> Self {
+        AppBuilder {
+            windows: Vec::new(),
+            watcher: None,
+            style_errors: print_style_errors,
+            backend: PhantomData,
+        }
+    }
+
     pub fn set_style(self, stylesheet: Stylesheet) -> Self {
-        STYLESHEET.0.write().unwrap().dup(stylesheet);
+        THEME.set_style(stylesheet);
+        self
+    }
+
+    /// Stack another stylesheet on the app stylesheet, see [`App::add_stylesheet`].
+    pub fn add_stylesheet(self, stylesheet: Stylesheet) -> Self {
+        THEME.add_style(stylesheet);
+        self
+    }
+
+    /// Add a named theme, see [`App::set_theme`].
+    pub fn theme(self, name: &str, stylesheet: Stylesheet) -> Self {
+        THEME.add_theme(name, stylesheet);
+        self
+    }
+
+    /// Start with a theme added by [`AppBuilder::theme`] active.
+    pub fn active_theme(self, name: &str) -> Self {
+        THEME.set_theme(Some(name));
+        self
+    }
+
+    /// Set the color scheme matched by `prefers-color-scheme`, light by default.
+    pub fn color_scheme(self, color_scheme: ColorScheme) -> Self {
+        THEME.set_color_scheme(color_scheme);
+        self
+    }
+
+    /// Set whether `prefers-reduced-motion: reduce` matches.
+    pub fn reduced_motion(self, reduced_motion: bool) -> Self {
+        THEME.set_reduced_motion(reduced_motion);
+        self
+    }
+
+    /// Development mode: load the stylesheet from a file and reload it whenever it changes,
+    /// see [`App::watch_stylesheet`].
+    pub fn watch_stylesheet<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.watcher.get_or_insert_with(StyleWatcher::new).watch(path);
         self
     }
 
-    pub fn add_window(mut self, mut window: ui::Window) -> Self {
-        window.set_index(self.window_count);
-        self.window_count += 1;
+    /// Called with the errors of watched stylesheets that failed to reload while the app runs.
+    /// They are printed to stderr by default.
+    pub fn on_style_errors(mut self, callback: fn(&[String])) -> Self {
+        self.style_errors = callback;
+        self
+    }
+
+    pub fn add_window(mut self, window: B::Window) -> Self {
         self.windows.push(window);
         self
     }
 
-    pub fn add_windows(mut self, windows: Vec<ui::Window>) -> Self {
-        for mut window in windows {
-            window.set_index(self.window_count);
-            self.window_count += 1;
-            self.windows.push(window);
-        }
+    pub fn add_windows(mut self, windows: Vec<B::Window>) -> Self {
+        self.windows.extend(windows);
         self
     }
 
-    pub fn build(self) -> App {
+    pub fn build(self) -> App<B> {
         App {
-            window_count: self.window_count,
             windows: self.windows,
+            watcher: self.watcher,
+            style_errors: self.style_errors,
+            backend: PhantomData,
         }
     }
 
-    pub fn run(self) -> Result<(), Error> {
-        let app = App {
-            window_count: self.window_count,
-            windows: self.windows,
-        };
-        app.run()
+    pub fn run(self) -> Result<(), B::Error> {
+        self.build().run()
     }
 }
 
-pub struct App {
-    window_count: u32,
-    windows: Vec<ui::Window>,
+impl<B: MarkupBackend> AppBuilder<B> {
+    /// Add the windows of a markup file and use its stylesheet, if it has one.
+    pub fn markup<P: AsRef<Path>>(mut self, path: P) -> Result<Self, MarkupError> {
+        let document = Document::<B>::open(path)?;
+        if let Some(stylesheet) = document.stylesheet {
+            self = self.set_style(stylesheet);
+        }
+        Ok(self.add_windows(document.windows))
+    }
+}
+
+/// An application made up of windows rendered by the backend `B`, the native backend for the
+/// current platform by default.
+pub struct App<B: Backend = Native> {
+    windows: Vec<B::Window>,
+    watcher: Option<StyleWatcher>,
+    style_errors: fn(&[String]),
+    backend: PhantomData<B>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App::new()
+    }
 }
 
 impl App {
     pub fn new() -> Self {
-        App {
-            window_count: 0,
-            windows: Vec::new(),
-        }
+        App::with_backend()
     }
 
     pub fn builder() -> AppBuilder {
-        AppBuilder {
-            window_count: 0,
+        AppBuilder::new()
+    }
+}
+
+impl<B: Backend> App<B> {
+    /// Create an app rendered by a backend other than the native one,
+    /// `App::<Backend>::with_backend()`.
+    pub fn with_backend() -> Self {
+        App {
             windows: Vec::new(),
+            watcher: None,
+            style_errors: print_style_errors,
+            backend: PhantomData,
         }
     }
 
-    pub fn to_builder(self) -> AppBuilder {
+    pub fn to_builder(self) -> AppBuilder<B> {
         AppBuilder {
-            window_count: self.window_count,
             windows: self.windows,
+            watcher: self.watcher,
+            style_errors: self.style_errors,
+            backend: PhantomData,
         }
     }
 
     pub fn set_style(stylesheet: Stylesheet) {
-        STYLESHEET.0.write().unwrap().dup(stylesheet);
+        THEME.set_style(stylesheet);
+    }
+
+    /// Stack another stylesheet on the app stylesheet and restyle every window. Sheets added
+    /// later win over earlier ones, e.g. `tokens.css`, `base.css` then `components.css`.
+    pub fn add_stylesheet(&mut self, stylesheet: Stylesheet) {
+        THEME.add_style(stylesheet);
+        self.restyle();
+    }
+
+    /// Switch to a named theme, or back to only the app stylesheet with `None`, and restyle
+    /// every window. Returns `false` if there is no theme with the name.
+    pub fn set_theme(&mut self, name: Option<&str>) -> bool {
+        if !THEME.set_theme(name) {
+            return false;
+        }
+        self.restyle();
+        true
+    }
+
+    /// Switch the color scheme matched by `prefers-color-scheme` and restyle every window.
+    pub fn set_color_scheme(&mut self, color_scheme: ColorScheme) {
+        THEME.set_color_scheme(color_scheme);
+        self.restyle();
+    }
+
+    /// Switch whether `prefers-reduced-motion: reduce` matches and restyle every window.
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        THEME.set_reduced_motion(reduced_motion);
+        self.restyle();
+    }
+
+    /// Restyle and lay out every window again, e.g. after changing the [`THEME`] directly.
+    pub fn restyle(&mut self) {
+        for window in self.windows.iter_mut() {
+            window.restyle();
+        }
     }
 
-    pub fn add_window(&mut self, mut window: ui::Window) {
-        window.set_index(self.window_count);
-        self.window_count += 1;
+    /// Development mode: load the stylesheet from a file and reload it whenever it changes.
+    /// Watched files stack in the order they were added.
+    ///
+    /// On a change the open windows are restyled and laid out again. Parse errors are passed to
+    /// [`AppBuilder::on_style_errors`] and the previous stylesheet is kept until the file is
+    /// fixed.
+    pub fn watch_stylesheet<P: AsRef<Path>>(&mut self, path: P) {
+        self.watcher.get_or_insert_with(StyleWatcher::new).watch(path);
+    }
+
+    /// Reload the watched stylesheets if they changed and restyle every window. Returns whether
+    /// the stylesheet was replaced, or the errors of the files if any of them failed to parse.
+    pub fn reload_styles(&mut self) -> Result<bool, Vec<String>> {
+        let reloaded = match self.watcher.as_mut() {
+            Some(watcher) => watcher.check()?,
+            None => false,
+        };
+
+        if reloaded {
+            self.restyle();
+        }
+        Ok(reloaded)
+    }
+
+    pub fn add_window(&mut self, window: B::Window) {
         self.windows.push(window)
     }
 
-    pub fn add_windows(&mut self, windows: Vec<ui::Window>) {
-        for mut window in windows {
-            window.set_index(self.window_count);
-            self.window_count += 1;
-            self.windows.push(window);
+    pub fn add_windows(&mut self, windows: Vec<B::Window>) {
+        self.windows.extend(windows);
+    }
+
+    pub fn windows(&self) -> &[B::Window] {
+        &self.windows
+    }
+
+    pub fn windows_mut(&mut self) -> &mut [B::Window] {
+        &mut self.windows
+    }
+
+    /// Create every window, numbering them in the order they were added, without entering the
+    /// event loop. Drive the app with [`App::tick`] afterwards.
+    pub fn start(&mut self) -> Result<(), B::Error> {
+        for (index, window) in self.windows.iter_mut().enumerate() {
+            B::create_window(window, index as u32)?;
+        }
+        Ok(())
+    }
+
+    /// Process the pending events of the windows. Returns whether any window is still open.
+    ///
+    /// Watched stylesheets are checked for changes every
+    /// [`POLL_INTERVAL`](native_core::reload::POLL_INTERVAL).
+    pub fn tick(&mut self) -> Result<bool, B::Error> {
+        let due = self.watcher.as_ref().is_some_and(StyleWatcher::is_due);
+        if due {
+            if let Err(errors) = self.reload_styles() {
+                (self.style_errors)(&errors);
+            }
         }
+        B::tick(&mut self.windows)
     }
 
-    pub fn run(self) -> Result<(), Error> {
-        ui::run(self.windows)
+    /// Create every window then run the event loop until all of them are closed.
+    ///
+    /// With watched stylesheets the loop is driven by [`App::tick`] so the files can be checked
+    /// between events.
+    pub fn run(mut self) -> Result<(), B::Error> {
+        self.start()?;
+        if self.watcher.is_none() {
+            return B::run(self.windows);
+        }
+
+        while self.tick()? {
+            thread::sleep(FRAME);
+        }
+        Ok(())
+    }
+}
+
+fn print_style_errors(errors: &[String]) {
+    for error in errors {
+        eprintln!("{}", error);
     }
 }
@@ -0,0 +1,708 @@
+//! Windows described in an HTML like markup file.
+//!
+//! ```html
+//! <link rel="stylesheet" href="style.css">
+//! <window id="main" class="dark" title="Hello" width="400" height="200">
+//!     <text class="h1">Hello</text>
+//! </window>
+//! ```
+//!
+//! Every `<window>` becomes a window of the backend and its children are turned into
+//! components by a [`Registry`] which maps tag names to factories. `<link rel="stylesheet">`
+//! and `<style>` elements are collected into the stylesheet of the [`Document`], a `<style>`
+//! inside of a `<window>` is scoped to that window. `<html>`, `<head>` and `<body>` may wrap
+//! the content like in a regular page. The attributes of an element are kept on the built
+//! element so attribute selectors like `[role="tab"]` can match them.
+
+use std::{collections::HashMap, fmt, fs, path::Path};
+
+use native_core::{
+    layout::{Child, Layout},
+    prelude::Renderable,
+    Backend, Node,
+};
+use style::Stylesheet;
+
+/// Elements that never have children or a closing tag.
+const VOID: [&str; 4] = ["link", "meta", "br", "img"];
+
+/// An element of a markup file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Element {
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    /// The text directly inside the element.
+    pub text: String,
+    pub children: Vec<Element>,
+    /// Line the element starts on, counting from 1.
+    pub line: usize,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.attribute("id").filter(|id| !id.trim().is_empty())
+    }
+
+    pub fn classes(&self) -> Vec<&str> {
+        self.attribute("class")
+            .map(|classes| classes.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    /// The text with runs of whitespace collapsed like a browser would.
+    pub fn content(&self) -> String {
+        self.text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// An error pointing at this element.
+    pub fn error(&self, message: impl Into<String>) -> MarkupError {
+        MarkupError::new(self.line, message)
+    }
+}
+
+/// Error while loading a markup file. Displayed as `line 3: message`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl MarkupError {
+    pub fn new(line: usize, message: impl Into<String>) -> Self {
+        MarkupError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for MarkupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+/// Backends whose windows and built in components can be created from markup.
+pub trait MarkupBackend: Backend + Sized {
+    /// Build a window from its element and the layout of its children.
+    fn window(
+        element: &Element,
+        layout: Layout<Self::Data, Self::Error>,
+    ) -> Result<Self::Window, MarkupError>;
+
+    /// Build a `<text>` element.
+    fn text(element: &Element) -> Result<Child<Self::Data, Self::Error>, MarkupError> {
+        if let Some(child) = element.children.first() {
+            return Err(child.error(format!("<text> can't contain <{}>", child.tag)));
+        }
+        let text = <Self as Backend>::text(&element.content(), element.id(), element.classes());
+        if let Child::Component(component) = &text {
+            set_attributes(element, component.borrow_mut().node_mut());
+        }
+        Ok(text)
+    }
+}
+
+macro_rules! markup_backend {
+    ($platform: ident, $backend: ident) => {
+        impl MarkupBackend for $platform::$backend {
+            fn window(
+                element: &Element,
+                layout: Layout<Self::Data, Self::Error>,
+            ) -> Result<Self::Window, MarkupError> {
+                let mut builder = $platform::ui::Window::builder()
+                    .classes(element.classes())
+                    .layout(layout);
+                if let Some(id) = element.id() {
+                    builder = builder.id(id);
+                }
+                if let Some(title) = element.attribute("title") {
+                    builder = builder.title(title);
+                }
+                if let Some((width, height)) = window_size(element)? {
+                    builder = builder.size(width, height);
+                }
+                if let Some(stylesheet) = scoped_stylesheet(element)? {
+                    builder = builder.stylesheet(stylesheet);
+                }
+                let mut window = builder.build();
+                set_attributes(element, window.node_mut());
+                Ok(window)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "headless")]
+markup_backend!(headless, Headless);
+#[cfg(feature = "terminal")]
+markup_backend!(terminal, Terminal);
+#[cfg(target_os = "windows")]
+markup_backend!(skylight, Skylight);
+#[cfg(target_os = "macos")]
+markup_backend!(braeburn, Braeburn);
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+markup_backend!(humboldt, Humboldt);
+
+/// The `width` and `height` attributes of a window, which have to be given together.
+fn window_size(element: &Element) -> Result<Option<(i32, i32)>, MarkupError> {
+    let number = |name: &str| {
+        element
+            .attribute(name)
+            .map(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("px")
+                    .parse::<i32>()
+                    .map_err(|_| {
+                        element.error(format!("{} must be a number, found '{}'", name, value))
+                    })
+            })
+            .transpose()
+    };
+
+    match (number("width")?, number("height")?) {
+        (Some(width), Some(height)) => Ok(Some((width, height))),
+        (None, None) => Ok(None),
+        _ => Err(element.error("<window> needs both a width and a height")),
+    }
+}
+
+/// Copy the attributes of an element to the node of the element built from it.
+fn set_attributes(element: &Element, node: &mut Node) {
+    node.attributes.extend(element.attributes.iter().cloned());
+}
+
+/// The `<style>` children of an element.
+fn scoped_stylesheet(element: &Element) -> Result<Option<Stylesheet>, MarkupError> {
+    let mut stylesheet = None;
+    for child in element.children.iter().filter(|child| child.tag == "style") {
+        stylesheet
+            .get_or_insert_with(Stylesheet::default)
+            .extend(style_element(child)?);
+    }
+    Ok(stylesheet)
+}
+
+/// Parse a `<style>` element, failing on the line of the markup the first invalid rule is on.
+fn style_element(element: &Element) -> Result<Stylesheet, MarkupError> {
+    Stylesheet::try_parse(&element.text).map_err(|errors| {
+        let error = &errors[0];
+        MarkupError::new(
+            element.line + (error.line as usize).saturating_sub(1),
+            format!("invalid stylesheet: {}", error.message),
+        )
+    })
+}
+
+/// Creates a component from an element. Nested elements can be built with [`Registry::build`].
+pub type Factory<B> = fn(
+    &Element,
+    &Registry<B>,
+)
+    -> Result<Child<<B as Backend>::Data, <B as Backend>::Error>, MarkupError>;
+
+/// Maps tag names to the components they create. `text` is registered by default.
+pub struct Registry<B: MarkupBackend> {
+    factories: HashMap<String, Factory<B>>,
+}
+
+impl<B: MarkupBackend> Default for Registry<B> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<B: MarkupBackend> Registry<B> {
+    pub fn new() -> Self {
+        let mut factories: HashMap<String, Factory<B>> = HashMap::new();
+        factories.insert("text".to_string(), |element, _| {
+            <B as MarkupBackend>::text(element)
+        });
+        Registry { factories }
+    }
+
+    /// Use `factory` for elements named `tag`, replacing the previous one.
+    pub fn register(mut self, tag: &str, factory: Factory<B>) -> Self {
+        self.factories.insert(tag.to_string(), factory);
+        self
+    }
+
+    /// Create the component for an element.
+    pub fn build(&self, element: &Element) -> Result<Child<B::Data, B::Error>, MarkupError> {
+        match self.factories.get(&element.tag) {
+            Some(factory) => factory(element, self),
+            None => Err(element.error(format!("unknown element <{}>", element.tag))),
+        }
+    }
+
+    /// Create the components for the children of an element, skipping its `<style>` elements.
+    pub fn layout(&self, element: &Element) -> Result<Layout<B::Data, B::Error>, MarkupError> {
+        if !element.text.trim().is_empty() {
+            return Err(element.error(format!(
+                "text in <{}> has to be wrapped in a <text> element",
+                element.tag
+            )));
+        }
+
+        element
+            .children
+            .iter()
+            .filter(|child| child.tag != "style")
+            .map(|child| self.build(child))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Layout::from)
+    }
+}
+
+/// The windows and stylesheet of a markup file.
+pub struct Document<B: MarkupBackend> {
+    pub windows: Vec<B::Window>,
+    /// The linked and inline styles, `None` if there weren't any.
+    pub stylesheet: Option<Stylesheet>,
+}
+
+impl<B: MarkupBackend> Document<B> {
+    /// Load a markup file with the default registry. Linked stylesheets are resolved relative
+    /// to the file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MarkupError> {
+        Document::open_with(path, &Registry::new())
+    }
+
+    pub fn open_with<P: AsRef<Path>>(path: P, registry: &Registry<B>) -> Result<Self, MarkupError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|error| {
+            MarkupError::new(0, format!("couldn't read '{}': {}", path.display(), error))
+        })?;
+        Document::parse_with(&source, path.parent(), registry)
+    }
+
+    /// Parse markup with the default registry. Linked stylesheets are resolved relative to
+    /// `base`, or the working directory.
+    pub fn parse(source: &str, base: Option<&Path>) -> Result<Self, MarkupError> {
+        Document::parse_with(source, base, &Registry::new())
+    }
+
+    pub fn parse_with(
+        source: &str,
+        base: Option<&Path>,
+        registry: &Registry<B>,
+    ) -> Result<Self, MarkupError> {
+        let mut document = Document {
+            windows: Vec::new(),
+            stylesheet: None,
+        };
+        for element in parse(source)? {
+            document.add(&element, base, registry)?;
+        }
+        Ok(document)
+    }
+
+    fn add_stylesheet(&mut self, stylesheet: Stylesheet) {
+        self.stylesheet
+            .get_or_insert_with(Stylesheet::default)
+            .extend(stylesheet);
+    }
+
+    fn add(
+        &mut self,
+        element: &Element,
+        base: Option<&Path>,
+        registry: &Registry<B>,
+    ) -> Result<(), MarkupError> {
+        match element.tag.as_str() {
+            "html" | "head" | "body" => {
+                for child in element.children.iter() {
+                    self.add(child, base, registry)?;
+                }
+            }
+            "title" | "meta" => (),
+            "style" => self.add_stylesheet(style_element(element)?),
+            "link" => {
+                if element.attribute("rel") != Some("stylesheet") {
+                    return Ok(());
+                }
+                let href = element
+                    .attribute("href")
+                    .ok_or_else(|| element.error("stylesheet <link> is missing an href"))?;
+                let path = match base {
+                    Some(base) => base.join(href),
+                    None => href.into(),
+                };
+                if let Err(error) = fs::metadata(&path) {
+                    return Err(element.error(format!(
+                        "couldn't read '{}': {}",
+                        path.display(),
+                        error
+                    )));
+                }
+                // Imports of the stylesheet are resolved relative to it
+                let stylesheet = Stylesheet::try_file(&path).map_err(|errors| {
+                    let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                    element.error(format!("invalid stylesheet {}", errors.join(", ")))
+                })?;
+                self.add_stylesheet(stylesheet);
+            }
+            "window" => {
+                let layout = registry.layout(element)?;
+                self.windows.push(B::window(element, layout)?);
+            }
+            tag => {
+                return Err(element.error(format!("<{}> has to be inside a <window>", tag)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parse markup into its top level elements.
+pub fn parse(source: &str) -> Result<Vec<Element>, MarkupError> {
+    let mut reader = Reader {
+        source,
+        position: 0,
+        line: 1,
+        closed: false,
+    };
+    let mut roots = Vec::new();
+    let mut open: Vec<Element> = Vec::new();
+
+    while !reader.is_empty() {
+        if reader.eat("<!--") {
+            let line = reader.line;
+            reader
+                .until("-->")
+                .ok_or_else(|| MarkupError::new(line, "comment is never closed"))?;
+        } else if reader.eat("<!") {
+            let line = reader.line;
+            reader
+                .until(">")
+                .ok_or_else(|| MarkupError::new(line, "declaration is never closed"))?;
+        } else if reader.eat("</") {
+            let line = reader.line;
+            let tag = reader.name();
+            reader.whitespace();
+            if !reader.eat(">") {
+                return Err(MarkupError::new(
+                    reader.line,
+                    format!("expected '>' to close </{}>", tag),
+                ));
+            }
+
+            let element = match open.pop() {
+                Some(element) if element.tag == tag => element,
+                Some(element) => {
+                    return Err(MarkupError::new(
+                        line,
+                        format!(
+                            "expected </{}> for the element on line {}, found </{}>",
+                            element.tag, element.line, tag
+                        ),
+                    ))
+                }
+                None => return Err(MarkupError::new(line, format!("unexpected </{}>", tag))),
+            };
+            push(&mut open, &mut roots, element);
+        } else if reader.eat("<") {
+            let element = reader.tag()?;
+            if reader.closed || VOID.contains(&element.tag.as_str()) {
+                push(&mut open, &mut roots, element);
+            } else if element.tag == "style" {
+                let mut element = element;
+                element.text = reader
+                    .until("</style>")
+                    .ok_or_else(|| element.error("<style> is never closed"))?
+                    .to_string();
+                push(&mut open, &mut roots, element);
+            } else {
+                open.push(element);
+            }
+        } else {
+            let line = reader.line;
+            let text = decode(reader.text(), line)?;
+            match open.last_mut() {
+                Some(element) => element.text.push_str(&text),
+                None if text.trim().is_empty() => (),
+                None => {
+                    return Err(MarkupError::new(
+                        line,
+                        format!("unexpected text '{}' outside of an element", text.trim()),
+                    ))
+                }
+            }
+        }
+    }
+
+    match open.pop() {
+        Some(element) => Err(element.error(format!("<{}> is never closed", element.tag))),
+        None => Ok(roots),
+    }
+}
+
+fn push(open: &mut [Element], roots: &mut Vec<Element>, element: Element) {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(element),
+        None => roots.push(element),
+    }
+}
+
+/// Replace the character references `&amp;`, `&lt;`, `&gt;`, `&quot;`, `&apos;` and `&#..;`.
+fn decode(text: &str, line: usize) -> Result<String, MarkupError> {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest
+            .find(';')
+            .ok_or_else(|| MarkupError::new(line, "'&' has to be written as '&amp;'"))?;
+        let name = &rest[1..end];
+        let c = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => name
+                    .strip_prefix('#')
+                    .and_then(|decimal| decimal.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        decoded.push(c.ok_or_else(|| {
+            MarkupError::new(line, format!("unknown character reference '&{};'", name))
+        })?);
+        rest = &rest[end + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+struct Reader<'a> {
+    source: &'a str,
+    position: usize,
+    line: usize,
+    /// Whether the last tag read was self closing, `<text />`.
+    closed: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    fn is_empty(&self) -> bool {
+        self.position >= self.source.len()
+    }
+
+    fn advance(&mut self, bytes: usize) -> &'a str {
+        let taken = &self.rest()[..bytes];
+        self.line += taken.matches('\n').count();
+        self.position += bytes;
+        taken
+    }
+
+    fn eat(&mut self, pattern: &str) -> bool {
+        let found = self.rest().starts_with(pattern);
+        if found {
+            self.advance(pattern.len());
+        }
+        found
+    }
+
+    /// Everything up to `pattern`, skipping past it.
+    fn until(&mut self, pattern: &str) -> Option<&'a str> {
+        let end = self.rest().find(pattern)?;
+        let taken = self.advance(end);
+        self.advance(pattern.len());
+        Some(taken)
+    }
+
+    fn whitespace(&mut self) {
+        let end = self
+            .rest()
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(self.rest().len());
+        self.advance(end);
+    }
+
+    fn name(&mut self) -> String {
+        let end = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == ':'))
+            .unwrap_or(self.rest().len());
+        self.advance(end).to_ascii_lowercase()
+    }
+
+    fn text(&mut self) -> &'a str {
+        let end = self.rest().find('<').unwrap_or(self.rest().len());
+        self.advance(end)
+    }
+
+    /// An opening tag after its `<`.
+    fn tag(&mut self) -> Result<Element, MarkupError> {
+        let line = self.line;
+        let tag = self.name();
+        if tag.is_empty() {
+            return Err(MarkupError::new(line, "expected an element name after '<'"));
+        }
+
+        let mut element = Element {
+            tag,
+            line,
+            ..Default::default()
+        };
+        loop {
+            self.whitespace();
+            if self.eat("/>") {
+                self.closed = true;
+                return Ok(element);
+            }
+            if self.eat(">") {
+                self.closed = false;
+                return Ok(element);
+            }
+
+            let name = self.name();
+            if name.is_empty() {
+                let found = self.rest().chars().next().map(String::from);
+                return Err(MarkupError::new(
+                    self.line,
+                    match found {
+                        Some(found) => format!("unexpected '{}' in <{}>", found, element.tag),
+                        None => format!("<{}> is never closed", element.tag),
+                    },
+                ));
+            }
+
+            self.whitespace();
+            let value = match self.eat("=") {
+                true => {
+                    self.whitespace();
+                    let value_line = self.line;
+                    let value = match self.rest().chars().next() {
+                        Some(quote @ ('"' | '\'')) => {
+                            self.advance(1);
+                            self.until(&quote.to_string()).ok_or_else(|| {
+                                MarkupError::new(
+                                    value_line,
+                                    format!("value of {} is never closed", name),
+                                )
+                            })?
+                        }
+                        _ => {
+                            let end = self
+                                .rest()
+                                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                                .unwrap_or(self.rest().len());
+                            self.advance(end)
+                        }
+                    };
+                    decode(value, value_line)?
+                }
+                false => String::new(),
+            };
+            element.attributes.push((name, value));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod tests {
+    use headless::{ui::component::Text, Headless};
+    use native_core::{
+        prelude::{Container, Renderable},
+        raster::Content,
+    };
+
+    use super::*;
+
+    #[test]
+    fn load_windows_and_styles() {
+        let document = Document::<Headless>::parse(
+            r#"<!DOCTYPE html>
+            <html>
+            <head><style>.h1 { height: 40px; }</style></head>
+            <body>
+                <!-- The main window -->
+                <window id="main" class="dark" width="200" height="100">
+                    <text id="title" class="h1" data-state="error">Hello &amp; welcome</text>
+                    <text>World</text>
+                </window>
+            </body>
+            </html>"#,
+            None,
+        )
+        .unwrap();
+        assert!(document.stylesheet.is_some());
+
+        let mut windows = document.windows;
+        assert_eq!(windows.len(), 1);
+        let window = &mut windows[0];
+        assert_eq!(window.id(), "#main");
+        assert!(window.classes().contains(".dark"));
+        assert_eq!(window.layout().children.len(), 2);
+
+        let title = window.find_by_id("title").unwrap();
+        assert!(title.classes().contains(".h1"));
+        let title = title.downcast_ref::<Text>().unwrap();
+        assert!(matches!(title.content(), Content::Text(text) if text == "Hello & welcome"));
+        drop(title);
+        assert!(window
+            .query_selector(r#"text[data-state^="err"]"#)
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn errors_carry_line_numbers() {
+        let error = |source: &str| Document::<Headless>::parse(source, None).err().unwrap();
+
+        assert_eq!(
+            error("<window>\n  <text>Hi</text>\n  <button>Go</button>\n</window>"),
+            MarkupError::new(3, "unknown element <button>")
+        );
+        assert_eq!(
+            error("<window>\n  <text>Hi</window>").to_string(),
+            "line 2: expected </text> for the element on line 2, found </window>"
+        );
+        assert_eq!(error("\n\n<window>\n  <text>Hi</text>\n").line, 3);
+    }
+
+    #[test]
+    fn invalid_stylesheets_fail_the_load() {
+        let error = |source: &str, base: Option<&Path>| {
+            Document::<Headless>::parse(source, base).err().unwrap()
+        };
+
+        let style = error(
+            "<style>\n.a { width: 1px; }\n@media (bogus: 1) {}\n</style>",
+            None,
+        );
+        assert_eq!(style.line, 3);
+        assert!(style.message.starts_with("invalid stylesheet"));
+        let scoped = "<window>\n  <style>\n    @media (bogus: 1) {}\n  </style>\n</window>";
+        assert_eq!(error(scoped, None).line, 3);
+
+        let dir = std::env::temp_dir().join(format!("markup-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("broken.css"), "@media (bogus: 1) {}").unwrap();
+        let link = error(
+            "\n<link rel=\"stylesheet\" href=\"broken.css\">",
+            Some(&dir),
+        );
+        assert_eq!(link.line, 2);
+        assert!(link.message.contains("broken.css:1:"), "{}", link.message);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,2 +1,6 @@
-#[cfg(target_os="windows")]
+#[cfg(target_os = "windows")]
 pub use skylight::{styles, prelude::{layout, component}};
+#[cfg(target_os = "macos")]
+pub use braeburn::{styles, prelude::{layout, component}};
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub use humboldt::{styles, prelude::{layout, component}};
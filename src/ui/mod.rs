@@ -2,7 +2,3 @@ mod window;
 
 pub mod popup;
 pub use window::Window;
-
-#[cfg(target_os="windows")]
-pub use skylight::ui::run;
-
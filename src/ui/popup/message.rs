@@ -1,15 +1,16 @@
+use native_core::Backend;
+
 pub use super::{Buttons, Icon, MessageReturn};
+use crate::Native;
 
 #[cfg(target_os = "windows")]
 pub use skylight::ui::popup::{ButtonLayout, Icon as PopupIcon, MessageBox};
 
-#[cfg(target_os = "windows")]
 pub fn message(title: &str, message: &str, buttons: Buttons, icon: Icon) -> MessageReturn {
-    MessageReturn(MessageBox::is_success(MessageBox::new(
-        None,
+    MessageReturn(Native::message(
         title,
         message,
-        buttons.to_name().into(),
-        icon.to_name().into()
-    )))
+        buttons.to_name(),
+        icon.to_name(),
+    ))
 }
@@ -1,3 +1,6 @@
-#[cfg(target_os="windows")]
-pub use skylight::ui::Window;
+use native_core::Backend;
 
+use crate::Native;
+
+/// Window of the native backend for the current platform.
+pub type Window = <Native as Backend>::Window;
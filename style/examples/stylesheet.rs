@@ -2,12 +2,15 @@ extern crate style;
 use style::Stylesheet;
 
 fn main() {
-    let stylesheet = Stylesheet::parse("div {
+    let (stylesheet, _) = Stylesheet::parse("div {
     font-style: normal;
 }");
     
     println!("{:?}", stylesheet.rules);
 
-    let stylesheet = Stylesheet::file("test.css");
+    let (stylesheet, errors) = Stylesheet::file("test.css");
     println!("{:?}", stylesheet.rules);
+    for error in errors {
+        println!("{}", error);
+    }
 }
@@ -0,0 +1,795 @@
+//! Transitions and `@keyframes` animations.
+//!
+//! `transition` and `animation` declarations are resolved per element by [`motion`], the
+//! values in between are produced by [`Style::interpolate`]. Lengths, percentages, sizes and
+//! colors interpolate smoothly, anything else switches half way through. Timing is left to the
+//! caller, the types here only map a point in time to a style.
+//!
+//! [`motion`]: crate::motion
+
+use std::{fmt, time::Duration};
+
+use cssparser::{BasicParseError, BasicParseErrorKind, ParseError, Parser, Token};
+use itertools::Itertools;
+
+use crate::{color::Color, size::Size, Appearance, Dimensions, Style, Unit};
+
+/// Properties that can be transitioned and animated, what `transition: all` covers.
+pub const ANIMATABLE: [&str; 25] = [
+    "background-color",
+    "color",
+    "border-color",
+    "border-width",
+    "min-height",
+    "height",
+    "max-height",
+    "min-width",
+    "width",
+    "max-width",
+    "inset",
+    "left",
+    "top",
+    "right",
+    "bottom",
+    "padding",
+    "padding-left",
+    "padding-top",
+    "padding-right",
+    "padding-bottom",
+    "margin",
+    "margin-left",
+    "margin-top",
+    "margin-right",
+    "margin-bottom",
+];
+
+/// Values that can be blended, `t` goes from 0 at `self` to 1 at `to`.
+pub trait Interpolate {
+    fn interpolate(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Interpolate for Unit {
+    /// Lengths and percentages blend, mixing them or `auto` switches half way.
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        match (self, to) {
+            (Unit::PX(from), Unit::PX(to)) => Unit::PX(from + (to - from) * t),
+            (Unit::Percent(from), Unit::Percent(to)) => Unit::Percent(from + (to - from) * t),
+            _ => discrete(self, to, t),
+        }
+    }
+}
+
+impl Interpolate for Size {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Size {
+            left: self.left.interpolate(&to.left, t),
+            top: self.top.interpolate(&to.top, t),
+            right: self.right.interpolate(&to.right, t),
+            bottom: self.bottom.interpolate(&to.bottom, t),
+        }
+    }
+}
+
+impl Interpolate for Color {
+    /// Channels are blended premultiplied by their alpha so fading in from transparent doesn't
+    /// pass through black.
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        let alpha = self.alpha + (to.alpha - self.alpha) * t;
+        if alpha <= 0. {
+            return Color::new(to.red, to.green, to.blue, 0.);
+        }
+        let channel = |from: u8, to_: u8| {
+            let from = from as f32 * self.alpha;
+            let to_ = to_ as f32 * to.alpha;
+            ((from + (to_ - from) * t) / alpha).round().clamp(0., 255.) as u8
+        };
+        Color::new(
+            channel(self.red, to.red),
+            channel(self.green, to.green),
+            channel(self.blue, to.blue),
+            alpha,
+        )
+    }
+}
+
+fn discrete<T: Clone>(from: &T, to: &T, t: f32) -> T {
+    match t < 0.5 {
+        true => from.clone(),
+        false => to.clone(),
+    }
+}
+
+macro_rules! interpolate {
+    ($from: expr, $to: expr, $t: expr, $($variant: ident),* $(,)?) => {
+        match ($from, $to) {
+            $((Style::$variant(from), Style::$variant(to)) => Style::$variant(from.interpolate(to, $t)),)*
+            (from, to) => discrete(from, to, $t),
+        }
+    };
+}
+
+impl Style {
+    /// The style `t` of the way from `self` to `to`. Values of different properties, or that
+    /// can't be blended, switch to `to` half way through.
+    pub fn interpolate(&self, to: &Style, t: f32) -> Style {
+        interpolate!(
+            self,
+            to,
+            t,
+            BackgroundColor,
+            Color,
+            BorderColor,
+            BorderWidth,
+            MinHeight,
+            Height,
+            MaxHeight,
+            MinWidth,
+            Width,
+            MaxWidth,
+            Inset,
+            Left,
+            Top,
+            Right,
+            Bottom,
+            Padding,
+            PaddingLeft,
+            PaddingTop,
+            PaddingRight,
+            PaddingBottom,
+            Margin,
+            MarginLeft,
+            MarginTop,
+            MarginRight,
+            MarginBottom,
+        )
+    }
+
+    /// The computed value of an [`ANIMATABLE`] property as a style, `None` for other properties
+    /// and colors left to the platform. A missing background is transparent.
+    pub fn of(name: &str, from: &(Dimensions, Appearance)) -> Option<Style> {
+        let (dimensions, appearance) = from;
+        Some(match name {
+            "background-color" => Style::BackgroundColor(
+                appearance
+                    .background_color
+                    .unwrap_or_else(Color::transparent),
+            ),
+            "color" => Style::Color(appearance.color?),
+            "border-color" => Style::BorderColor(appearance.border_color?),
+            "border-width" => Style::BorderWidth(dimensions.border),
+            "min-height" => Style::MinHeight(dimensions.min_height),
+            "height" => Style::Height(dimensions.height),
+            "max-height" => Style::MaxHeight(dimensions.max_height),
+            "min-width" => Style::MinWidth(dimensions.min_width),
+            "width" => Style::Width(dimensions.width),
+            "max-width" => Style::MaxWidth(dimensions.max_width),
+            "inset" => Style::Inset(dimensions.inset),
+            "left" => Style::Left(dimensions.inset.left),
+            "top" => Style::Top(dimensions.inset.top),
+            "right" => Style::Right(dimensions.inset.right),
+            "bottom" => Style::Bottom(dimensions.inset.bottom),
+            "padding" => Style::Padding(dimensions.padding),
+            "padding-left" => Style::PaddingLeft(dimensions.padding.left),
+            "padding-top" => Style::PaddingTop(dimensions.padding.top),
+            "padding-right" => Style::PaddingRight(dimensions.padding.right),
+            "padding-bottom" => Style::PaddingBottom(dimensions.padding.bottom),
+            "margin" => Style::Margin(dimensions.margin),
+            "margin-left" => Style::MarginLeft(dimensions.margin.left),
+            "margin-top" => Style::MarginTop(dimensions.margin.top),
+            "margin-right" => Style::MarginRight(dimensions.margin.right),
+            "margin-bottom" => Style::MarginBottom(dimensions.margin.bottom),
+            _ => return None,
+        })
+    }
+}
+
+/// How progress through a transition or keyframe maps to progress of the value.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    #[default]
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// `cubic-bezier(x1, y1, x2, y2)`.
+    CubicBezier(f32, f32, f32, f32),
+    /// `steps(count, start)` jumps at the start of each step, `steps(count, end)` at its end.
+    Steps(u32, bool),
+}
+
+impl Easing {
+    /// The eased progress for `t` between 0 and 1.
+    pub fn progress(&self, t: f32) -> f32 {
+        if t <= 0. {
+            return 0.;
+        }
+        if t >= 1. {
+            return 1.;
+        }
+        match *self {
+            Easing::Linear => t,
+            Easing::Ease => bezier(0.25, 0.1, 0.25, 1., t),
+            Easing::EaseIn => bezier(0.42, 0., 1., 1., t),
+            Easing::EaseOut => bezier(0., 0., 0.58, 1., t),
+            Easing::EaseInOut => bezier(0.42, 0., 0.58, 1., t),
+            Easing::CubicBezier(x1, y1, x2, y2) => bezier(x1, y1, x2, y2, t),
+            Easing::Steps(count, start) => {
+                let count = count.max(1) as f32;
+                let step = match start {
+                    true => (t * count).ceil(),
+                    false => (t * count).floor(),
+                };
+                step / count
+            }
+        }
+    }
+
+    pub fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Easing, BasicParseError<'i>> {
+        let location = input.current_source_location();
+        let token = input.next()?.clone();
+        match &token {
+            Token::Ident(name) => match name.to_ascii_lowercase().as_str() {
+                "linear" => Ok(Easing::Linear),
+                "ease" => Ok(Easing::Ease),
+                "ease-in" => Ok(Easing::EaseIn),
+                "ease-out" => Ok(Easing::EaseOut),
+                "ease-in-out" => Ok(Easing::EaseInOut),
+                "step-start" => Ok(Easing::Steps(1, true)),
+                "step-end" => Ok(Easing::Steps(1, false)),
+                _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+            },
+            Token::Function(name) if name.eq_ignore_ascii_case("cubic-bezier") => input
+                .parse_nested_block(|input| {
+                    let mut points = [0.; 4];
+                    for (index, point) in points.iter_mut().enumerate() {
+                        if index > 0 {
+                            input.expect_comma()?;
+                        }
+                        *point = input.expect_number()?;
+                    }
+                    // The curve has to be a function of time
+                    if !(0. ..=1.).contains(&points[0]) || !(0. ..=1.).contains(&points[2]) {
+                        return Err(input.new_error(BasicParseErrorKind::QualifiedRuleInvalid));
+                    }
+                    Ok::<_, ParseError<'i, ()>>(Easing::CubicBezier(
+                        points[0], points[1], points[2], points[3],
+                    ))
+                })
+                .map_err(ParseError::basic),
+            Token::Function(name) if name.eq_ignore_ascii_case("steps") => input
+                .parse_nested_block(|input| {
+                    let count = input.expect_integer()?;
+                    if count < 1 {
+                        return Err(input.new_error(BasicParseErrorKind::QualifiedRuleInvalid));
+                    }
+                    let mut start = false;
+                    if input.try_parse(|input| input.expect_comma()).is_ok() {
+                        let location = input.current_source_location();
+                        let position = input.expect_ident_cloned()?;
+                        start = match position.to_ascii_lowercase().as_str() {
+                            "start" | "jump-start" => true,
+                            "end" | "jump-end" => false,
+                            _ => {
+                                return Err(
+                                    location.new_unexpected_token_error(Token::Ident(position))
+                                )
+                            }
+                        };
+                    }
+                    Ok::<_, ParseError<'i, ()>>(Easing::Steps(count as u32, start))
+                })
+                .map_err(ParseError::basic),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+        }
+    }
+}
+
+/// The y value of a cubic bezier from `(0, 0)` to `(1, 1)` at `x`, found by bisecting the
+/// curve parameter.
+fn bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let curve = |a: f32, b: f32, s: f32| {
+        3. * a * s * (1. - s) * (1. - s) + 3. * b * s * s * (1. - s) + s * s * s
+    };
+    let (mut low, mut high) = (0., 1.);
+    for _ in 0..32 {
+        let s = (low + high) / 2.;
+        match curve(x1, x2, s) < x {
+            true => low = s,
+            false => high = s,
+        }
+    }
+    curve(y1, y2, (low + high) / 2.)
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Easing::Linear => write!(f, "linear"),
+            Easing::Ease => write!(f, "ease"),
+            Easing::EaseIn => write!(f, "ease-in"),
+            Easing::EaseOut => write!(f, "ease-out"),
+            Easing::EaseInOut => write!(f, "ease-in-out"),
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2)
+            }
+            Easing::Steps(count, true) => write!(f, "steps({}, start)", count),
+            Easing::Steps(count, false) => write!(f, "steps({}, end)", count),
+        }
+    }
+}
+
+/// A time, `200ms` or `1.5s`. Only `0` can be written without a unit.
+fn parse_time<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Duration, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    let seconds = match token {
+        Token::Dimension { value, unit, .. } if unit.eq_ignore_ascii_case("s") => *value,
+        Token::Dimension { value, unit, .. } if unit.eq_ignore_ascii_case("ms") => *value / 1000.,
+        Token::Number { value, .. } if *value == 0. => 0.,
+        _ => return Err(location.new_basic_unexpected_token_error(token.clone())),
+    };
+    match seconds >= 0. {
+        true => Ok(Duration::from_secs_f32(seconds)),
+        false => Err(location.new_basic_unexpected_token_error(token.clone())),
+    }
+}
+
+/// Serialize a time in seconds, `0.2s`.
+struct Time(Duration);
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0.as_secs_f32())
+    }
+}
+
+/// A `transition` of a property, `width 200ms ease-in 50ms`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    /// The transitioned property, `all` for every [`ANIMATABLE`] one.
+    pub property: String,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub delay: Duration,
+}
+
+impl Transition {
+    /// Whether the transition covers the property.
+    pub fn covers(&self, property: &str) -> bool {
+        self.property == "all" || self.property == property
+    }
+
+    /// Parse the comma separated transitions of a `transition` declaration, empty for `none`.
+    pub fn parse_list<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Vec<Transition>, BasicParseError<'i>> {
+        if input
+            .try_parse(|input| input.expect_ident_matching("none"))
+            .is_ok()
+        {
+            return Ok(Vec::new());
+        }
+        input
+            .parse_comma_separated(|input| Ok::<_, ParseError<'i, ()>>(Transition::parse(input)?))
+            .map_err(ParseError::basic)
+    }
+
+    /// The parts can come in any order, the first time is the duration and the second the
+    /// delay.
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Transition, BasicParseError<'i>> {
+        let mut property = None;
+        let mut times = Vec::new();
+        let mut easing = None;
+        while !input.is_exhausted() {
+            if let Ok(time) = input.try_parse(parse_time) {
+                times.push(time);
+                continue;
+            }
+            if easing.is_none() {
+                if let Ok(value) = input.try_parse(Easing::parse) {
+                    easing = Some(value);
+                    continue;
+                }
+            }
+
+            let location = input.current_source_location();
+            let name = input.expect_ident_cloned()?;
+            if property.is_some() || !(&*name == "all" || ANIMATABLE.contains(&&*name)) {
+                return Err(location.new_basic_unexpected_token_error(Token::Ident(name)));
+            }
+            property = Some(name.to_string());
+        }
+        if times.len() > 2 {
+            return Err(input.new_basic_error(BasicParseErrorKind::QualifiedRuleInvalid));
+        }
+
+        Ok(Transition {
+            property: property.unwrap_or_else(|| "all".to_string()),
+            duration: times.first().copied().unwrap_or_default(),
+            easing: easing.unwrap_or_default(),
+            delay: times.get(1).copied().unwrap_or_default(),
+        })
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.property,
+            Time(self.duration),
+            self.easing,
+            Time(self.delay)
+        )
+    }
+}
+
+/// Which way the keyframes play in each iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AnimationDirection {
+    #[default]
+    Normal,
+    Reverse,
+    /// Forwards then backwards.
+    Alternate,
+    /// Backwards then forwards.
+    AlternateReverse,
+}
+
+/// Whether the keyframes apply before an animation starts and after it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FillMode {
+    #[default]
+    None,
+    /// Keep the last frame after the end.
+    Forwards,
+    /// Show the first frame during the delay.
+    Backwards,
+    Both,
+}
+
+/// An `animation` playing a `@keyframes` rule, `pulse 1s ease-in-out infinite alternate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Animation {
+    /// Name of the `@keyframes` rule.
+    pub name: String,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub delay: Duration,
+    /// Number of times the keyframes play, infinite for `infinite`.
+    pub iterations: f32,
+    pub direction: AnimationDirection,
+    pub fill_mode: FillMode,
+}
+
+impl Animation {
+    /// Parse the comma separated animations of an `animation` declaration, empty for `none`.
+    pub fn parse_list<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Vec<Animation>, BasicParseError<'i>> {
+        if input
+            .try_parse(|input| input.expect_ident_matching("none"))
+            .is_ok()
+        {
+            return Ok(Vec::new());
+        }
+        input
+            .parse_comma_separated(|input| Ok::<_, ParseError<'i, ()>>(Animation::parse(input)?))
+            .map_err(ParseError::basic)
+    }
+
+    /// The parts can come in any order, the first time is the duration and the second the
+    /// delay. The first identifier that isn't a keyword is the name.
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Animation, BasicParseError<'i>> {
+        let mut name = None;
+        let mut times = Vec::new();
+        let mut easing = None;
+        let mut iterations = None;
+        let mut direction = None;
+        let mut fill_mode = None;
+        while !input.is_exhausted() {
+            if let Ok(time) = input.try_parse(parse_time) {
+                times.push(time);
+                continue;
+            }
+            if easing.is_none() {
+                if let Ok(value) = input.try_parse(Easing::parse) {
+                    easing = Some(value);
+                    continue;
+                }
+            }
+            if iterations.is_none() {
+                if let Ok(count) = input.try_parse(|input| input.expect_number()) {
+                    iterations = Some(count.max(0.));
+                    continue;
+                }
+            }
+
+            let location = input.current_source_location();
+            let ident = input.expect_ident_cloned()?;
+            match ident.to_ascii_lowercase().as_str() {
+                "infinite" if iterations.is_none() => iterations = Some(f32::INFINITY),
+                "normal" if direction.is_none() => direction = Some(AnimationDirection::Normal),
+                "reverse" if direction.is_none() => direction = Some(AnimationDirection::Reverse),
+                "alternate" if direction.is_none() => {
+                    direction = Some(AnimationDirection::Alternate)
+                }
+                "alternate-reverse" if direction.is_none() => {
+                    direction = Some(AnimationDirection::AlternateReverse)
+                }
+                "none" if fill_mode.is_none() => fill_mode = Some(FillMode::None),
+                "forwards" if fill_mode.is_none() => fill_mode = Some(FillMode::Forwards),
+                "backwards" if fill_mode.is_none() => fill_mode = Some(FillMode::Backwards),
+                "both" if fill_mode.is_none() => fill_mode = Some(FillMode::Both),
+                _ if name.is_none() => name = Some(ident.to_string()),
+                _ => return Err(location.new_basic_unexpected_token_error(Token::Ident(ident))),
+            }
+        }
+        if times.len() > 2 {
+            return Err(input.new_basic_error(BasicParseErrorKind::QualifiedRuleInvalid));
+        }
+
+        Ok(Animation {
+            name: name.ok_or_else(|| input.new_basic_error(BasicParseErrorKind::EndOfInput))?,
+            duration: times.first().copied().unwrap_or_default(),
+            easing: easing.unwrap_or_default(),
+            delay: times.get(1).copied().unwrap_or_default(),
+            iterations: iterations.unwrap_or(1.),
+            direction: direction.unwrap_or_default(),
+            fill_mode: fill_mode.unwrap_or_default(),
+        })
+    }
+
+    /// Whether every iteration has played `elapsed` after the animation started.
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        if self.duration.is_zero() {
+            return elapsed >= self.delay;
+        }
+        let end = self.delay.as_secs_f32() + self.duration.as_secs_f32() * self.iterations;
+        elapsed.as_secs_f32() >= end
+    }
+
+    /// The progress through the keyframes `elapsed` after the animation started, from 0 at the
+    /// first keyframe to 1 at the last. `None` while the keyframes don't apply, during the delay
+    /// and after the end unless the fill mode keeps them.
+    pub fn progress(&self, elapsed: Duration) -> Option<f32> {
+        let active = elapsed.as_secs_f32() - self.delay.as_secs_f32();
+        let duration = self.duration.as_secs_f32();
+        let (iteration, t) = if active < 0. {
+            if !matches!(self.fill_mode, FillMode::Backwards | FillMode::Both) {
+                return None;
+            }
+            (0., 0.)
+        } else if self.is_finished(elapsed) {
+            if !matches!(self.fill_mode, FillMode::Forwards | FillMode::Both) {
+                return None;
+            }
+            // The end of the last iteration, part of the way through it for fractional counts
+            match self.iterations.fract() {
+                fract if fract > 0. => (self.iterations.floor(), fract),
+                _ => ((self.iterations - 1.).max(0.), 1.),
+            }
+        } else {
+            let position = active / duration;
+            (position.floor(), position.fract())
+        };
+
+        let odd = iteration % 2. == 1.;
+        let reverse = match self.direction {
+            AnimationDirection::Normal => false,
+            AnimationDirection::Reverse => true,
+            AnimationDirection::Alternate => odd,
+            AnimationDirection::AlternateReverse => !odd,
+        };
+        Some(match reverse {
+            true => 1. - t,
+            false => t,
+        })
+    }
+}
+
+impl fmt::Display for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} ",
+            self.name,
+            Time(self.duration),
+            self.easing,
+            Time(self.delay)
+        )?;
+        match self.iterations.is_infinite() {
+            true => write!(f, "infinite")?,
+            false => write!(f, "{}", self.iterations)?,
+        }
+        let direction = match self.direction {
+            AnimationDirection::Normal => "normal",
+            AnimationDirection::Reverse => "reverse",
+            AnimationDirection::Alternate => "alternate",
+            AnimationDirection::AlternateReverse => "alternate-reverse",
+        };
+        let fill_mode = match self.fill_mode {
+            FillMode::None => "none",
+            FillMode::Forwards => "forwards",
+            FillMode::Backwards => "backwards",
+            FillMode::Both => "both",
+        };
+        write!(f, " {} {}", direction, fill_mode)
+    }
+}
+
+/// A frame of a `@keyframes` rule, `50% { width: 20px; }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe {
+    /// Position in the animation from 0 to 1.
+    pub offset: f32,
+    pub styles: Vec<Style>,
+}
+
+/// A `@keyframes` rule.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Keyframes {
+    pub name: String,
+    /// The frames ordered by their offset.
+    pub frames: Vec<Keyframe>,
+}
+
+impl Keyframes {
+    pub fn new(name: &str, mut frames: Vec<Keyframe>) -> Self {
+        frames.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        Keyframes {
+            name: name.to_string(),
+            frames,
+        }
+    }
+
+    /// Apply the keyframes at `progress` on top of `styles`. Each property is interpolated
+    /// between the frames that set it on either side, with `easing` applied per pair of frames.
+    /// Missing `0%` and `100%` frames take the value of `base`, the style of the element
+    /// without the animation.
+    pub fn apply(
+        &self,
+        progress: f32,
+        easing: Easing,
+        base: &(Dimensions, Appearance),
+        styles: &mut (Dimensions, Appearance),
+    ) {
+        let properties = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.styles.iter().map(Style::name))
+            .filter(|name| !name.is_empty())
+            .unique();
+        for property in properties {
+            let mut stops: Vec<(f32, &Style)> = self
+                .frames
+                .iter()
+                .flat_map(|frame| {
+                    frame
+                        .styles
+                        .iter()
+                        .filter(|style| style.name() == property)
+                        .map(move |style| (frame.offset, style))
+                })
+                .collect();
+            let initial = Style::of(property, base);
+            if let Some(initial) = &initial {
+                if stops[0].0 > 0. {
+                    stops.insert(0, (0., initial));
+                }
+                if stops[stops.len() - 1].0 < 1. {
+                    stops.push((1., initial));
+                }
+            }
+
+            let next = stops.iter().position(|(offset, _)| *offset > progress);
+            let style = match next {
+                None => stops[stops.len() - 1].1.clone(),
+                Some(0) => stops[0].1.clone(),
+                Some(next) => {
+                    let (start, from) = stops[next - 1];
+                    let (end, to) = stops[next];
+                    from.interpolate(to, easing.progress((progress - start) / (end - start)))
+                }
+            };
+            style.apply(&mut styles.0, &mut styles.1);
+        }
+    }
+}
+
+impl fmt::Display for Keyframes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "@keyframes {} {{", self.name)?;
+        for frame in self.frames.iter() {
+            writeln!(f, "  {}% {{", frame.offset * 100.)?;
+            for style in frame.styles.iter() {
+                writeln!(f, "    {};", style)?;
+            }
+            writeln!(f, "  }}")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+/// The transitions and animations of an element, see [`motion`](crate::motion).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Motion {
+    pub transitions: Vec<Transition>,
+    /// The animations along with the `@keyframes` they play.
+    pub animations: Vec<(Animation, Keyframes)>,
+}
+
+impl Motion {
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty() && self.animations.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{cascade, motion, Media, Stylesheet};
+
+    use super::*;
+
+    #[test]
+    fn transitions_keyframes_and_easing() {
+        let sheet = Stylesheet::try_parse(
+            ".panel { transition: width 200ms ease-in-out 50ms, background-color 1s; animation: grow 2s linear infinite alternate; }
+            @keyframes grow { from { height: 10px; } 50% { height: 30px; } }
+            .plain { transition: none; animation: missing 1s; }",
+        ).unwrap();
+        let keys = |key: &str| [HashSet::from([key.to_string()])];
+        let panel = motion(&[&sheet], &keys(".panel"), &[], &Media::default());
+        assert_eq!(panel.transitions.len(), 2);
+        assert_eq!(
+            panel.transitions[0].to_string(),
+            "width 0.2s ease-in-out 0.05s"
+        );
+        assert!(panel.transitions[1].covers("background-color"));
+        let (animation, keyframes) = &panel.animations[0];
+        assert_eq!(
+            animation.to_string(),
+            "grow 2s linear 0s infinite alternate none"
+        );
+        assert_eq!(keyframes.frames.len(), 2);
+        assert!(motion(&[&sheet], &keys(".plain"), &[], &Media::default()).is_empty());
+        assert!(
+            sheet
+                .to_string()
+                .contains("@keyframes grow {\n  0% {\n    height: 10px;\n  }"),
+            "{}",
+            sheet
+        );
+
+        // Alternate iterations play backwards, the missing 100% frame is the element's own height
+        assert_eq!(animation.progress(Duration::from_millis(500)), Some(0.25));
+        assert_eq!(animation.progress(Duration::from_millis(2500)), Some(0.75));
+        let base = cascade(
+            &[&sheet],
+            &keys(".panel"),
+            &[Style::Height(Unit::PX(20.))],
+            &Media::default(),
+            None,
+        );
+        let height = |progress| {
+            let mut styles = base;
+            keyframes.apply(progress, Easing::Linear, &base, &mut styles);
+            styles.0.height
+        };
+        assert_eq!(height(0.25), Unit::PX(20.));
+        assert_eq!(height(0.75), Unit::PX(25.));
+
+        assert_eq!(Easing::Linear.progress(0.3), 0.3);
+        assert!((Easing::EaseInOut.progress(0.5) - 0.5).abs() < 0.001);
+        assert!(Easing::EaseIn.progress(0.25) < 0.25);
+        assert_eq!(Easing::Steps(4, false).progress(0.3), 0.25);
+        let fade = Color::transparent().interpolate(&Color::new(255, 0, 0, 1.), 0.5);
+        assert_eq!(fade, Color::new(255, 0, 0, 0.5));
+        assert!(Style::parse("transition", "width 1s 2s 3s").is_err());
+        assert!(Style::parse("transition", "display 1s").is_err());
+    }
+}
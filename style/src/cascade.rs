@@ -0,0 +1,349 @@
+//! Ordering the declarations that match an element.
+//!
+//! Declarations are applied from the lowest to the highest precedence:
+//!
+//! 1. User agent declarations, the built in defaults
+//! 2. Author declarations, the stylesheets of the app
+//! 3. Inline styles
+//! 4. `!important` author declarations
+//! 5. `!important` user agent declarations
+//!
+//! Within an origin declarations in later `@layer`s and outside of any layer win over earlier
+//! layers. Layers are shared by name between the stylesheets and ordered by where they first
+//! appear. The layer order is reversed for `!important` declarations. Ties are broken by the
+//! specificity of the selector, then by the order of the stylesheets and lastly by the order
+//! the declarations appear in.
+//!
+//! Properties in [`INHERITED`] start from the computed value of the parent, the others from
+//! their initial value. `inherit`, `initial` and `unset` are resolved as they are applied.
+//!
+//! `transition` and `animation` are resolved the same way by [`motion`]. They are never
+//! inherited.
+
+use std::collections::HashSet;
+
+use crate::{Appearance, Dimensions, Keyword, Media, Motion, Style, Stylesheet, INHERITED};
+
+/// Where the declarations of a stylesheet come from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Origin {
+    /// Defaults provided by the library that every app sheet overrides.
+    UserAgent,
+    #[default]
+    Author,
+}
+
+/// A declaration of a stylesheet rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub style: Style,
+    pub important: bool,
+    pub origin: Origin,
+    /// Index into the layers of the stylesheet, `None` outside of any layer.
+    pub layer: Option<usize>,
+    /// Index of the conditions of the `@media` rules the declaration is nested in.
+    pub media: Option<usize>,
+    /// Position in the stylesheet.
+    pub order: usize,
+}
+
+/// Specificity of a single selector key as `(ids, classes, types)`, see
+/// [`Selector::specificity`](crate::Selector::specificity).
+pub fn specificity(key: &str) -> (u32, u32, u32) {
+    match key.chars().next() {
+        Some('#') => (1, 0, 0),
+        Some('.') | Some(':') => (0, 1, 0),
+        _ => (0, 0, 1),
+    }
+}
+
+/// `(tier, layer, specificity, stylesheet, order)`, see [`matched`].
+type Rank = (u8, usize, (u32, u32, u32), usize, usize);
+
+/// Resolve the styles of the last element of `path`, the selector keys of the elements from the
+/// root of the tree to the element. Stylesheets later in the list come later in source order,
+/// winning over earlier ones when the layer and specificity tie. Declarations in `@media` rules that don't match `media` are skipped.
+/// `parent` is the computed style of the parent element, `None` for the root.
+pub fn cascade(
+    stylesheets: &[&Stylesheet],
+    path: &[HashSet<String>],
+    inline: &[Style],
+    media: &Media,
+    parent: Option<&(Dimensions, Appearance)>,
+) -> (Dimensions, Appearance) {
+    let initial = (Dimensions::default(), Appearance::default());
+    let parent = parent.unwrap_or(&initial);
+    let mut dimensions = Dimensions::default();
+    let mut appearance = Appearance::inherit(&parent.1);
+    for style in matched(stylesheets, path, inline, media) {
+        match style {
+            Style::Keyword(name, keyword) => {
+                let from = match keyword {
+                    Keyword::Inherit => parent,
+                    Keyword::Initial => &initial,
+                    Keyword::Unset if INHERITED.contains(name) => parent,
+                    Keyword::Unset => &initial,
+                };
+                Style::copy(name, from, &mut dimensions, &mut appearance);
+            }
+            style => style.apply(&mut dimensions, &mut appearance),
+        }
+    }
+    (dimensions, appearance)
+}
+
+/// Resolve the transitions and animations of the last element of `path` like [`cascade`].
+/// Animations are paired with the `@keyframes` of the same name in the last stylesheet that
+/// defines them, the ones without any are dropped.
+pub fn motion(
+    stylesheets: &[&Stylesheet],
+    path: &[HashSet<String>],
+    inline: &[Style],
+    media: &Media,
+) -> Motion {
+    let mut transitions = &[][..];
+    let mut animations = &[][..];
+    for style in matched(stylesheets, path, inline, media) {
+        match style {
+            Style::Transition(list) => transitions = list,
+            Style::Animation(list) => animations = list,
+            Style::Keyword("transition", _) => transitions = &[],
+            Style::Keyword("animation", _) => animations = &[],
+            _ => (),
+        }
+    }
+
+    Motion {
+        transitions: transitions.to_vec(),
+        animations: animations
+            .iter()
+            .filter_map(|animation| {
+                let keyframes = stylesheets
+                    .iter()
+                    .rev()
+                    .find_map(|stylesheet| stylesheet.keyframes(&animation.name))?;
+                Some((animation.clone(), keyframes.clone()))
+            })
+            .collect(),
+    }
+}
+
+/// The declarations matching the last element of `path` from the lowest precedence to the
+/// highest.
+fn matched<'a>(
+    stylesheets: &[&'a Stylesheet],
+    path: &[HashSet<String>],
+    inline: &'a [Style],
+    media: &Media,
+) -> Vec<&'a Style> {
+    let mut layers: Vec<&str> = Vec::new();
+    for stylesheet in stylesheets {
+        for layer in stylesheet.layers() {
+            if !layers.contains(&layer.as_str()) {
+                layers.push(layer);
+            }
+        }
+    }
+
+    let mut matched: Vec<(Rank, &Style)> = Vec::new();
+    for (index, stylesheet) in stylesheets.iter().enumerate() {
+        let names = stylesheet.layers();
+        let layer_of = |layer: usize| {
+            layers
+                .iter()
+                .position(|name| *name == names[layer])
+                .unwrap()
+        };
+        for (selector, declarations) in stylesheet.matching(path) {
+            let specificity = selector.specificity();
+            for declaration in declarations {
+                if !stylesheet.applies(declaration, media) {
+                    continue;
+                }
+                let tier = match (declaration.origin, declaration.important) {
+                    (Origin::UserAgent, false) => 0,
+                    (Origin::Author, false) => 1,
+                    (Origin::Author, true) => 3,
+                    (Origin::UserAgent, true) => 4,
+                };
+                let layer = match (declaration.layer, declaration.important) {
+                    (None, false) => layers.len(),
+                    (Some(layer), false) => layer_of(layer),
+                    (None, true) => 0,
+                    (Some(layer), true) => layers.len() - layer_of(layer),
+                };
+                matched.push((
+                    (tier, layer, specificity, index, declaration.order),
+                    &declaration.style,
+                ));
+            }
+        }
+    }
+    for (order, style) in inline.iter().enumerate() {
+        matched.push(((2, 0, (0, 0, 0), 0, order), style));
+    }
+    matched.sort_by_key(|(rank, _)| *rank);
+    matched.into_iter().map(|(_, style)| style).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Unit, Visibility};
+
+    use super::*;
+
+    fn width(stylesheets: &[&Stylesheet], keys: &[&str], inline: &[Style]) -> Unit {
+        let keys = keys.iter().map(|key| key.to_string()).collect();
+        cascade(stylesheets, &[keys], inline, &Media::default(), None)
+            .0
+            .width
+    }
+
+    #[test]
+    fn layers_and_important_follow_the_cascade() {
+        let sheet = Stylesheet::try_parse(
+            "@layer base, components;
+            @layer components { .card { width: 30px; } }
+            @layer base { .card { width: 20px; } text { height: 5px !important; } }
+            .card { width: 40px; }",
+        )
+        .unwrap();
+        assert_eq!(sheet.layers(), ["base", "components"]);
+        // Unlayered rules beat every layer, later layers beat earlier ones
+        assert_eq!(width(&[&sheet], &[".card"], &[]), Unit::PX(40.));
+
+        let layered = Stylesheet::try_parse(
+            "@layer base, components;
+            @layer components { .card { width: 30px; } }
+            @layer base { .card { width: 20px; } }",
+        )
+        .unwrap();
+        assert_eq!(width(&[&layered], &[".card"], &[]), Unit::PX(30.));
+
+        let important = Stylesheet::try_parse(
+            "@layer base, components;
+            @layer base { .card { width: 20px !important; } }
+            @layer components { .card { width: 30px !important; } }
+            .card { width: 40px !important; }",
+        )
+        .unwrap();
+        // Important declarations reverse the layer order
+        assert_eq!(
+            width(&[&important], &[".card"], &[Style::Width(Unit::PX(1.))]),
+            Unit::PX(20.)
+        );
+    }
+
+    #[test]
+    fn origins_specificity_and_inline() {
+        let agent =
+            Stylesheet::try_parse("#title { width: 10px; } text { height: 1px !important; }")
+                .unwrap()
+                .with_origin(Origin::UserAgent);
+        let author = Stylesheet::try_parse(
+            "text { width: 20px; height: 2px !important; } .big { width: 30px; }",
+        )
+        .unwrap();
+
+        // Any author rule beats the user agent, whatever its specificity
+        assert_eq!(
+            width(&[&agent, &author], &["#title", "text"], &[]),
+            Unit::PX(20.)
+        );
+        // Classes beat types within an origin
+        assert_eq!(
+            width(&[&agent, &author], &["text", ".big"], &[]),
+            Unit::PX(30.)
+        );
+        // Inline styles beat normal author rules
+        assert_eq!(
+            width(
+                &[&author],
+                &["text", ".big"],
+                &[Style::Width(Unit::PX(50.))]
+            ),
+            Unit::PX(50.)
+        );
+
+        // Important user agent declarations beat important author ones
+        let keys = HashSet::from(["text".to_string()]);
+        assert_eq!(
+            cascade(&[&agent, &author], &[keys], &[], &Media::default(), None)
+                .0
+                .height,
+            Unit::PX(1.)
+        );
+    }
+
+    #[test]
+    fn stylesheets_break_ties_after_layers_and_specificity() {
+        let first = Stylesheet::try_parse(
+            "@layer base, components;
+            @layer components { .card { width: 30px; } }
+            .card.big { width: 10px; }",
+        )
+        .unwrap();
+        let second = Stylesheet::try_parse(
+            "@layer base { .card.big { width: 20px; } }
+            .card { width: 40px; }",
+        )
+        .unwrap();
+
+        // A more specific selector wins over a later stylesheet
+        assert_eq!(
+            width(&[&first, &second], &[".card", ".big"], &[]),
+            Unit::PX(10.)
+        );
+        // Equal specificity falls back to the order of the stylesheets
+        assert_eq!(width(&[&first, &second], &[".card"], &[]), Unit::PX(40.));
+
+        // Layers keep the order they were first declared in, whichever sheet they are used in
+        let order = Stylesheet::try_parse("@layer base, components;").unwrap();
+        let components =
+            Stylesheet::try_parse("@layer components { .card { width: 30px; } }").unwrap();
+        let base = Stylesheet::try_parse("@layer base { .card { width: 20px; } }").unwrap();
+        assert_eq!(
+            width(&[&order, &components, &base], &[".card"], &[]),
+            Unit::PX(30.)
+        );
+    }
+
+    #[test]
+    fn inherited_properties_and_keywords() {
+        let sheet = Stylesheet::try_parse(
+            ".card { width: 10px; color: red; visibility: hidden; }
+            .title { width: inherit; }
+            .plain { color: initial; visibility: unset; width: unset; }",
+        )
+        .unwrap();
+        let keys = |key: &str| HashSet::from([key.to_string()]);
+        let parent = cascade(&[&sheet], &[keys(".card")], &[], &Media::default(), None);
+
+        let title = cascade(
+            &[&sheet],
+            &[keys(".card"), keys(".title")],
+            &[],
+            &Media::default(),
+            Some(&parent),
+        );
+        assert_eq!(title.0.width, Unit::PX(10.));
+        assert_eq!(title.1.color, parent.1.color);
+        assert_eq!(title.1.visibility, Visibility::Hidden);
+
+        let plain = cascade(
+            &[&sheet],
+            &[keys(".card"), keys(".plain")],
+            &[],
+            &Media::default(),
+            Some(&parent),
+        );
+        assert_eq!(plain.0.width, Unit::Default);
+        assert_eq!(plain.1.color, None);
+        assert_eq!(plain.1.visibility, Visibility::Hidden);
+        assert_eq!(
+            Style::parse("color", "inherit").unwrap().to_string(),
+            "color: inherit"
+        );
+        assert!(Style::parse("color", "inherit 1px").is_err());
+    }
+}
@@ -147,6 +147,12 @@ impl ToCss for Color {
     }
 }
 
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_css(f)
+    }
+}
+
 /// Either a number or a percentage.
 pub enum NumberOrPercentage {
     /// `<number>`.
@@ -0,0 +1,227 @@
+//! Geometry primitives shared by styling and layout.
+//!
+//! Every type is generic over its scalar with whole `i32` pixels as the default. The `f32`
+//! aliases, [`PointF`], [`Size2DF`] and [`InsetsF`], are for sub-pixel math.
+
+use std::{
+    fmt::Debug,
+    ops::{Add, Sub},
+};
+
+/// A number that geometry can be expressed in.
+pub trait Scalar:
+    Copy + Debug + Default + PartialOrd + Add<Output = Self> + Sub<Output = Self>
+{
+    fn half(self) -> Self;
+
+    /// The smaller of both values. Named so it doesn't shadow `Ord::min` and `f32::min`.
+    fn smaller(self, other: Self) -> Self {
+        match self < other {
+            true => self,
+            false => other,
+        }
+    }
+
+    /// The larger of both values.
+    fn larger(self, other: Self) -> Self {
+        match self > other {
+            true => self,
+            false => other,
+        }
+    }
+}
+
+impl Scalar for i32 {
+    fn half(self) -> Self {
+        self / 2
+    }
+}
+
+impl Scalar for f32 {
+    fn half(self) -> Self {
+        self / 2.
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+pub type PointF = Point<f32>;
+
+impl<T: Scalar> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    pub fn translate(&self, x: T, y: T) -> Self {
+        Point {
+            x: self.x + x,
+            y: self.y + y,
+        }
+    }
+}
+
+impl<T: Scalar> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Scalar> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from(value: (i32, i32)) -> Self {
+        Point::new(value.0, value.1)
+    }
+}
+
+impl Point {
+    /// Multiply both coordinates by `factor`, rounding to whole pixels.
+    pub fn scale(&self, factor: f32) -> Point {
+        Point::new(
+            (self.x as f32 * factor).round() as i32,
+            (self.y as f32 * factor).round() as i32,
+        )
+    }
+}
+
+impl From<Point> for PointF {
+    fn from(value: Point) -> Self {
+        Point::new(value.x as f32, value.y as f32)
+    }
+}
+
+impl PointF {
+    /// Round to the nearest device pixel.
+    pub fn round(&self) -> Point {
+        Point::new(self.x.round() as i32, self.y.round() as i32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Size2D<T = i32> {
+    pub width: T,
+    pub height: T,
+}
+
+pub type Size2DF = Size2D<f32>;
+
+impl<T: Scalar> Size2D<T> {
+    pub fn new(width: T, height: T) -> Self {
+        Size2D { width, height }
+    }
+
+    /// A size is empty when either side has no length.
+    pub fn is_empty(&self) -> bool {
+        self.width <= T::default() || self.height <= T::default()
+    }
+}
+
+impl From<Size2D> for Size2DF {
+    fn from(value: Size2D) -> Self {
+        Size2D::new(value.width as f32, value.height as f32)
+    }
+}
+
+impl Size2DF {
+    /// Round to the nearest device pixel.
+    pub fn round(&self) -> Size2D {
+        Size2D::new(self.width.round() as i32, self.height.round() as i32)
+    }
+}
+
+/// Distances from each edge of a rect, used for padding, margin and inset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Insets<T = i32> {
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+    pub left: T,
+}
+
+pub type InsetsF = Insets<f32>;
+
+impl<T: Scalar> Insets<T> {
+    pub fn new(top: T, right: T, bottom: T, left: T) -> Self {
+        Insets {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// The same distance from every edge.
+    pub fn uniform(value: T) -> Self {
+        Insets::new(value, value, value, value)
+    }
+
+    /// Combined left and right distance.
+    pub fn horizontal(&self) -> T {
+        self.left + self.right
+    }
+
+    /// Combined top and bottom distance.
+    pub fn vertical(&self) -> T {
+        self.top + self.bottom
+    }
+}
+
+impl<T: Scalar> Add for Insets<T> {
+    type Output = Insets<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Insets::new(
+            self.top + rhs.top,
+            self.right + rhs.right,
+            self.bottom + rhs.bottom,
+            self.left + rhs.left,
+        )
+    }
+}
+
+impl From<Insets> for InsetsF {
+    fn from(value: Insets) -> Self {
+        Insets::new(
+            value.top as f32,
+            value.right as f32,
+            value.bottom as f32,
+            value.left as f32,
+        )
+    }
+}
+
+impl Insets {
+    /// Multiply every distance by `factor`, rounding to whole pixels.
+    pub fn scale(&self, factor: f32) -> Insets {
+        Insets::new(
+            (self.top as f32 * factor).round() as i32,
+            (self.right as f32 * factor).round() as i32,
+            (self.bottom as f32 * factor).round() as i32,
+            (self.left as f32 * factor).round() as i32,
+        )
+    }
+}
+
+impl InsetsF {
+    /// Round to the nearest device pixel.
+    pub fn round(&self) -> Insets {
+        Insets::new(
+            self.top.round() as i32,
+            self.right.round() as i32,
+            self.bottom.round() as i32,
+            self.left.round() as i32,
+        )
+    }
+}
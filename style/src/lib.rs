@@ -1,16 +1,32 @@
-use std::{collections::{HashMap, HashSet}, fs::File, io::Read, path::Path};
 use itertools::Itertools;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
 
 use cssparser::{Parser, ParserInput, RuleListParser};
 
 use color::Color;
+mod animation;
+mod cascade;
+mod media;
 mod parser;
 mod rules;
+mod selector;
 mod size;
 
 pub mod color;
-pub use parser::{Rule, RuleParser, StyleParser};
+pub mod geometry;
+pub use animation::{
+    Animation, AnimationDirection, Easing, FillMode, Interpolate, Keyframe, Keyframes, Motion,
+    Transition, ANIMATABLE,
+};
+pub use cascade::{cascade, motion, specificity, Declaration, Origin};
+pub use media::{ColorScheme, Feature, Media, MediaQuery, MediaType, Orientation, Query};
+pub use parser::{Import, Rule, RuleParser, StyleError, StyleParser};
 pub use rules::*;
+pub use selector::{Attribute, AttributeCompare, Combinator, Compound, Condition, Selector};
 pub use size::Size;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -26,8 +42,12 @@ pub struct Dimensions {
     pub padding: Size,
     pub margin: Size,
     pub inset: Size,
+    /// Border widths. Borders are drawn inside the element's rect.
+    pub border: Size,
     pub overflow_x: Overflow,
     pub overflow_y: Overflow,
+    /// Stacking order between siblings, `None` for `auto`.
+    pub z_index: Option<i32>,
 }
 
 impl Default for Dimensions {
@@ -44,8 +64,10 @@ impl Default for Dimensions {
             padding: Size::default(),
             margin: Size::default(),
             inset: Size::default(),
+            border: Size::default(),
             overflow_x: Overflow::default(),
             overflow_y: Overflow::default(),
+            z_index: None,
         }
     }
 }
@@ -53,23 +75,59 @@ impl Default for Dimensions {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Appearance {
     pub font_style: FontStyle,
+    /// Text color, `None` for the platform default.
+    pub color: Option<Color>,
+    pub direction: Direction,
+    pub visibility: Visibility,
 
     pub background_color: Option<Color>,
+    /// Border color, `None` to use the text color.
+    pub border_color: Option<Color>,
+}
+
+impl Appearance {
+    /// The appearance an element starts from, the [`INHERITED`] properties of its parent.
+    pub fn inherit(parent: &Appearance) -> Self {
+        Appearance {
+            font_style: parent.font_style,
+            color: parent.color,
+            direction: parent.direction,
+            visibility: parent.visibility,
+            ..Default::default()
+        }
+    }
 }
 
 impl Default for Appearance {
     fn default() -> Self {
         Appearance {
             font_style: FontStyle::default(),
+            color: None,
+            direction: Direction::default(),
+            visibility: Visibility::default(),
             background_color: None,
+            border_color: None,
         }
     }
 }
 
-#[derive(Debug, Default)]
+/// The rules of a stylesheet keyed by their selector.
+#[derive(Debug, Clone, Default)]
 pub struct Stylesheet {
-    rules: HashMap<String, Vec<Style>>,
-    cached: HashMap<String, (Dimensions, Appearance)>,
+    rules: HashMap<String, Vec<Declaration>>,
+    selectors: HashMap<String, Selector>,
+    /// Selectors by their [`Selector::subject`] so only rules that can match are tested.
+    subjects: HashMap<String, Vec<String>>,
+    /// Names of the layers in the order they were first declared.
+    layers: Vec<String>,
+    /// Conditions of the rules nested in `@media` blocks, every query has to match.
+    media: Vec<Vec<MediaQuery>>,
+    /// Position of the next declaration.
+    next: usize,
+    /// Files the rules were loaded from.
+    sources: Vec<PathBuf>,
+    /// `@keyframes` rules by name, a later rule replaces an earlier one with the same name.
+    keyframes: HashMap<String, Keyframes>,
 }
 
 unsafe impl Send for Stylesheet {}
@@ -77,132 +135,712 @@ unsafe impl Sync for Stylesheet {}
 
 impl Stylesheet {
     pub fn dup(&mut self, src: Stylesheet) {
-        self.rules = src.rules.clone();
-        self.cached = HashMap::new();
+        *self = src;
     }
 
-    pub fn parse(src: &str) -> Self {
-        let mut input = ParserInput::new(src);
-        let mut input = Parser::new(&mut input);
-        let rules: Vec<Rule> = RuleListParser::new_for_stylesheet(&mut input, RuleParser {})
-            .collect::<Vec<_>>()
+    /// Add the rules of another stylesheet after the existing ones so they take precedence.
+    /// Layers declared by both keep the position they have in this stylesheet.
+    pub fn extend(&mut self, src: Stylesheet) {
+        let layers: Vec<usize> = src.layers.iter().map(|name| self.layer(name)).collect();
+        let media: Vec<usize> = src
+            .media
             .into_iter()
-            .filter_map(|rule| rule.ok())
+            .map(|media| self.condition(media))
             .collect();
-
-        let mut styles = HashMap::new();
-        for rule in rules {
-            styles.insert(rule.key, rule.styles);
+        for (key, selector) in src.selectors {
+            self.index(key, selector);
+        }
+        for (key, declarations) in src.rules {
+            let entry = self.rules.entry(key).or_default();
+            for mut declaration in declarations {
+                declaration.layer = declaration.layer.map(|layer| layers[layer]);
+                declaration.media = declaration.media.map(|condition| media[condition]);
+                declaration.order += self.next;
+                entry.push(declaration);
+            }
         }
+        self.next += src.next;
+        self.sources.extend(src.sources);
+        self.keyframes.extend(src.keyframes);
+    }
 
-        Stylesheet {
-            rules: styles,
-            cached: HashMap::new(),
+    /// Set the origin of every declaration, e.g. for the defaults of a library.
+    pub fn with_origin(mut self, origin: Origin) -> Self {
+        for declaration in self.rules.values_mut().flatten() {
+            declaration.origin = origin;
         }
+        self
+    }
+
+    /// The `@keyframes` rule with the name.
+    pub fn keyframes(&self, name: &str) -> Option<&Keyframes> {
+        self.keyframes.get(name)
+    }
+
+    /// The layer names in cascade order, the last one takes precedence.
+    pub fn layers(&self) -> &[String] {
+        &self.layers
     }
 
-    pub fn file(path: &str) -> Self {
-        let path = Path::new(path);
-        let mut file = match File::open(path) {
-            Err(error) => panic!("Couldn't open file '{}': {}", path.display(), error),
-            Ok(file) => file,
+    /// The declarations of the rules with the selector key in the order they were declared.
+    pub fn declarations(&self, key: &str) -> &[Declaration] {
+        self.rules.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// The rules whose selector matches the last element of `path`, which holds the keys of the
+    /// elements from the root of the tree to the element.
+    pub fn matching(&self, path: &[HashSet<String>]) -> Vec<(&Selector, &[Declaration])> {
+        let keys = match path.last() {
+            Some(keys) => keys,
+            None => return Vec::new(),
         };
+        keys.iter()
+            .map(String::as_str)
+            .chain(["*"])
+            .filter_map(|key| self.subjects.get(key))
+            .flatten()
+            .map(|key| (&self.selectors[key], self.declarations(key)))
+            .filter(|(selector, _)| selector.matches(path))
+            .collect()
+    }
 
-        let mut s = String::new();
-        match file.read_to_string(&mut s) {
-            Err(error) => panic!("Couldn't read file '{}': {}", path.display(), error),
-            Ok(_) => (),
+    fn index(&mut self, key: String, selector: Selector) {
+        if !self.selectors.contains_key(&key) {
+            self.subjects
+                .entry(selector.subject().to_string())
+                .or_default()
+                .push(key.clone());
+            self.selectors.insert(key, selector);
         }
+    }
 
-        Stylesheet::parse(s.as_str())
+    /// Whether the `@media` rules a declaration is nested in match, always true outside of them.
+    pub fn applies(&self, declaration: &Declaration, media: &Media) -> bool {
+        declaration.media.map_or(true, |condition| {
+            self.media[condition]
+                .iter()
+                .all(|query| query.matches(media))
+        })
     }
 
-    pub fn get_styles(&mut self, rules: HashSet<String>) -> (Dimensions, Appearance) {
-        let mut dimensions = Dimensions::default();
-        let mut appearance = Appearance::default();
+    /// Whether any `@media` rule of the stylesheet matches one media but not the other, so
+    /// elements styled by it need to be restyled.
+    pub fn media_changed(&self, old: &Media, new: &Media) -> bool {
+        self.media
+            .iter()
+            .flatten()
+            .any(|query| query.matches(old) != query.matches(new))
+    }
 
-        let key = rules.iter().join("::");
+    /// Index of a media condition, adding it if it is new.
+    fn condition(&mut self, media: Vec<MediaQuery>) -> usize {
+        match self.media.iter().position(|condition| *condition == media) {
+            Some(index) => index,
+            None => {
+                self.media.push(media);
+                self.media.len() - 1
+            }
+        }
+    }
 
-        if self.cached.contains_key(&key) {
-            if let Some(styles) = self.cached.get(&key) {
-                return styles.clone();
+    /// Index of a layer, declaring it after the existing ones if it is new.
+    fn layer(&mut self, name: &str) -> usize {
+        match self.layers.iter().position(|layer| layer == name) {
+            Some(index) => index,
+            None => {
+                self.layers.push(name.to_string());
+                self.layers.len() - 1
             }
         }
+    }
 
-        for rule in rules.iter() {
-            if self.rules.contains_key(rule) {
-                for style in self.rules.get(rule).unwrap().iter() {
-                    match style {
-                        Style::MinWidth(min_width) => dimensions.min_width = *min_width,
-                        Style::Width(width) => dimensions.width = *width,
-                        Style::MaxWidth(max_width) => dimensions.max_width = *max_width,
-                        Style::MinHeight(min_height) => dimensions.min_height = *min_height,
-                        Style::Height(height) => dimensions.height = *height,
-                        Style::MaxHeight(max_height) => dimensions.max_height = *max_height,
+    fn add_rule(&mut self, rule: Rule) {
+        if let Some(keyframes) = rule.keyframes {
+            self.keyframes.insert(keyframes.name.clone(), keyframes);
+            return;
+        }
+        let layer = rule.layer.as_deref().map(|name| self.layer(name));
+        if rule.key.is_empty() {
+            return;
+        }
+        let media = match rule.media.is_empty() {
+            true => None,
+            false => Some(self.condition(rule.media)),
+        };
 
-                        Style::FontStyle(font_style) => appearance.font_style = *font_style,
+        let styles = rule
+            .styles
+            .into_iter()
+            .map(|style| (style, false))
+            .chain(rule.important.into_iter().map(|style| (style, true)));
+        self.index(rule.key.clone(), rule.selector);
+        let entry = self.rules.entry(rule.key).or_default();
+        for (style, important) in styles {
+            entry.push(Declaration {
+                style,
+                important,
+                origin: Origin::Author,
+                layer,
+                media,
+                order: self.next,
+            });
+            self.next += 1;
+        }
+    }
 
-                        Style::BackgroundColor(color) => appearance.background_color = Some(*color),
-                        Style::Position(position) => dimensions.position = *position,
+    /// Parse a stylesheet, skipping rules and declarations that are invalid. Returns their errors
+    /// along with the rules that could be parsed. `@import`s are resolved relative to the
+    /// current directory.
+    pub fn parse(src: &str) -> (Self, Vec<StyleError>) {
+        let dir = env::current_dir().unwrap_or_default();
+        Stylesheet::parse_source(src, &dir, &mut Vec::new())
+    }
 
-                        Style::Padding(size) => dimensions.padding = *size,
-                        Style::PaddingInline(inline) => {
-                            dimensions.padding.left = inline.clone();
-                            dimensions.padding.right = *inline;
-                        }
-                        Style::PaddingBlock(block) => {
-                            dimensions.padding.top = block.clone();
-                            dimensions.padding.bottom = *block;
-                        }
-                        Style::PaddingLeft(left) => dimensions.padding.left = *left,
-                        Style::PaddingTop(top) => dimensions.padding.top = *top,
-                        Style::PaddingRight(right) => dimensions.padding.right = *right,
-                        Style::PaddingBottom(bottom) => dimensions.padding.bottom = *bottom,
-
-                        Style::Margin(size) => dimensions.margin = *size,
-                        Style::MarginInline(inline) => {
-                            dimensions.margin.left = inline.clone();
-                            dimensions.margin.right = *inline;
-                        }
-                        Style::MarginBlock(block) => {
-                            dimensions.margin.top = block.clone();
-                            dimensions.margin.bottom = *block;
-                        }
-                        Style::MarginTop(top) => dimensions.margin.top = *top,
-                        Style::MarginLeft(left) => dimensions.margin.left = *left,
-                        Style::MarginRight(right) => dimensions.margin.right = *right,
-                        Style::MarginBottom(bottom) => dimensions.margin.bottom = *bottom,
-
-                        Style::Inset(inset) => dimensions.inset = *inset,
-                        Style::InsetBlock(block) => {
-                            dimensions.inset.left = block.clone();
-                            dimensions.inset.left = *block;
-                        }
-                        Style::InsetInline(inline) => {
-                            dimensions.inset.top = inline.clone();
-                            dimensions.inset.bottom = *inline;
-                        }
-                        Style::Top(top) => dimensions.inset.top = *top,
-                        Style::Left(left) => dimensions.inset.left = *left,
-                        Style::Right(right) => dimensions.inset.right = *right,
-                        Style::Bottom(bottom) => dimensions.inset.bottom = *bottom,
-                        Style::Overflow(overflow) => {
-                            dimensions.overflow_x = overflow.clone();
-                            dimensions.overflow_y = *overflow
-                        }
-                        Style::OverflowX(overflow) => {
-                            dimensions.overflow_x = *overflow;
+    /// Parse a stylesheet, failing if any rule or declaration is invalid.
+    pub fn try_parse(src: &str) -> Result<Self, Vec<StyleError>> {
+        match Stylesheet::parse(src) {
+            (stylesheet, errors) if errors.is_empty() => Ok(stylesheet),
+            (_, errors) => Err(errors),
+        }
+    }
+
+    /// Parse the source of a stylesheet, loading its imports relative to `dir`. `stack` holds
+    /// the files being imported to detect cycles.
+    fn parse_source(src: &str, dir: &Path, stack: &mut Vec<PathBuf>) -> (Self, Vec<StyleError>) {
+        let mut input = ParserInput::new(src);
+        let mut input = Parser::new(&mut input);
+
+        let mut stylesheet = Stylesheet::default();
+        let mut errors = Vec::new();
+        // Imports have to precede every rule apart from `@layer` statements
+        let mut imports = true;
+        for rules in RuleListParser::new_for_stylesheet(&mut input, RuleParser::default()) {
+            match rules {
+                Ok(rules) => {
+                    // Any other rule ends the imports, even an empty one or one with only
+                    // invalid declarations
+                    imports &= !rules.is_empty()
+                        && rules
+                            .iter()
+                            .all(|rule| rule.import.is_some() || rule.is_layer_statement());
+                    for mut rule in rules {
+                        errors.append(&mut rule.errors);
+                        let import = match rule.import.take() {
+                            Some(import) => import,
+                            None => {
+                                stylesheet.add_rule(rule);
+                                continue;
+                            }
+                        };
+
+                        if !imports || rule.layer.is_some() || !rule.media.is_empty() {
+                            errors.push(StyleError::new(
+                                import.location,
+                                format!(
+                                    "@import '{}' has to come before any other rule",
+                                    import.url
+                                ),
+                            ));
+                            continue;
                         }
-                        Style::OverflowY(overflow) => {
-                            dimensions.overflow_y = *overflow;
+                        match Stylesheet::load_file(&dir.join(&import.url), stack) {
+                            Ok((imported, mut import_errors)) => {
+                                errors.append(&mut import_errors);
+                                stylesheet
+                                    .extend(imported.nest(import.layer.as_deref(), import.media));
+                            }
+                            Err(message) => errors.push(StyleError::new(import.location, message)),
                         }
-                        Style::Variable => (),
-                    };
+                    }
+                }
+                Err((error, source)) => {
+                    imports = false;
+                    errors.push(StyleError::invalid_rule(error.location, source));
                 }
             }
         }
+        (stylesheet, errors)
+    }
+
+    /// Load a stylesheet and its imports, failing if the file can't be read or is part of an
+    /// import cycle. Errors in the file are attributed to it.
+    fn load_file(path: &Path, stack: &mut Vec<PathBuf>) -> Result<(Self, Vec<StyleError>), String> {
+        let canonical = fs::canonicalize(path)
+            .map_err(|error| format!("couldn't open '{}': {}", path.display(), error))?;
+        if let Some(start) = stack.iter().position(|file| *file == canonical) {
+            let cycle = stack[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|file| file.file_name().unwrap_or_default().to_string_lossy())
+                .join(" -> ");
+            return Err(format!("import cycle {}", cycle));
+        }
+        let src = fs::read_to_string(&canonical)
+            .map_err(|error| format!("couldn't read '{}': {}", path.display(), error))?;
+
+        stack.push(canonical.clone());
+        let dir = canonical.parent().unwrap_or(Path::new(""));
+        let (mut stylesheet, mut errors) = Stylesheet::parse_source(&src, dir, stack);
+        stack.pop();
+
+        stylesheet.sources.insert(0, path.to_path_buf());
+        for error in errors.iter_mut().filter(|error| error.file.is_none()) {
+            error.file = Some(path.to_path_buf());
+        }
+        Ok((stylesheet, errors))
+    }
+
+    /// Put the rules of an imported stylesheet in the layer and under the media query of the
+    /// `@import`. Its own layers become sublayers of the layer.
+    fn nest(mut self, layer: Option<&str>, media: Option<MediaQuery>) -> Self {
+        if let Some(layer) = layer {
+            let mut layers = vec![layer.to_string()];
+            layers.extend(self.layers.iter().map(|name| format!("{}.{}", layer, name)));
+            self.layers = layers;
+            for declaration in self.rules.values_mut().flatten() {
+                declaration.layer = Some(declaration.layer.map_or(0, |layer| layer + 1));
+            }
+        }
+
+        if let Some(query) = media {
+            let mut conditions: Vec<Vec<MediaQuery>> = self
+                .media
+                .iter()
+                .map(|condition| {
+                    [query.clone()]
+                        .into_iter()
+                        .chain(condition.iter().cloned())
+                        .collect()
+                })
+                .collect();
+            conditions.push(vec![query]);
+            let unconditional = conditions.len() - 1;
+            self.media = conditions;
+            for declaration in self.rules.values_mut().flatten() {
+                declaration.media = Some(declaration.media.unwrap_or(unconditional));
+            }
+        }
+        self
+    }
+
+    /// Load a stylesheet from a file. `@import`s are resolved relative to the file that
+    /// contains them. Returns the errors of the file and its imports along with the rules that
+    /// could be parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> (Self, Vec<StyleError>) {
+        let path = path.as_ref();
+        match Stylesheet::load_file(path, &mut Vec::new()) {
+            Ok(loaded) => loaded,
+            Err(message) => {
+                let stylesheet = Stylesheet {
+                    sources: vec![path.to_path_buf()],
+                    ..Default::default()
+                };
+                let error = StyleError {
+                    file: Some(path.to_path_buf()),
+                    line: 0,
+                    column: 0,
+                    message,
+                };
+                (stylesheet, vec![error])
+            }
+        }
+    }
+
+    /// Load a stylesheet from a file, failing if it or any of its imports has errors.
+    pub fn try_file<P: AsRef<Path>>(path: P) -> Result<Self, Vec<StyleError>> {
+        match Stylesheet::load(path) {
+            (stylesheet, errors) if errors.is_empty() => Ok(stylesheet),
+            (_, errors) => Err(errors),
+        }
+    }
+
+    /// Load a stylesheet from a file, see [`Stylesheet::load`]. A file that can't be read is
+    /// reported as an error.
+    pub fn file(path: &str) -> (Self, Vec<StyleError>) {
+        Stylesheet::load(path)
+    }
+
+    /// The files the stylesheet was loaded from, the file itself followed by its imports.
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+
+    pub fn get_styles(&self, rules: HashSet<String>) -> (Dimensions, Appearance) {
+        cascade(&[self], &[rules], &[], &Media::default(), None)
+    }
+
+    /// Write the rules of a layer, the ones in `@media` rules wrapped in their blocks.
+    fn write_rules(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        layer: Option<usize>,
+        indent: &str,
+    ) -> fmt::Result {
+        self.write_block(f, layer, None, indent)?;
+        for (index, condition) in self.media.iter().enumerate() {
+            let used =
+                self.rules.values().flatten().any(|declaration| {
+                    declaration.layer == layer && declaration.media == Some(index)
+                });
+            if !used {
+                continue;
+            }
+
+            let mut inner = indent.to_string();
+            for query in condition {
+                writeln!(f, "{}@media {} {{", inner, query)?;
+                inner.push_str("  ");
+            }
+            self.write_block(f, layer, Some(index), &inner)?;
+            for _ in condition {
+                inner.truncate(inner.len() - 2);
+                writeln!(f, "{}}}", inner)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_block(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        layer: Option<usize>,
+        media: Option<usize>,
+        indent: &str,
+    ) -> fmt::Result {
+        for key in self.rules.keys().sorted() {
+            let declarations: Vec<&Declaration> = self.rules[key]
+                .iter()
+                .filter(|declaration| {
+                    declaration.layer == layer
+                        && declaration.media == media
+                        && declaration.style != Style::Variable
+                })
+                .collect();
+            if declarations.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "{}{} {{", indent, key)?;
+            for declaration in declarations {
+                match declaration.important {
+                    true => writeln!(f, "{}  {} !important;", indent, declaration.style)?,
+                    false => writeln!(f, "{}  {};", indent, declaration.style)?,
+                }
+            }
+            writeln!(f, "{}}}", indent)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize the rules as css, sorted by selector so the output is stable. Layered rules are
+/// wrapped in their `@layer` blocks and conditional ones in their `@media` blocks, `@keyframes`
+/// come last.
+impl fmt::Display for Stylesheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let named: Vec<&String> = self
+            .layers
+            .iter()
+            .filter(|name| !name.contains('<'))
+            .collect();
+        if !named.is_empty() {
+            writeln!(f, "@layer {};", named.iter().join(", "))?;
+        }
+        for (index, name) in self.layers.iter().enumerate() {
+            match name.contains('<') {
+                true => writeln!(f, "@layer {{")?,
+                false => writeln!(f, "@layer {} {{", name)?,
+            }
+            self.write_rules(f, Some(index), "  ")?;
+            writeln!(f, "}}")?;
+        }
+        self.write_rules(f, None, "")?;
+        for name in self.keyframes.keys().sorted() {
+            write!(f, "{}", self.keyframes[name])?;
+        }
+        Ok(())
+    }
+}
+
+impl Style {
+    /// Apply the style on top of already computed dimensions and appearance.
+    pub fn apply(&self, dimensions: &mut Dimensions, appearance: &mut Appearance) {
+        match self {
+            Style::MinWidth(min_width) => dimensions.min_width = *min_width,
+            Style::Width(width) => dimensions.width = *width,
+            Style::MaxWidth(max_width) => dimensions.max_width = *max_width,
+            Style::MinHeight(min_height) => dimensions.min_height = *min_height,
+            Style::Height(height) => dimensions.height = *height,
+            Style::MaxHeight(max_height) => dimensions.max_height = *max_height,
+
+            Style::FontStyle(font_style) => appearance.font_style = *font_style,
+            Style::Direction(direction) => appearance.direction = *direction,
+            Style::Visibility(visibility) => appearance.visibility = *visibility,
+
+            Style::BackgroundColor(color) => appearance.background_color = Some(*color),
+            Style::Color(color) => appearance.color = Some(*color),
+            Style::Border(width, color) => {
+                dimensions.border = Size::from(*width);
+                appearance.border_color = *color;
+            }
+            Style::BorderWidth(size) => dimensions.border = *size,
+            Style::BorderColor(color) => appearance.border_color = Some(*color),
+            Style::Position(position) => dimensions.position = *position,
+            Style::ZIndex(z_index) => dimensions.z_index = *z_index,
+
+            Style::Padding(size) => dimensions.padding = *size,
+            Style::PaddingInline(inline) => {
+                dimensions.padding.left = inline.clone();
+                dimensions.padding.right = *inline;
+            }
+            Style::PaddingBlock(block) => {
+                dimensions.padding.top = block.clone();
+                dimensions.padding.bottom = *block;
+            }
+            Style::PaddingLeft(left) => dimensions.padding.left = *left,
+            Style::PaddingTop(top) => dimensions.padding.top = *top,
+            Style::PaddingRight(right) => dimensions.padding.right = *right,
+            Style::PaddingBottom(bottom) => dimensions.padding.bottom = *bottom,
+
+            Style::Margin(size) => dimensions.margin = *size,
+            Style::MarginInline(inline) => {
+                dimensions.margin.left = inline.clone();
+                dimensions.margin.right = *inline;
+            }
+            Style::MarginBlock(block) => {
+                dimensions.margin.top = block.clone();
+                dimensions.margin.bottom = *block;
+            }
+            Style::MarginTop(top) => dimensions.margin.top = *top,
+            Style::MarginLeft(left) => dimensions.margin.left = *left,
+            Style::MarginRight(right) => dimensions.margin.right = *right,
+            Style::MarginBottom(bottom) => dimensions.margin.bottom = *bottom,
+
+            Style::Inset(inset) => dimensions.inset = *inset,
+            Style::InsetBlock(block) => {
+                dimensions.inset.left = block.clone();
+                dimensions.inset.left = *block;
+            }
+            Style::InsetInline(inline) => {
+                dimensions.inset.top = inline.clone();
+                dimensions.inset.bottom = *inline;
+            }
+            Style::Top(top) => dimensions.inset.top = *top,
+            Style::Left(left) => dimensions.inset.left = *left,
+            Style::Right(right) => dimensions.inset.right = *right,
+            Style::Bottom(bottom) => dimensions.inset.bottom = *bottom,
+            Style::Overflow(overflow) => {
+                dimensions.overflow_x = overflow.clone();
+                dimensions.overflow_y = *overflow
+            }
+            Style::OverflowX(overflow) => {
+                dimensions.overflow_x = *overflow;
+            }
+            Style::OverflowY(overflow) => {
+                dimensions.overflow_y = *overflow;
+            }
+            Style::Variable | Style::Keyword(..) | Style::Transition(_) | Style::Animation(_) => (),
+        };
+    }
+
+    /// Copy the computed value of a property, used to resolve `inherit`, `initial` and `unset`.
+    pub fn copy(
+        name: &str,
+        from: &(Dimensions, Appearance),
+        dimensions: &mut Dimensions,
+        appearance: &mut Appearance,
+    ) {
+        let (source, from) = from;
+        match name {
+            "background-color" => appearance.background_color = from.background_color,
+            "color" => appearance.color = from.color,
+            "font-style" => appearance.font_style = from.font_style,
+            "direction" => appearance.direction = from.direction,
+            "visibility" => appearance.visibility = from.visibility,
+            "border" => {
+                dimensions.border = source.border;
+                appearance.border_color = from.border_color;
+            }
+            "border-width" => dimensions.border = source.border,
+            "border-color" => appearance.border_color = from.border_color,
+            "min-height" => dimensions.min_height = source.min_height,
+            "height" => dimensions.height = source.height,
+            "max-height" => dimensions.max_height = source.max_height,
+            "min-width" => dimensions.min_width = source.min_width,
+            "width" => dimensions.width = source.width,
+            "max-width" => dimensions.max_width = source.max_width,
+            "position" => dimensions.position = source.position,
+            "z-index" => dimensions.z_index = source.z_index,
+            "inset" => dimensions.inset = source.inset,
+            "inset-block" => {
+                dimensions.inset.top = source.inset.top;
+                dimensions.inset.bottom = source.inset.bottom;
+            }
+            "inset-inline" => {
+                dimensions.inset.left = source.inset.left;
+                dimensions.inset.right = source.inset.right;
+            }
+            "left" => dimensions.inset.left = source.inset.left,
+            "top" => dimensions.inset.top = source.inset.top,
+            "right" => dimensions.inset.right = source.inset.right,
+            "bottom" => dimensions.inset.bottom = source.inset.bottom,
+            "padding" => dimensions.padding = source.padding,
+            "padding-block" => {
+                dimensions.padding.top = source.padding.top;
+                dimensions.padding.bottom = source.padding.bottom;
+            }
+            "padding-inline" => {
+                dimensions.padding.left = source.padding.left;
+                dimensions.padding.right = source.padding.right;
+            }
+            "padding-left" => dimensions.padding.left = source.padding.left,
+            "padding-top" => dimensions.padding.top = source.padding.top,
+            "padding-right" => dimensions.padding.right = source.padding.right,
+            "padding-bottom" => dimensions.padding.bottom = source.padding.bottom,
+            "margin" => dimensions.margin = source.margin,
+            "margin-block" => {
+                dimensions.margin.top = source.margin.top;
+                dimensions.margin.bottom = source.margin.bottom;
+            }
+            "margin-inline" => {
+                dimensions.margin.left = source.margin.left;
+                dimensions.margin.right = source.margin.right;
+            }
+            "margin-left" => dimensions.margin.left = source.margin.left,
+            "margin-top" => dimensions.margin.top = source.margin.top,
+            "margin-right" => dimensions.margin.right = source.margin.right,
+            "margin-bottom" => dimensions.margin.bottom = source.margin.bottom,
+            "overflow" => {
+                dimensions.overflow_x = source.overflow_x;
+                dimensions.overflow_y = source.overflow_y;
+            }
+            "overflow-x" => dimensions.overflow_x = source.overflow_x,
+            "overflow-y" => dimensions.overflow_y = source.overflow_y,
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_follow_source_order() {
+        let dir = env::temp_dir().join(format!("imports-{}", std::process::id()));
+        fs::create_dir_all(dir.join("parts")).unwrap();
+        fs::write(
+            dir.join("parts/tokens.css"),
+            ".card { width: 10px; height: 5px; }",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("parts/base.css"),
+            "@import \"tokens.css\";\n.card { width: 20px; }",
+        )
+        .unwrap();
+        fs::write(dir.join("parts/components.css"), ".card { width: 99px; }").unwrap();
+        fs::write(
+            dir.join("main.css"),
+            "@import \"parts/base.css\";\n@import \"missing.css\";\n\
+             @import url(parts/components.css) layer(components);\n.card { height: 7px; }\n\
+             @import \"parts/tokens.css\";",
+        )
+        .unwrap();
+        fs::write(dir.join("a.css"), "@import \"b.css\";").unwrap();
+        fs::write(dir.join("b.css"), "@import \"a.css\";").unwrap();
+
+        let (sheet, errors) = Stylesheet::load(dir.join("main.css"));
+        let card = sheet.get_styles(HashSet::from([".card".to_string()])).0;
+        // Later rules win over imported ones, layered imports lose to unlayered rules
+        assert_eq!(card.width, Unit::PX(20.));
+        assert_eq!(card.height, Unit::PX(7.));
+        assert_eq!(sheet.layers(), ["components"]);
+        assert_eq!(sheet.sources().len(), 4);
+
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert_eq!(
+            (errors[0].file.as_deref(), errors[0].line),
+            (Some(&*dir.join("main.css")), 2)
+        );
+        assert!(errors[0].message.contains("missing.css"));
+        assert!(errors[1].message.contains("has to come before"));
+
+        let errors = Stylesheet::try_file(dir.join("a.css")).unwrap_err();
+        assert_eq!(errors[0].message, "import cycle a.css -> b.css -> a.css");
+        assert_eq!(errors[0].file.as_deref(), Some(&*dir.join("b.css")));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn imports_after_empty_rules_are_rejected() {
+        for src in [
+            "@media (min-width: 10px) {}\n@import \"a.css\";",
+            ".card {}\n@import \"a.css\";",
+            ".a { bogus: 1 }\n@import \"b.css\";",
+        ] {
+            let (_, errors) = Stylesheet::parse_source(src, &env::temp_dir(), &mut Vec::new());
+            let last = errors.last().unwrap();
+            assert!(last.message.contains("has to come before"), "{}", src);
+            assert_eq!(last.line, 2);
+        }
+    }
+
+    #[test]
+    fn anonymous_layers_stay_distinct_across_sheets() {
+        // Both anonymous layers start at 1:1 of their sheet
+        let mut sheet = Stylesheet::try_parse(
+            "@layer { .a { width: 10px; } }\n@layer base { .a { width: 20px; } }",
+        )
+        .unwrap();
+        sheet.extend(Stylesheet::try_parse("@layer { .a { width: 30px; } }").unwrap());
+
+        assert_eq!(sheet.layers().len(), 3);
+        let a = HashSet::from([".a".to_string()]);
+        assert_eq!(sheet.get_styles(a).0.width, Unit::PX(30.));
+    }
+
+    #[test]
+    fn nested_rules_flatten() {
+        let sheet = Stylesheet::try_parse(
+            ".card, .panel {\n  width: 10px;\n  &.active { width: 20px; }\n  > .title { height: 5px; }\n  \
+             .icon { height: 2px; }\n  @media (max-width: 100px) { width: 1px; }\n  height: 3px;\n}",
+        ).unwrap();
+        let keys = |keys: &[&str]| {
+            keys.iter()
+                .map(|key| key.to_string())
+                .collect::<HashSet<_>>()
+        };
+        let styles =
+            |path: &[HashSet<String>], media: &Media| cascade(&[&sheet], path, &[], media, None).0;
+        let wide = Media {
+            width: 500.,
+            ..Default::default()
+        };
+        let narrow = Media {
+            width: 50.,
+            ..Default::default()
+        };
+
+        assert_eq!(styles(&[keys(&[".card"])], &wide).width, Unit::PX(10.));
+        assert_eq!(styles(&[keys(&[".card"])], &wide).height, Unit::PX(3.));
+        assert_eq!(
+            styles(&[keys(&[".panel", ".active"])], &wide).width,
+            Unit::PX(20.)
+        );
+        assert_eq!(styles(&[keys(&[".card"])], &narrow).width, Unit::PX(1.));
+
+        let title = [keys(&[".card"]), keys(&[".title"])];
+        assert_eq!(styles(&title, &wide).height, Unit::PX(5.));
+        let nested = [
+            keys(&[".card"]),
+            keys(&[".body"]),
+            keys(&[".title", ".icon"]),
+        ];
+        assert_eq!(styles(&nested, &wide).height, Unit::PX(2.));
+        assert_eq!(styles(&[keys(&[".title"])], &wide).height, Unit::Default);
 
-        self.cached.insert(key.clone(), (dimensions, appearance));
-        self.cached.get(&key).unwrap().clone()
+        assert!(sheet.to_string().contains(".card > .title"), "{}", sheet);
     }
 }
@@ -0,0 +1,375 @@
+//! Media queries
+//!
+//! `@media` rules only apply while their query matches the [`Media`] of the window an element
+//! is in. Supported are the `all`, `screen` and `print` media types, `not` and `only`, and the
+//! features `min-width`, `max-width`, `min-height`, `max-height`, `orientation`,
+//! `prefers-color-scheme`, `prefers-reduced-motion` and `resolution` with its `min-` and `max-`
+//! variants. Lengths are in logical pixels.
+
+use std::fmt;
+
+use cssparser::{BasicParseError, BasicParseErrorKind, ParseError, Parser, Token};
+use itertools::Itertools;
+
+/// The color scheme the user prefers, `prefers-color-scheme`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorScheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait,
+    Landscape,
+}
+
+/// What media queries are evaluated against: the client area of a window and the preferences
+/// of the user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Media {
+    /// Width of the client area in logical pixels.
+    pub width: f32,
+    /// Height of the client area in logical pixels.
+    pub height: f32,
+    /// Device pixels per logical pixel.
+    pub resolution: f32,
+    pub color_scheme: ColorScheme,
+    pub reduced_motion: bool,
+}
+
+impl Default for Media {
+    fn default() -> Self {
+        Media {
+            width: 0.,
+            height: 0.,
+            resolution: 1.,
+            color_scheme: ColorScheme::default(),
+            reduced_motion: false,
+        }
+    }
+}
+
+impl Media {
+    /// The viewport is portrait when it is at least as tall as it is wide.
+    pub fn orientation(&self) -> Orientation {
+        match self.height >= self.width {
+            true => Orientation::Portrait,
+            false => Orientation::Landscape,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaType {
+    #[default]
+    All,
+    Screen,
+    /// Never matches, windows are always on a screen.
+    Print,
+}
+
+/// A media feature, `(min-width: 600px)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feature {
+    MinWidth(f32),
+    MaxWidth(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    Orientation(Orientation),
+    ColorScheme(ColorScheme),
+    ReducedMotion(bool),
+    /// Resolutions are in dots per pixel, `2dppx` or `192dpi`.
+    Resolution(f32),
+    MinResolution(f32),
+    MaxResolution(f32),
+}
+
+impl Feature {
+    pub fn matches(&self, media: &Media) -> bool {
+        match *self {
+            Feature::MinWidth(width) => media.width >= width,
+            Feature::MaxWidth(width) => media.width <= width,
+            Feature::MinHeight(height) => media.height >= height,
+            Feature::MaxHeight(height) => media.height <= height,
+            Feature::Orientation(orientation) => media.orientation() == orientation,
+            Feature::ColorScheme(scheme) => media.color_scheme == scheme,
+            Feature::ReducedMotion(reduce) => media.reduced_motion == reduce,
+            Feature::Resolution(resolution) => media.resolution == resolution,
+            Feature::MinResolution(resolution) => media.resolution >= resolution,
+            Feature::MaxResolution(resolution) => media.resolution <= resolution,
+        }
+    }
+
+    fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Feature, ParseError<'i, BasicParseError<'i>>> {
+        let location = input.current_source_location();
+        let name = input.expect_ident_cloned()?.to_ascii_lowercase();
+        if input.is_exhausted() {
+            // Boolean context, `(prefers-reduced-motion)`
+            return match name.as_str() {
+                "prefers-reduced-motion" => Ok(Feature::ReducedMotion(true)),
+                name => Err(unexpected(location, name)),
+            };
+        }
+        input.expect_colon()?;
+
+        let feature = match name.as_str() {
+            "min-width" => Feature::MinWidth(parse_length(input)?),
+            "max-width" => Feature::MaxWidth(parse_length(input)?),
+            "min-height" => Feature::MinHeight(parse_length(input)?),
+            "max-height" => Feature::MaxHeight(parse_length(input)?),
+            "resolution" => Feature::Resolution(parse_resolution(input)?),
+            "min-resolution" => Feature::MinResolution(parse_resolution(input)?),
+            "max-resolution" => Feature::MaxResolution(parse_resolution(input)?),
+            "orientation" => match &*input.expect_ident_cloned()?.to_ascii_lowercase() {
+                "portrait" => Feature::Orientation(Orientation::Portrait),
+                "landscape" => Feature::Orientation(Orientation::Landscape),
+                value => return Err(unexpected(location, value)),
+            },
+            "prefers-color-scheme" => match &*input.expect_ident_cloned()?.to_ascii_lowercase() {
+                "light" => Feature::ColorScheme(ColorScheme::Light),
+                "dark" => Feature::ColorScheme(ColorScheme::Dark),
+                value => return Err(unexpected(location, value)),
+            },
+            "prefers-reduced-motion" => match &*input.expect_ident_cloned()?.to_ascii_lowercase() {
+                "reduce" => Feature::ReducedMotion(true),
+                "no-preference" => Feature::ReducedMotion(false),
+                value => return Err(unexpected(location, value)),
+            },
+            name => return Err(unexpected(location, name)),
+        };
+        Ok(feature)
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Feature::MinWidth(width) => write!(f, "(min-width: {}px)", width),
+            Feature::MaxWidth(width) => write!(f, "(max-width: {}px)", width),
+            Feature::MinHeight(height) => write!(f, "(min-height: {}px)", height),
+            Feature::MaxHeight(height) => write!(f, "(max-height: {}px)", height),
+            Feature::Orientation(Orientation::Portrait) => write!(f, "(orientation: portrait)"),
+            Feature::Orientation(Orientation::Landscape) => write!(f, "(orientation: landscape)"),
+            Feature::ColorScheme(ColorScheme::Light) => write!(f, "(prefers-color-scheme: light)"),
+            Feature::ColorScheme(ColorScheme::Dark) => write!(f, "(prefers-color-scheme: dark)"),
+            Feature::ReducedMotion(true) => write!(f, "(prefers-reduced-motion: reduce)"),
+            Feature::ReducedMotion(false) => write!(f, "(prefers-reduced-motion: no-preference)"),
+            Feature::Resolution(resolution) => write!(f, "(resolution: {}dppx)", resolution),
+            Feature::MinResolution(resolution) => write!(f, "(min-resolution: {}dppx)", resolution),
+            Feature::MaxResolution(resolution) => write!(f, "(max-resolution: {}dppx)", resolution),
+        }
+    }
+}
+
+/// A single query of a media query list, `not screen and (max-width: 600px)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub negated: bool,
+    pub media_type: MediaType,
+    pub features: Vec<Feature>,
+}
+
+impl Query {
+    pub fn matches(&self, media: &Media) -> bool {
+        let matches = self.media_type != MediaType::Print
+            && self.features.iter().all(|feature| feature.matches(media));
+        matches != self.negated
+    }
+
+    fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Query, ParseError<'i, BasicParseError<'i>>> {
+        let mut query = Query::default();
+
+        let location = input.current_source_location();
+        if let Ok(ident) = input.try_parse(|input| input.expect_ident_cloned()) {
+            let mut media_type = ident.to_ascii_lowercase();
+            if media_type == "not" || media_type == "only" {
+                query.negated = media_type == "not";
+                media_type = input.expect_ident_cloned()?.to_ascii_lowercase();
+            }
+            query.media_type = match media_type.as_str() {
+                "all" => MediaType::All,
+                "screen" => MediaType::Screen,
+                "print" => MediaType::Print,
+                name => return Err(unexpected(location, name)),
+            };
+            if input.is_exhausted() {
+                return Ok(query);
+            }
+            input.expect_ident_matching("and")?;
+        }
+
+        loop {
+            input.expect_parenthesis_block()?;
+            query
+                .features
+                .push(input.parse_nested_block(Feature::parse)?);
+            if input.is_exhausted() {
+                return Ok(query);
+            }
+            input.expect_ident_matching("and")?;
+        }
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let media_type = match self.media_type {
+            MediaType::All if !self.negated && !self.features.is_empty() => None,
+            MediaType::All => Some("all"),
+            MediaType::Screen => Some("screen"),
+            MediaType::Print => Some("print"),
+        };
+        let mut parts: Vec<String> = Vec::new();
+        if self.negated {
+            parts.push("not".to_string());
+        }
+        parts.extend(media_type.map(String::from));
+        for feature in self.features.iter() {
+            if !parts.is_empty() {
+                parts.push("and".to_string());
+            }
+            parts.push(feature.to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// The comma separated queries of an `@media` rule, it matches if any of them does.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaQuery {
+    pub queries: Vec<Query>,
+}
+
+impl MediaQuery {
+    pub fn matches(&self, media: &Media) -> bool {
+        self.queries.iter().any(|query| query.matches(media))
+    }
+
+    /// Parse a media query list, the prelude of an `@media` rule.
+    pub fn parse<'i, 't>(
+        input: &mut Parser<'i, 't>,
+    ) -> Result<MediaQuery, ParseError<'i, BasicParseError<'i>>> {
+        let queries = input.parse_comma_separated(Query::parse)?;
+        Ok(MediaQuery { queries })
+    }
+}
+
+impl fmt::Display for MediaQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.queries.iter().join(", "))
+    }
+}
+
+fn unexpected<'i>(
+    location: cssparser::SourceLocation,
+    value: &str,
+) -> ParseError<'i, BasicParseError<'i>> {
+    location.new_custom_error(BasicParseError {
+        kind: BasicParseErrorKind::UnexpectedToken(Token::Ident(value.to_string().into())),
+        location,
+    })
+}
+
+/// A length in logical pixels. `em` and `rem` are taken as 16 pixels.
+fn parse_length<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    match token {
+        Token::Number { value, .. } if *value == 0. => Ok(0.),
+        Token::Dimension { value, unit, .. } => match &*unit.to_ascii_lowercase() {
+            "px" => Ok(*value),
+            "em" | "rem" => Ok(value * 16.),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+        },
+        _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+    }
+}
+
+/// A resolution in dots per pixel.
+fn parse_resolution<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    match token {
+        Token::Dimension { value, unit, .. } => match &*unit.to_ascii_lowercase() {
+            "dppx" | "x" => Ok(*value),
+            "dpi" => Ok(value / 96.),
+            "dpcm" => Ok(value * 2.54 / 96.),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+        },
+        _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{Stylesheet, Unit};
+
+    use super::*;
+
+    fn media(width: f32, height: f32) -> Media {
+        Media {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn queries_match_the_media() {
+        let (sheet, errors) = Stylesheet::parse(
+            ".sidebar { width: 200px; }
+            @media (max-width: 600px) { .sidebar { width: 0px; } }
+            @media screen and (orientation: portrait), (min-resolution: 2dppx) { .sidebar { height: 10px; } }
+            @media not print { @media (prefers-color-scheme: dark) { .sidebar { height: 20px; } } }
+            @media (unknown-feature: 1) { .sidebar { width: 1px; } }",
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line, 5);
+        let styles = |media: &Media| {
+            crate::cascade(
+                &[&sheet],
+                &[HashSet::from([".sidebar".to_string()])],
+                &[],
+                media,
+                None,
+            )
+            .0
+        };
+
+        assert_eq!(styles(&media(800., 400.)).width, Unit::PX(200.));
+        assert_eq!(styles(&media(600., 400.)).width, Unit::PX(0.));
+        assert_eq!(styles(&media(800., 400.)).height, Unit::Default);
+        assert_eq!(styles(&media(400., 800.)).height, Unit::PX(10.));
+        let dark = Media {
+            color_scheme: ColorScheme::Dark,
+            resolution: 2.,
+            ..media(800., 400.)
+        };
+        assert_eq!(styles(&dark).height, Unit::PX(20.));
+
+        assert!(sheet.media_changed(&media(800., 400.), &media(500., 400.)));
+        assert!(!sheet.media_changed(&media(800., 400.), &media(700., 300.)));
+    }
+
+    #[test]
+    fn media_rules_serialize() {
+        let sheet = Stylesheet::try_parse(
+            "@media not screen and (min-width: 40em), (prefers-reduced-motion) { text { width: 1px; } }",
+        ).unwrap();
+        assert_eq!(
+            sheet.to_string(),
+            "@media not screen and (min-width: 640px), (prefers-reduced-motion: reduce) {\n  text {\n    width: 1px;\n  }\n}\n"
+        );
+        assert!(
+            Stylesheet::try_parse("@media (min-width: wide) { text { width: 1px; } }").is_err()
+        );
+    }
+}
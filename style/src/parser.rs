@@ -4,14 +4,25 @@
 //! Uses [servo's cssparser](https://github.com/servo/rust-cssparser) with custom
 //! data types to parse the data.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use cssparser::{
-    AtRuleParser, BasicParseError, CowRcStr, DeclarationListParser, DeclarationParser, ParseError,
-    Parser, ParserState, QualifiedRuleParser, Token,
+    parse_important, AtRuleParser, BasicParseError, BasicParseErrorKind, CowRcStr,
+    DeclarationParser, Delimiter, ParseError, Parser, ParserInput, ParserState,
+    QualifiedRuleParser, RuleListParser, SourceLocation, Token,
 };
 
-use crate::{size::Size, Color};
+use crate::{
+    animation::{Animation, Keyframe, Keyframes, Transition},
+    media::MediaQuery,
+    size::Size,
+    Color, Selector,
+};
 
 use super::rules::*;
 
@@ -34,107 +45,638 @@ macro_rules! ident{
     };
 }
 
-#[derive(Debug)]
+/// A rule of a stylesheet. A rule without a key only declares its layer, `@layer name;`,
+/// imports a stylesheet or carries the errors of an at-rule block.
+#[derive(Debug, Default)]
 pub struct Rule {
+    /// The selector as text, `.card > .title`.
     pub key: String,
+    pub selector: Selector,
     pub styles: Vec<Style>,
+    /// Declarations marked `!important`.
+    pub important: Vec<Style>,
+    /// The cascade layer, `outer.inner` for nested layers. `None` for unlayered rules.
+    pub layer: Option<String>,
+    /// Queries of the `@media` rules the rule is nested in, all of them have to match.
+    pub media: Vec<MediaQuery>,
+    /// The stylesheet to import in place of the rule, `@import "base.css";`.
+    pub import: Option<Import>,
+    /// The animation of a `@keyframes` rule.
+    pub keyframes: Option<Keyframes>,
+    /// Declarations in the block, or rules in a layer, that couldn't be parsed.
+    pub errors: Vec<StyleError>,
+}
+
+impl Rule {
+    fn layer(name: String) -> Self {
+        Rule {
+            layer: Some(name),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the rule only declares its layer, `@layer name;`.
+    pub fn is_layer_statement(&self) -> bool {
+        self.key.is_empty()
+            && self.layer.is_some()
+            && self.styles.is_empty()
+            && self.important.is_empty()
+            && self.media.is_empty()
+            && self.import.is_none()
+            && self.keyframes.is_none()
+            && self.errors.is_empty()
+    }
 }
 
+/// An `@import` rule, `@import url("base.css") layer(base) (min-width: 600px);`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    /// Path of the stylesheet relative to the importing one.
+    pub url: String,
+    /// Layer the imported rules are put in.
+    pub layer: Option<String>,
+    /// Condition the imported rules are nested in.
+    pub media: Option<MediaQuery>,
+    pub location: SourceLocation,
+}
+
+/// The prelude of a supported at-rule.
 #[derive(Debug)]
-pub struct RuleParser;
+pub enum AtRulePrelude {
+    /// `@layer a, b`, empty for an anonymous layer block.
+    Layer(Vec<String>),
+    Media(MediaQuery),
+    Import(Import),
+    /// `@keyframes name`.
+    Keyframes(String),
+}
 
-impl<'i> AtRuleParser<'i> for RuleParser {
-    type Prelude = ();
-    type AtRule = Rule;
-    type Error = BasicParseError<'i>;
+/// A rule or declaration that couldn't be parsed. Displayed as `line:column: message`, prefixed
+/// with the file for stylesheets loaded from one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleError {
+    pub file: Option<PathBuf>,
+    /// Line of the source, counting from 1. `0` for errors about the whole file.
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
 }
 
-impl<'i> QualifiedRuleParser<'i> for RuleParser {
-    type Prelude = String;
-    type QualifiedRule = Rule;
+impl StyleError {
+    pub fn new(location: SourceLocation, message: impl Into<String>) -> Self {
+        StyleError {
+            file: None,
+            line: location.line + 1,
+            column: location.column,
+            message: message.into(),
+        }
+    }
+
+    /// A rule that couldn't be parsed, quoting the first line of its source.
+    pub fn invalid_rule(location: SourceLocation, source: &str) -> Self {
+        StyleError::new(
+            location,
+            format!(
+                "invalid rule '{}'",
+                source.lines().next().unwrap_or("").trim()
+            ),
+        )
+    }
+}
+
+impl fmt::Display for StyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+            if self.line == 0 {
+                return write!(f, " {}", self.message);
+            }
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Parses the rules of a stylesheet. Each rule parses to a list as `@layer` and `@media`
+/// blocks hold many rules.
+#[derive(Debug, Default)]
+pub struct RuleParser {
+    /// The layer the rules are nested in.
+    pub layer: Option<String>,
+    /// The queries of the `@media` blocks the rules are nested in.
+    pub media: Vec<MediaQuery>,
+}
+
+impl RuleParser {
+    /// The full name of a layer declared inside of the current one.
+    fn qualify(&self, name: &str) -> String {
+        match &self.layer {
+            Some(layer) => format!("{}.{}", layer, name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Parse the rules of a block nested in the current one.
+    fn nested<'i, 't>(self, input: &mut Parser<'i, 't>, errors: &mut Vec<StyleError>) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        for rule in RuleListParser::new_for_nested_rule(input, self) {
+            match rule {
+                Ok(nested) => rules.extend(nested),
+                Err((error, source)) => {
+                    errors.push(StyleError::invalid_rule(error.location, source))
+                }
+            }
+        }
+        rules
+    }
+}
+
+/// Supports `@layer a, b;` statements declaring the layer order, `@layer name { ... }` blocks,
+/// `@media query { ... }` blocks and `@keyframes name { ... }` rules. Layer blocks without a
+/// name are anonymous layers.
+impl<'i> AtRuleParser<'i> for RuleParser {
+    type Prelude = AtRulePrelude;
+    type AtRule = Vec<Rule>;
     type Error = BasicParseError<'i>;
 
-    /// Parses out the selector.
     fn parse_prelude<'t>(
         &mut self,
+        name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        let location = input.current_source_location();
+        if name.eq_ignore_ascii_case("media") {
+            return Ok(AtRulePrelude::Media(MediaQuery::parse(input)?));
+        }
+        if name.eq_ignore_ascii_case("import") {
+            return Ok(AtRulePrelude::Import(parse_import(input)?));
+        }
+        if name.eq_ignore_ascii_case("keyframes") {
+            return Ok(AtRulePrelude::Keyframes(
+                input.expect_ident_or_string()?.to_string(),
+            ));
+        }
+        if !name.eq_ignore_ascii_case("layer") {
+            return Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)));
+        }
 
-        let token = input.next()?;
-        // println!("{:?}", token);
-        let selector = match token {
-            // Element
-            Token::Ident(ref element_name) => element_name.to_string(),
-            // Id
-            Token::IDHash(ref name) => format!("#{}", name),
-            Token::Colon => {
-                let token = input.next()?;
-                match token {
-                    Token::Ident(ref name) => format!(":{}", name),
-                    t => return Err(location.new_unexpected_token_error(t.clone())),
-                }
+        if input.is_exhausted() {
+            return Ok(AtRulePrelude::Layer(Vec::new()));
+        }
+        let names = input.parse_comma_separated(|input| Ok(parse_layer_name(input)?))?;
+        Ok(AtRulePrelude::Layer(names))
+    }
+
+    fn rule_without_block(
+        &mut self,
+        prelude: Self::Prelude,
+        _start: &ParserState,
+    ) -> Result<Self::AtRule, ()> {
+        match prelude {
+            AtRulePrelude::Layer(names) if !names.is_empty() => Ok(names
+                .iter()
+                .map(|name| Rule::layer(self.qualify(name)))
+                .collect()),
+            AtRulePrelude::Import(import) => Ok(vec![Rule {
+                layer: self.layer.clone(),
+                media: self.media.clone(),
+                import: Some(import),
+                ..Default::default()
+            }]),
+            _ => Err(()),
+        }
+    }
+
+    fn parse_block<'t>(
+        &mut self,
+        prelude: Self::Prelude,
+        _start: &ParserState,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
+        match prelude {
+            AtRulePrelude::Import(_) => {
+                Err(input.new_error(BasicParseErrorKind::AtRuleBodyInvalid))
             }
-            Token::Delim(delim) => match delim {
-                // Class
-                '.' => {
-                    let token = input.next()?;
-                    match token {
-                        Token::Ident(ref element_name) => format!(".{}", element_name),
-                        t => return Err(location.new_unexpected_token_error(t.clone())),
-                    }
-                },
-                _ => return Err(location.new_unexpected_token_error(token.clone())),
-            },
-            t => {
-                return Err(location.new_unexpected_token_error(t.clone()));
+            AtRulePrelude::Keyframes(name) => {
+                let mut errors = Vec::new();
+                let keyframes = parse_keyframes(&name, input, &mut errors);
+                Ok(vec![Rule {
+                    keyframes: Some(keyframes),
+                    errors,
+                    ..Default::default()
+                }])
             }
-        };
+            AtRulePrelude::Media(query) => {
+                let mut media = self.media.clone();
+                media.push(query);
+                let nested = RuleParser {
+                    layer: self.layer.clone(),
+                    media: media.clone(),
+                };
+
+                let mut errors = Vec::new();
+                let mut rules = nested.nested(input, &mut errors);
+                // Carries the media so an empty block still counts as a rule before `@import`s
+                rules.insert(
+                    0,
+                    Rule {
+                        errors,
+                        media,
+                        ..Default::default()
+                    },
+                );
+                Ok(rules)
+            }
+            AtRulePrelude::Layer(names) => {
+                let name = match names.as_slice() {
+                    [] => anonymous_layer(),
+                    [name] => name.clone(),
+                    _ => return Err(input.new_error(BasicParseErrorKind::AtRuleBodyInvalid)),
+                };
+                let layer = self.qualify(&name);
+
+                let mut rules = vec![Rule::layer(layer.clone())];
+                let nested = RuleParser {
+                    layer: Some(layer),
+                    media: self.media.clone(),
+                };
+                let mut errors = Vec::new();
+                rules.extend(nested.nested(input, &mut errors));
+                rules[0].errors = errors;
+                Ok(rules)
+            }
+        }
+    }
+}
 
-        // If there's a next, someone is writing their code assuming cascading. Let's... warn them.
-        /*match input.next()? {
-            Ok(_) => {},
-            Err(e) => {}
-        };*/
+impl<'i> QualifiedRuleParser<'i> for RuleParser {
+    type Prelude = Vec<Selector>;
+    type QualifiedRule = Vec<Rule>;
+    type Error = BasicParseError<'i>;
 
-        Ok(selector)
+    /// Parses out the selectors. `&` is only allowed in nested rules.
+    fn parse_prelude<'t>(
+        &mut self,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+        let location = input.current_source_location();
+        let selectors = input.parse_comma_separated(|input| Ok(Selector::parse(input)?))?;
+        if selectors.iter().any(Selector::has_parent) {
+            return Err(location.new_unexpected_token_error(Token::Delim('&')));
+        }
+        Ok(selectors)
     }
 
-    /// Parses the block (`{...}`) into a Rule struct.
+    /// Parses the block (`{...}`) into rules, see [`RuleParser::parse_style_block`].
     fn parse_block<'t>(
         &mut self,
-        key: Self::Prelude,
+        selectors: Self::Prelude,
         _start: &ParserState,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        let styles = DeclarationListParser::new(input, StyleParser { variables: HashMap::new() }).collect::<Vec<_>>();
-
-        Ok(Rule {
-            key,
-            styles: styles
-                .into_iter()
-                .filter_map(|decl| {
-                    if !decl.is_ok() {
-                        eprintln!("{:?}", decl);
-                    }
+        Ok(self.parse_style_block(input, &selectors))
+    }
+}
 
-                    let decl = decl.ok();
-                    if let Some(decl) = decl {
-                        if decl != Style::Variable {
-                            return Some(decl);
+impl RuleParser {
+    /// Parse the contents of a style rule. Declarations apply to the selectors of the rule,
+    /// nested style rules and `@media` rules are flattened into rules with the full selector,
+    /// `.card { .title { ... } }` into `.card .title { ... }`.
+    ///
+    /// The rules are returned in source order. Declarations following a nested rule start a
+    /// new rule so they still come after it.
+    fn parse_style_block<'i, 't>(
+        &self,
+        input: &mut Parser<'i, 't>,
+        selectors: &[Selector],
+    ) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        let mut errors = Vec::new();
+        let mut styles = Vec::new();
+        let mut important = Vec::new();
+        let flush = |rules: &mut Vec<Rule>, styles: &mut Vec<Style>, important: &mut Vec<Style>| {
+            if styles.is_empty() && important.is_empty() {
+                return;
+            }
+            for selector in selectors {
+                rules.push(Rule {
+                    key: selector.to_string(),
+                    selector: selector.clone(),
+                    styles: styles.clone(),
+                    important: important.clone(),
+                    layer: self.layer.clone(),
+                    media: self.media.clone(),
+                    ..Default::default()
+                });
+            }
+            styles.clear();
+            important.clear();
+        };
+        let mut parser = ImportantParser(StyleParser {
+            variables: HashMap::new(),
+        });
+
+        loop {
+            while input.try_parse(|input| input.expect_semicolon()).is_ok() {}
+            input.skip_whitespace();
+            let start = input.state();
+            let location = input.current_source_location();
+            let token = match input.next() {
+                Ok(token) => token.clone(),
+                Err(_) => break,
+            };
+
+            match token {
+                Token::AtKeyword(name) => {
+                    let query =
+                        input.parse_until_before(
+                            Delimiter::Semicolon | Delimiter::CurlyBracketBlock,
+                            |input| match name.eq_ignore_ascii_case("media") {
+                                true => MediaQuery::parse(input),
+                                false => Err(input
+                                    .new_error(BasicParseErrorKind::AtRuleInvalid(name.clone()))),
+                            },
+                        );
+                    let block = input
+                        .try_parse(|input| input.expect_curly_bracket_block())
+                        .is_ok();
+                    match (query, block) {
+                        (Ok(query), true) => {
+                            flush(&mut rules, &mut styles, &mut important);
+                            let mut media = self.media.clone();
+                            media.push(query);
+                            let nested = RuleParser {
+                                layer: self.layer.clone(),
+                                media,
+                            };
+                            let _ = input.parse_nested_block(|input| {
+                                rules.extend(nested.parse_style_block(input, selectors));
+                                Ok::<_, ParseError<'i, ()>>(())
+                            });
+                        }
+                        _ => {
+                            let _ = input.try_parse(|input| input.expect_semicolon());
+                            errors.push(StyleError::invalid_rule(
+                                location,
+                                input.slice_from(start.position()),
+                            ));
                         }
                     }
-                    None
-                })
-                .collect(),
-        })
+                    continue;
+                }
+                Token::Ident(name) if input.try_parse(|input| input.expect_colon()).is_ok() => {
+                    let declaration = input.parse_until_before(
+                        Delimiter::Semicolon | Delimiter::CurlyBracketBlock,
+                        |input| parser.parse_value(name.clone(), input),
+                    );
+                    // `text:hover { ... }` is a nested rule rather than a declaration
+                    if !is_block(input) {
+                        match declaration {
+                            Ok((Style::Variable, _)) => (),
+                            Ok((style, false)) => styles.push(style),
+                            Ok((style, true)) => important.push(style),
+                            Err(_) => errors.push(StyleError::new(
+                                location,
+                                format!(
+                                    "invalid declaration '{}'",
+                                    input.slice_from(start.position()).trim()
+                                ),
+                            )),
+                        }
+                        continue;
+                    }
+                }
+                _ => (),
+            }
+
+            input.reset(&start);
+            let nested = input.parse_until_before(
+                Delimiter::Semicolon | Delimiter::CurlyBracketBlock,
+                |input| {
+                    input.parse_comma_separated(|input| {
+                        Ok::<_, ParseError<'i, ()>>(Selector::parse(input)?)
+                    })
+                },
+            );
+            let block = input
+                .try_parse(|input| input.expect_curly_bracket_block())
+                .is_ok();
+            match (nested, block) {
+                (Ok(nested), true) => {
+                    flush(&mut rules, &mut styles, &mut important);
+                    let nested: Vec<Selector> = selectors
+                        .iter()
+                        .flat_map(|parent| nested.iter().map(move |selector| selector.nest(parent)))
+                        .collect();
+                    let _ = input.parse_nested_block(|input| {
+                        rules.extend(self.parse_style_block(input, &nested));
+                        Ok::<_, ParseError<'i, ()>>(())
+                    });
+                }
+                _ => {
+                    let _ = input.try_parse(|input| input.expect_semicolon());
+                    errors.push(StyleError::invalid_rule(
+                        location,
+                        input.slice_from(start.position()),
+                    ));
+                }
+            }
+        }
+        flush(&mut rules, &mut styles, &mut important);
+
+        if !errors.is_empty() {
+            rules.insert(
+                0,
+                Rule {
+                    errors,
+                    ..Default::default()
+                },
+            );
+        }
+        rules
+    }
+}
+
+/// Whether the next token opens a `{}` block, without consuming it.
+fn is_block(input: &mut Parser) -> bool {
+    let state = input.state();
+    let block = matches!(input.next(), Ok(Token::CurlyBracketBlock));
+    input.reset(&state);
+    block
+}
+
+/// The frames of a `@keyframes` rule, `from { ... } 50% { ... } to { ... }`. Frames with an
+/// invalid selector and invalid declarations are skipped and reported.
+fn parse_keyframes<'i, 't>(
+    name: &str,
+    input: &mut Parser<'i, 't>,
+    errors: &mut Vec<StyleError>,
+) -> Keyframes {
+    let mut frames = Vec::new();
+    loop {
+        input.skip_whitespace();
+        let start = input.state();
+        let location = input.current_source_location();
+        if input.is_exhausted() {
+            break;
+        }
+
+        let offsets = input.parse_until_before(Delimiter::CurlyBracketBlock, |input| {
+            input.parse_comma_separated(|input| Ok::<_, ParseError<'i, ()>>(parse_offset(input)?))
+        });
+        let block = input
+            .try_parse(|input| input.expect_curly_bracket_block())
+            .is_ok();
+        match (offsets, block) {
+            (Ok(offsets), true) => {
+                let styles = input
+                    .parse_nested_block(|input| {
+                        Ok::<_, ParseError<'i, ()>>(parse_declarations(input, errors))
+                    })
+                    .unwrap_or_default();
+                frames.extend(offsets.into_iter().map(|offset| Keyframe {
+                    offset,
+                    styles: styles.clone(),
+                }));
+            }
+            (_, block) => {
+                errors.push(StyleError::invalid_rule(
+                    location,
+                    input.slice_from(start.position()),
+                ));
+                if !block {
+                    break;
+                }
+            }
+        }
+    }
+    Keyframes::new(name, frames)
+}
+
+/// The selector of a keyframe, `from`, `to` or a percentage.
+fn parse_offset<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+    match token {
+        Token::Ident(ident) if ident.eq_ignore_ascii_case("from") => Ok(0.),
+        Token::Ident(ident) if ident.eq_ignore_ascii_case("to") => Ok(1.),
+        Token::Percentage { unit_value, .. } if (0. ..=1.).contains(unit_value) => Ok(*unit_value),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+    }
+}
+
+/// A list of declarations without nested rules, as in a keyframe. `!important` is not allowed.
+fn parse_declarations<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    errors: &mut Vec<StyleError>,
+) -> Vec<Style> {
+    let mut parser = StyleParser {
+        variables: HashMap::new(),
+    };
+    let mut styles = Vec::new();
+    loop {
+        while input.try_parse(|input| input.expect_semicolon()).is_ok() {}
+        input.skip_whitespace();
+        let start = input.state();
+        let location = input.current_source_location();
+        if input.is_exhausted() {
+            break;
+        }
+
+        let declaration = input.parse_until_before(Delimiter::Semicolon, |input| {
+            let name = input.expect_ident_cloned()?;
+            input.expect_colon()?;
+            parser.parse_value(name, input)
+        });
+        match declaration {
+            Ok(Style::Variable) => (),
+            Ok(style) => styles.push(style),
+            Err(_) => errors.push(StyleError::new(
+                location,
+                format!(
+                    "invalid declaration '{}'",
+                    input.slice_from(start.position()).trim()
+                ),
+            )),
+        }
+    }
+    styles
+}
+
+/// A layer name, `base` or `components.buttons` for a nested layer.
+fn parse_layer_name<'i, 't>(input: &mut Parser<'i, 't>) -> Result<String, BasicParseError<'i>> {
+    let mut name = input.expect_ident()?.to_string();
+    while input.try_parse(|input| input.expect_delim('.')).is_ok() {
+        name.push('.');
+        name.push_str(input.expect_ident()?);
+    }
+    Ok(name)
+}
+
+/// A name for an anonymous layer, `@layer { ... }` or `@import "a.css" layer;`. Every
+/// anonymous layer is distinct, so the names are unique across stylesheets, and they contain a
+/// `<` to never collide with a named layer.
+fn anonymous_layer() -> String {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    format!("<anonymous {}>", NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+/// The prelude of an `@import`: the url, then an optional `layer` or `layer(name)` and media
+/// query.
+fn parse_import<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<Import, ParseError<'i, BasicParseError<'i>>> {
+    let location = input.current_source_location();
+    let url = input.expect_url_or_string()?.to_string();
+
+    let layer = if input
+        .try_parse(|input| input.expect_ident_matching("layer"))
+        .is_ok()
+    {
+        Some(anonymous_layer())
+    } else if input
+        .try_parse(|input| input.expect_function_matching("layer"))
+        .is_ok()
+    {
+        Some(input.parse_nested_block(|input| Ok(parse_layer_name(input)?))?)
+    } else {
+        None
+    };
+
+    let media = match input.is_exhausted() {
+        true => None,
+        false => Some(MediaQuery::parse(input)?),
+    };
+
+    Ok(Import {
+        url,
+        layer,
+        media,
+        location,
+    })
+}
+
+/// Parses declarations with [`StyleParser`] noting whether they are marked `!important`.
+struct ImportantParser(StyleParser);
+
+impl<'i> DeclarationParser<'i> for ImportantParser {
+    type Declaration = (Style, bool);
+    type Error = BasicParseError<'i>;
+
+    fn parse_value<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        let style =
+            input.parse_until_before(Delimiter::Bang, |input| self.0.parse_value(name, input))?;
+        let important = input.try_parse(parse_important).is_ok();
+        Ok((style, important))
     }
 }
 
 /// Contains logic for matching CSS attributes to their `Styles` counterpart.
 #[derive(Debug)]
 pub struct StyleParser {
-    variables: HashMap<String, String> 
+    variables: HashMap<String, String>,
 }
 
 /// Types, etc.
@@ -167,12 +709,26 @@ impl<'i> DeclarationParser<'i> for StyleParser {
             let value = match token {
                 Token::IDHash(ref name) => format!("#{}", name),
                 Token::Ident(ref ident) => ident.to_string(),
-                _ => String::new()
+                _ => String::new(),
             };
             self.variables.insert(name.to_string(), value.to_string());
             return Ok(Style::Variable);
         }
 
+        if let Some(name) = PROPERTIES.iter().find(|property| **property == name) {
+            let keyword = input.try_parse(|input| {
+                Ok::<_, ParseError<Self::Error>>(ident!(
+                    input,
+                    "inherit" => Keyword::Inherit,
+                    "initial" => Keyword::Initial,
+                    "unset" => Keyword::Unset,
+                ))
+            });
+            if let Ok(keyword) = keyword {
+                return Ok(Style::Keyword(name, keyword));
+            }
+        }
+
         let style = match name {
             "font-style" => ident!(
                 input,
@@ -180,6 +736,16 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 "italic" => Style::FontStyle(FontStyle::Italic),
                 "oblique" => Style::FontStyle(FontStyle::Oblique),
             ),
+            "direction" => ident!(
+                input,
+                "ltr" => Style::Direction(Direction::Ltr),
+                "rtl" => Style::Direction(Direction::Rtl),
+            ),
+            "visibility" => ident!(
+                input,
+                "visible" => Style::Visibility(Visibility::Visible),
+                "hidden" => Style::Visibility(Visibility::Hidden),
+            ),
 
             "position" => ident!(
                 input,
@@ -187,7 +753,14 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 "absolute" => Style::Position(Position::Absolute),
             ),
 
+            "z-index" => Style::ZIndex(parse_z_index(input)?),
+
             "background-color" => Style::BackgroundColor(Color::parse(input)?),
+            "color" => Style::Color(Color::parse(input)?),
+
+            "border" => parse_border(input)?,
+            "border-width" => Style::BorderWidth(Size::parse(input)?),
+            "border-color" => Style::BorderColor(Color::parse(input)?),
 
             "min-height" => Style::MinHeight(parse_value(input)?),
             "height" => Style::Height(parse_value(input)?),
@@ -239,6 +812,9 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 "hidden" => Overflow::Hidden,
             )),
 
+            "transition" => Style::Transition(Transition::parse_list(input)?),
+            "animation" => Style::Animation(Animation::parse_list(input)?),
+
             t => {
                 let location = input.current_source_location();
                 return Err(location.new_unexpected_token_error(Token::Ident(t.to_string().into())));
@@ -249,6 +825,21 @@ impl<'i> DeclarationParser<'i> for StyleParser {
     }
 }
 
+impl Style {
+    /// Parse a single declaration, `Style::parse("width", "120px")`.
+    pub fn parse(name: &str, value: &str) -> Result<Style, String> {
+        let mut input = ParserInput::new(value);
+        let mut input = Parser::new(&mut input);
+        let mut parser = StyleParser {
+            variables: HashMap::new(),
+        };
+
+        input
+            .parse_entirely(|input| parser.parse_value(name.to_string().into(), input))
+            .map_err(|error| format!("Invalid style '{}: {}': {:?}", name, value, error.kind))
+    }
+}
+
 /// A utility method for handling some values.
 /// Mostly used to reduce code verbosity in the massive switch table for `Styles` parsing.
 fn parse_value<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Unit, BasicParseError<'i>> {
@@ -256,7 +847,7 @@ fn parse_value<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Unit, BasicParseErr
     let token = input.next()?;
 
     match token {
-        Token::Ident(ident)  => Ok(match ident.to_string().as_str() {
+        Token::Ident(ident) => Ok(match ident.to_string().as_str() {
             "fit-content" => Unit::FitConent,
             _ => return Err(location.new_basic_unexpected_token_error(token.clone())),
         }),
@@ -266,3 +857,55 @@ fn parse_value<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Unit, BasicParseErr
         _ => Err(location.new_basic_unexpected_token_error(token.clone())),
     }
 }
+
+fn parse_z_index<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Option<i32>, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match token {
+        Token::Ident(ident) if ident.eq_ignore_ascii_case("auto") => Ok(None),
+        Token::Number {
+            int_value: Some(value),
+            ..
+        } => Ok(Some(*value)),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone())),
+    }
+}
+
+/// Parse the `border` shorthand, `1px solid red`. The parts can be in any order and the line
+/// style is ignored apart from `none` and `hidden` which remove the border.
+fn parse_border<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Style, BasicParseError<'i>> {
+    let mut width = None;
+    let mut color = None;
+    let mut visible = true;
+
+    while !input.is_exhausted() {
+        if width.is_none() {
+            if let Ok(value) = input.try_parse(parse_value) {
+                width = Some(value);
+                continue;
+            }
+        }
+
+        let state = input.state();
+        if let Ok(ident) = input.expect_ident_cloned() {
+            match &*ident {
+                "none" | "hidden" => {
+                    visible = false;
+                    continue;
+                }
+                "solid" | "dashed" | "dotted" | "double" | "groove" | "ridge" | "inset"
+                | "outset" => continue,
+                _ => (),
+            }
+        }
+        input.reset(&state);
+
+        color = Some(Color::parse(input)?);
+    }
+
+    match visible {
+        true => Ok(Style::Border(width.unwrap_or(Unit::PX(3.)), color)),
+        false => Ok(Style::Border(Unit::PX(0.), color)),
+    }
+}
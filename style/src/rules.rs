@@ -1,10 +1,14 @@
-use std::{borrow::Borrow, default};
+use std::{borrow::Borrow, default, fmt};
 
-use crate::size::Size;
+use crate::{
+    animation::{Animation, Transition},
+    size::Size,
+};
 
 use super::Color;
 
 use cssparser::CowRcStr;
+use itertools::Itertools;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Unit {
@@ -47,6 +51,17 @@ impl Unit {
     }
 }
 
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PX(px) => write!(f, "{}px", px),
+            Self::Percent(percent) => write!(f, "{}%", percent * 100.),
+            Self::FitConent => write!(f, "fit-content"),
+            Self::Default => write!(f, "auto"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Default)]
 pub enum FontStyle {
     #[default]
@@ -55,6 +70,83 @@ pub enum FontStyle {
     Oblique,
 }
 
+/// Direction text and inline content flow in.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Direction {
+    #[default]
+    Ltr,
+    Rtl,
+}
+
+/// Whether an element is painted. Hidden elements still take up space in the layout.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Visibility {
+    #[default]
+    Visible,
+    Hidden,
+}
+
+/// Values every property accepts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Keyword {
+    /// The computed value of the parent.
+    Inherit,
+    /// The value the property has when nothing sets it.
+    Initial,
+    /// `inherit` for inherited properties and `initial` for the others.
+    Unset,
+}
+
+/// Properties whose computed value is passed from an element to its children when none of
+/// its rules set them.
+pub const INHERITED: [&str; 4] = ["color", "font-style", "direction", "visibility"];
+
+/// The names of every property a stylesheet can set.
+pub const PROPERTIES: [&str; 42] = [
+    "background-color",
+    "color",
+    "font-style",
+    "direction",
+    "visibility",
+    "border",
+    "border-width",
+    "border-color",
+    "min-height",
+    "height",
+    "max-height",
+    "min-width",
+    "width",
+    "max-width",
+    "position",
+    "z-index",
+    "inset",
+    "inset-block",
+    "inset-inline",
+    "left",
+    "top",
+    "right",
+    "bottom",
+    "padding",
+    "padding-block",
+    "padding-inline",
+    "padding-left",
+    "padding-top",
+    "padding-right",
+    "padding-bottom",
+    "margin",
+    "margin-block",
+    "margin-inline",
+    "margin-left",
+    "margin-top",
+    "margin-right",
+    "margin-bottom",
+    "overflow",
+    "overflow-x",
+    "overflow-y",
+    "transition",
+    "animation",
+];
+
 #[derive(Debug, PartialEq, Clone, Copy, Default)]
 pub enum Overflow {
     #[default]
@@ -74,7 +166,17 @@ pub enum Position {
 pub enum Style {
     Variable,
     BackgroundColor(Color),
+    Color(Color),
     FontStyle(FontStyle),
+    Direction(Direction),
+    Visibility(Visibility),
+    /// `inherit`, `initial` or `unset` for the named property.
+    Keyword(&'static str, Keyword),
+
+    /// `border` shorthand, the width and optionally the color.
+    Border(Unit, Option<Color>),
+    BorderWidth(Size),
+    BorderColor(Color),
 
     MinHeight(Unit),
     Height(Unit),
@@ -84,6 +186,7 @@ pub enum Style {
     MaxWidth(Unit),
 
     Position(Position),
+    ZIndex(Option<i32>),
 
     Inset(Size),
     InsetBlock(Unit),
@@ -112,4 +215,183 @@ pub enum Style {
     Overflow(Overflow),
     OverflowX(Overflow),
     OverflowY(Overflow),
+
+    /// `transition` shorthand, empty for `none`.
+    Transition(Vec<Transition>),
+    /// `animation` shorthand, empty for `none`.
+    Animation(Vec<Animation>),
+}
+
+impl fmt::Display for FontStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Normal => write!(f, "normal"),
+            Self::Italic => write!(f, "italic"),
+            Self::Oblique => write!(f, "oblique"),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ltr => write!(f, "ltr"),
+            Self::Rtl => write!(f, "rtl"),
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Visible => write!(f, "visible"),
+            Self::Hidden => write!(f, "hidden"),
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Inherit => write!(f, "inherit"),
+            Self::Initial => write!(f, "initial"),
+            Self::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Scroll => write!(f, "scroll"),
+            Self::Hidden => write!(f, "hidden"),
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absolute => write!(f, "absolute"),
+            Self::Relative => write!(f, "relative"),
+        }
+    }
+}
+
+impl Style {
+    /// The css property the style is parsed from, empty for variables.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Variable => "",
+            Self::BackgroundColor(_) => "background-color",
+            Self::Color(_) => "color",
+            Self::FontStyle(_) => "font-style",
+            Self::Direction(_) => "direction",
+            Self::Visibility(_) => "visibility",
+            Self::Keyword(name, _) => name,
+            Self::Border(..) => "border",
+            Self::BorderWidth(_) => "border-width",
+            Self::BorderColor(_) => "border-color",
+            Self::MinHeight(_) => "min-height",
+            Self::Height(_) => "height",
+            Self::MaxHeight(_) => "max-height",
+            Self::MinWidth(_) => "min-width",
+            Self::Width(_) => "width",
+            Self::MaxWidth(_) => "max-width",
+            Self::Position(_) => "position",
+            Self::ZIndex(_) => "z-index",
+            Self::Inset(_) => "inset",
+            Self::InsetBlock(_) => "inset-block",
+            Self::InsetInline(_) => "inset-inline",
+            Self::Left(_) => "left",
+            Self::Top(_) => "top",
+            Self::Right(_) => "right",
+            Self::Bottom(_) => "bottom",
+            Self::Padding(_) => "padding",
+            Self::PaddingBlock(_) => "padding-block",
+            Self::PaddingInline(_) => "padding-inline",
+            Self::PaddingLeft(_) => "padding-left",
+            Self::PaddingTop(_) => "padding-top",
+            Self::PaddingRight(_) => "padding-right",
+            Self::PaddingBottom(_) => "padding-bottom",
+            Self::Margin(_) => "margin",
+            Self::MarginBlock(_) => "margin-block",
+            Self::MarginInline(_) => "margin-inline",
+            Self::MarginLeft(_) => "margin-left",
+            Self::MarginTop(_) => "margin-top",
+            Self::MarginRight(_) => "margin-right",
+            Self::MarginBottom(_) => "margin-bottom",
+            Self::Overflow(_) => "overflow",
+            Self::OverflowX(_) => "overflow-x",
+            Self::OverflowY(_) => "overflow-y",
+            Self::Transition(_) => "transition",
+            Self::Animation(_) => "animation",
+        }
+    }
+}
+
+/// Serialize as a css declaration, `width: 120px`, without the trailing `;`.
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Self::Variable = self {
+            return Ok(());
+        }
+
+        write!(f, "{}: ", self.name())?;
+        match self {
+            Self::Variable => Ok(()),
+            Self::BackgroundColor(color) | Self::Color(color) | Self::BorderColor(color) => {
+                write!(f, "{}", color)
+            }
+            Self::FontStyle(font_style) => write!(f, "{}", font_style),
+            Self::Direction(direction) => write!(f, "{}", direction),
+            Self::Visibility(visibility) => write!(f, "{}", visibility),
+            Self::Keyword(_, keyword) => write!(f, "{}", keyword),
+            Self::Border(width, color) => match color {
+                Some(color) => write!(f, "{} solid {}", width, color),
+                None => write!(f, "{} solid", width),
+            },
+            Self::BorderWidth(size)
+            | Self::Inset(size)
+            | Self::Padding(size)
+            | Self::Margin(size) => write!(f, "{}", size),
+            Self::MinHeight(unit)
+            | Self::Height(unit)
+            | Self::MaxHeight(unit)
+            | Self::MinWidth(unit)
+            | Self::Width(unit)
+            | Self::MaxWidth(unit)
+            | Self::InsetBlock(unit)
+            | Self::InsetInline(unit)
+            | Self::Left(unit)
+            | Self::Top(unit)
+            | Self::Right(unit)
+            | Self::Bottom(unit)
+            | Self::PaddingBlock(unit)
+            | Self::PaddingInline(unit)
+            | Self::PaddingLeft(unit)
+            | Self::PaddingTop(unit)
+            | Self::PaddingRight(unit)
+            | Self::PaddingBottom(unit)
+            | Self::MarginBlock(unit)
+            | Self::MarginInline(unit)
+            | Self::MarginLeft(unit)
+            | Self::MarginTop(unit)
+            | Self::MarginRight(unit)
+            | Self::MarginBottom(unit) => write!(f, "{}", unit),
+            Self::Position(position) => write!(f, "{}", position),
+            Self::ZIndex(z_index) => match z_index {
+                Some(z_index) => write!(f, "{}", z_index),
+                None => write!(f, "auto"),
+            },
+            Self::Overflow(overflow) | Self::OverflowX(overflow) | Self::OverflowY(overflow) => {
+                write!(f, "{}", overflow)
+            }
+            Self::Transition(transitions) if transitions.is_empty() => write!(f, "none"),
+            Self::Transition(transitions) => write!(f, "{}", transitions.iter().join(", ")),
+            Self::Animation(animations) if animations.is_empty() => write!(f, "none"),
+            Self::Animation(animations) => write!(f, "{}", animations.iter().join(", ")),
+        }
+    }
 }
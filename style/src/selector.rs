@@ -0,0 +1,537 @@
+//! Selector parsing and matching
+//!
+//! Selectors are matched against the same keys elements are styled with: the
+//! element name (`text`), classes (`.h1`), the id (`#title`), the pseudo-classes of
+//! the element's state and position (`:hover`, `:first-child`) and its attributes
+//! (`[role=tab]`). Compound selectors can be joined with the descendant (` `) and
+//! child (`>`) combinators.
+//!
+//! In nested style rules `&` refers to the selector of the parent rule, see [`Selector::nest`].
+
+use std::{collections::HashSet, fmt};
+
+use cssparser::{
+    parse_nth, BasicParseError, BasicParseErrorKind, ParseError, ParseErrorKind, Parser,
+    ParserInput, Token,
+};
+
+/// How a compound selector relates to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combinator {
+    Descendant,
+    Child,
+}
+
+/// How an attribute selector compares the value of the attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AttributeCompare {
+    /// `[name]`
+    Exists,
+    /// `[name=value]`
+    Equals,
+    /// `[name~=value]`, one of the whitespace separated words is the value.
+    Includes,
+    /// `[name|=value]`, the value or the value followed by `-`.
+    DashMatch,
+    /// `[name^=value]`
+    StartsWith,
+    /// `[name$=value]`
+    EndsWith,
+    /// `[name*=value]`
+    Contains,
+}
+
+/// An attribute selector, `[data-state^="err" i]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub compare: AttributeCompare,
+    pub value: String,
+    pub case_sensitive: bool,
+}
+
+impl Attribute {
+    /// The key an element with the attribute is matched with, `[role=tab]`.
+    pub fn key(name: &str, value: &str) -> String {
+        format!("[{}={}]", name, value)
+    }
+
+    fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Attribute, BasicParseError<'i>> {
+        let name = input.expect_ident()?.to_string();
+        if input.is_exhausted() {
+            return Ok(Attribute {
+                name,
+                compare: AttributeCompare::Exists,
+                value: String::new(),
+                case_sensitive: true,
+            });
+        }
+
+        let location = input.current_source_location();
+        let compare = match input.next()? {
+            Token::Delim('=') => AttributeCompare::Equals,
+            Token::IncludeMatch => AttributeCompare::Includes,
+            Token::DashMatch => AttributeCompare::DashMatch,
+            Token::PrefixMatch => AttributeCompare::StartsWith,
+            Token::SuffixMatch => AttributeCompare::EndsWith,
+            Token::SubstringMatch => AttributeCompare::Contains,
+            t => return Err(location.new_basic_unexpected_token_error(t.clone())),
+        };
+        let value = input.expect_ident_or_string()?.to_string();
+        let case_sensitive = input
+            .try_parse(|input| input.expect_ident_matching("i"))
+            .is_err();
+        Ok(Attribute {
+            name,
+            compare,
+            value,
+            case_sensitive,
+        })
+    }
+
+    pub fn matches(&self, keys: &HashSet<String>) -> bool {
+        let prefix = format!("[{}=", self.name);
+        keys.iter()
+            .filter_map(|key| key.strip_prefix(&prefix)?.strip_suffix(']'))
+            .any(|value| {
+                let (value, expected) = match self.case_sensitive {
+                    true => (value.to_string(), self.value.clone()),
+                    false => (value.to_lowercase(), self.value.to_lowercase()),
+                };
+                match self.compare {
+                    AttributeCompare::Exists => true,
+                    AttributeCompare::Equals => value == expected,
+                    AttributeCompare::Includes => {
+                        value.split_whitespace().any(|word| word == expected)
+                    }
+                    AttributeCompare::DashMatch => {
+                        value == expected || value.starts_with(&format!("{}-", expected))
+                    }
+                    AttributeCompare::StartsWith => {
+                        !expected.is_empty() && value.starts_with(&expected)
+                    }
+                    AttributeCompare::EndsWith => {
+                        !expected.is_empty() && value.ends_with(&expected)
+                    }
+                    AttributeCompare::Contains => !expected.is_empty() && value.contains(&expected),
+                }
+            })
+    }
+}
+
+/// Parts of a compound selector that can't be matched by looking up a single key.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `:nth-child(an+b)`, matched against the `:nth-child(index)` key of the element.
+    NthChild(i32, i32),
+    /// `:not(...)`, matches if none of the compounds do.
+    Not(Vec<Compound>),
+    Attribute(Attribute),
+}
+
+impl Condition {
+    pub fn matches(&self, keys: &HashSet<String>) -> bool {
+        match self {
+            Condition::NthChild(a, b) => keys
+                .iter()
+                .filter_map(|key| {
+                    key.strip_prefix(":nth-child(")?
+                        .strip_suffix(')')?
+                        .parse::<i32>()
+                        .ok()
+                })
+                .any(|index| match a {
+                    0 => index == *b,
+                    a => (index - b) % a == 0 && (index - b) / a >= 0,
+                }),
+            Condition::Not(compounds) => !compounds.iter().any(|compound| compound.matches(keys)),
+            Condition::Attribute(attribute) => attribute.matches(keys),
+        }
+    }
+
+    /// Specificity as `(ids, classes, types)`, `:not` counts as its most specific argument.
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        match self {
+            Condition::NthChild(..) | Condition::Attribute(_) => (0, 1, 0),
+            Condition::Not(compounds) => compounds
+                .iter()
+                .map(Compound::specificity)
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// A compound selector (`text.h1#title`). Every key must be present on an element for it to match.
+/// An empty compound is the universal selector `*`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Compound {
+    pub keys: Vec<String>,
+    pub conditions: Vec<Condition>,
+    /// Whether the compound contains the parent selector `&`.
+    pub parent: bool,
+}
+
+impl Compound {
+    pub fn matches(&self, keys: &HashSet<String>) -> bool {
+        self.keys.iter().all(|key| keys.contains(key))
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(keys))
+    }
+
+    /// Specificity as `(ids, classes, types)`.
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        self.keys
+            .iter()
+            .map(|key| crate::specificity(key))
+            .chain(self.conditions.iter().map(Condition::specificity))
+            .fold((0, 0, 0), |total, key| {
+                (total.0 + key.0, total.1 + key.1, total.2 + key.2)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selector {
+    /// Compound selectors from left to right paired with the combinator joining them to the
+    /// previous compound. The first combinator is ignored.
+    pub parts: Vec<(Combinator, Compound)>,
+}
+
+impl Selector {
+    /// Parse a single selector stopping before a `,` or the end of the input.
+    pub fn parse<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Selector, BasicParseError<'i>> {
+        let mut parts = Vec::new();
+        let mut combinator = Combinator::Descendant;
+        let mut compound = Compound::default();
+        let mut started = false;
+
+        loop {
+            let state = input.state();
+            let location = input.current_source_location();
+            let token = match input.next_including_whitespace() {
+                Ok(token) => token.clone(),
+                Err(_) => break,
+            };
+
+            match token {
+                // Element
+                Token::Ident(ref name) => compound.keys.push(name.to_string()),
+                // Id
+                Token::IDHash(ref name) | Token::Hash(ref name) => {
+                    compound.keys.push(format!("#{}", name))
+                }
+                // Class
+                Token::Delim('.') => match input.next_including_whitespace()? {
+                    Token::Ident(ref name) => compound.keys.push(format!(".{}", name)),
+                    t => return Err(location.new_basic_unexpected_token_error(t.clone())),
+                },
+                Token::Colon => match input.next_including_whitespace()? {
+                    Token::Ident(ref name) => compound.keys.push(format!(":{}", name)),
+                    Token::Function(ref name) if name.eq_ignore_ascii_case("nth-child") => {
+                        let (a, b) = input
+                            .parse_nested_block(|input| {
+                                parse_nth(input).map_err(ParseError::<()>::from)
+                            })
+                            .map_err(ParseError::basic)?;
+                        compound.conditions.push(Condition::NthChild(a, b));
+                    }
+                    Token::Function(ref name) if name.eq_ignore_ascii_case("not") => {
+                        let compounds = input
+                            .parse_nested_block(|input| {
+                                input.parse_comma_separated(|input| {
+                                    let location = input.current_source_location();
+                                    match Selector::parse(input)?.parts.as_slice() {
+                                        [(_, compound)] if !compound.parent => Ok(compound.clone()),
+                                        _ => Err(location.new_custom_error(())),
+                                    }
+                                })
+                            })
+                            .map_err(|error| BasicParseError {
+                                kind: match error.kind {
+                                    ParseErrorKind::Basic(kind) => kind,
+                                    // Only compound selectors can be negated
+                                    ParseErrorKind::Custom(()) => {
+                                        BasicParseErrorKind::QualifiedRuleInvalid
+                                    }
+                                },
+                                location: error.location,
+                            })?;
+                        compound.conditions.push(Condition::Not(compounds));
+                    }
+                    t => return Err(location.new_basic_unexpected_token_error(t.clone())),
+                },
+                // Attribute
+                Token::SquareBracketBlock => {
+                    let attribute = input
+                        .parse_nested_block(|input| {
+                            Attribute::parse(input).map_err(ParseError::<()>::from)
+                        })
+                        .map_err(ParseError::basic)?;
+                    compound.conditions.push(Condition::Attribute(attribute));
+                }
+                Token::Delim('*') => (),
+                Token::Delim('&') => compound.parent = true,
+                Token::WhiteSpace(_) => {
+                    if started {
+                        parts.push((combinator, std::mem::take(&mut compound)));
+                        combinator = Combinator::Descendant;
+                        started = false;
+                    }
+                    continue;
+                }
+                Token::Delim('>') => {
+                    if started {
+                        parts.push((combinator, std::mem::take(&mut compound)));
+                        started = false;
+                    }
+                    combinator = Combinator::Child;
+                    continue;
+                }
+                Token::Comma => {
+                    input.reset(&state);
+                    break;
+                }
+                t => return Err(location.new_basic_unexpected_token_error(t)),
+            }
+            started = true;
+        }
+
+        if started {
+            parts.push((combinator, compound));
+        } else if matches!(combinator, Combinator::Child) {
+            // A combinator has to be followed by a compound, `.row >`
+            return Err(input.new_basic_error(BasicParseErrorKind::EndOfInput));
+        }
+
+        if parts.is_empty() {
+            return Err(input.new_basic_error(BasicParseErrorKind::EndOfInput));
+        }
+
+        Ok(Selector { parts })
+    }
+
+    /// Parse a comma separated list of selectors, `.row text, #title`.
+    pub fn parse_list(src: &str) -> Result<Vec<Selector>, String> {
+        let mut input = ParserInput::new(src);
+        let mut input = Parser::new(&mut input);
+        input
+            .parse_comma_separated(|input| Selector::parse(input).map_err(ParseError::<()>::from))
+            .map_err(|error| {
+                format!(
+                    "Invalid selector '{}' at column {}: {:?}",
+                    src, error.location.column, error.kind
+                )
+            })
+    }
+
+    /// Resolve a selector of a nested rule against the selector of its parent rule. Every `&` is
+    /// replaced by the parent, a selector without one is relative to it: `.title` and
+    /// `> .title` nested in `.card` become `.card .title` and `.card > .title`, `&.active`
+    /// becomes `.card.active`.
+    pub fn nest(&self, parent: &Selector) -> Selector {
+        if !self.parts.iter().any(|(_, compound)| compound.parent) {
+            let mut parts = parent.parts.clone();
+            parts.extend(self.parts.iter().cloned());
+            return Selector { parts };
+        }
+
+        let mut parts: Vec<(Combinator, Compound)> = Vec::new();
+        for (combinator, compound) in self.parts.iter() {
+            if !compound.parent {
+                parts.push((*combinator, compound.clone()));
+                continue;
+            }
+
+            let start = parts.len();
+            parts.extend(parent.parts.iter().cloned());
+            parts[start].0 = *combinator;
+            if let Some((_, last)) = parts.last_mut() {
+                last.keys.extend(compound.keys.iter().cloned());
+                last.conditions.extend(compound.conditions.iter().cloned());
+            }
+        }
+        Selector { parts }
+    }
+
+    /// Whether the selector contains the parent selector `&`.
+    pub fn has_parent(&self) -> bool {
+        self.parts.iter().any(|(_, compound)| compound.parent)
+    }
+
+    /// The key elements matched by the selector have to have, the first key of the last
+    /// compound or `*` when it is universal.
+    pub fn subject(&self) -> &str {
+        self.parts
+            .last()
+            .and_then(|(_, compound)| compound.keys.first())
+            .map(String::as_str)
+            .unwrap_or("*")
+    }
+
+    /// Specificity of the selector as `(ids, classes, types)`.
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        self.parts
+            .iter()
+            .map(|(_, compound)| compound.specificity())
+            .fold((0, 0, 0), |total, key| {
+                (total.0 + key.0, total.1 + key.1, total.2 + key.2)
+            })
+    }
+
+    /// Check if the last element in `path` matches the selector.
+    ///
+    /// `path` holds the keys of each element starting at the root of the tree and ending with
+    /// the element being matched.
+    pub fn matches(&self, path: &[HashSet<String>]) -> bool {
+        match path.len() {
+            0 => false,
+            len => self.matches_from(self.parts.len() - 1, path, len - 1),
+        }
+    }
+
+    fn matches_from(&self, part: usize, path: &[HashSet<String>], index: usize) -> bool {
+        let (combinator, compound) = &self.parts[part];
+        if !compound.matches(&path[index]) {
+            return false;
+        }
+
+        if part == 0 {
+            return true;
+        }
+
+        match combinator {
+            Combinator::Child => index > 0 && self.matches_from(part - 1, path, index - 1),
+            Combinator::Descendant => (0..index)
+                .rev()
+                .any(|parent| self.matches_from(part - 1, path, parent)),
+        }
+    }
+}
+
+impl fmt::Display for Compound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.parent {
+            write!(f, "&")?;
+        } else if self.keys.is_empty() && self.conditions.is_empty() {
+            write!(f, "*")?;
+        }
+        write!(f, "{}", self.keys.concat())?;
+        for condition in self.conditions.iter() {
+            write!(f, "{}", condition)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::NthChild(0, b) => write!(f, ":nth-child({})", b),
+            Condition::NthChild(a, b) => write!(f, ":nth-child({}n{:+})", a, b),
+            Condition::Not(compounds) => {
+                let compounds: Vec<String> = compounds.iter().map(ToString::to_string).collect();
+                write!(f, ":not({})", compounds.join(", "))
+            }
+            Condition::Attribute(attribute) => write!(f, "{}", attribute),
+        }
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let compare = match self.compare {
+            AttributeCompare::Exists => return write!(f, "[{}]", self.name),
+            AttributeCompare::Equals => "=",
+            AttributeCompare::Includes => "~=",
+            AttributeCompare::DashMatch => "|=",
+            AttributeCompare::StartsWith => "^=",
+            AttributeCompare::EndsWith => "$=",
+            AttributeCompare::Contains => "*=",
+        };
+        write!(f, "[{}{}\"{}\"", self.name, compare, self.value)?;
+        match self.case_sensitive {
+            true => write!(f, "]"),
+            false => write!(f, " i]"),
+        }
+    }
+}
+
+/// Serialize the selector as css, `.card > text.title`.
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (combinator, compound)) in self.parts.iter().enumerate() {
+            match (index, combinator) {
+                (0, _) => (),
+                (_, Combinator::Descendant) => write!(f, " ")?,
+                (_, Combinator::Child) => write!(f, " > ")?,
+            }
+            write!(f, "{}", compound)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(keys: &[&str]) -> HashSet<String> {
+        keys.iter().map(|key| key.to_string()).collect()
+    }
+
+    fn parse(src: &str) -> Selector {
+        Selector::parse_list(src).unwrap().remove(0)
+    }
+
+    #[test]
+    fn nth_child() {
+        let odd = parse("text:nth-child(2n+1)");
+        assert!(odd.matches(&[keys(&["text", ":nth-child(1)"])]));
+        assert!(odd.matches(&[keys(&["text", ":nth-child(3)"])]));
+        assert!(!odd.matches(&[keys(&["text", ":nth-child(4)"])]));
+        assert_eq!(odd.to_string(), "text:nth-child(2n+1)");
+
+        let keyword = parse(":nth-child(odd)");
+        assert!(keyword.matches(&[keys(&[":nth-child(1)"])]));
+        assert!(!keyword.matches(&[keys(&[":nth-child(2)"])]));
+        assert_eq!(keyword.to_string(), ":nth-child(2n+1)");
+
+        let first_three = parse(":nth-child(-n+3)");
+        assert!(first_three.matches(&[keys(&[":nth-child(3)"])]));
+        assert!(!first_three.matches(&[keys(&[":nth-child(4)"])]));
+    }
+
+    #[test]
+    fn not() {
+        let tab = parse(r#"[role="tab"]:not(.active, [disabled])"#);
+        assert!(tab.matches(&[keys(&["[role=tab]"])]));
+        assert!(!tab.matches(&[keys(&["[role=tab]", "[disabled=]"])]));
+        assert!(!tab.matches(&[keys(&["[role=tab]", ".active"])]));
+        assert_eq!(tab.specificity(), (0, 2, 0));
+        assert_eq!(tab.to_string(), r#"[role="tab"]:not(.active, [disabled])"#);
+
+        // A compound argument only excludes elements matching all of it
+        let compound = parse("text:not(.a.b)");
+        assert!(compound.matches(&[keys(&["text", ".a"])]));
+        assert!(!compound.matches(&[keys(&["text", ".a", ".b"])]));
+        assert_eq!(compound.specificity(), (0, 2, 1));
+
+        assert!(Selector::parse_list(":not(.row text)").is_err());
+    }
+
+    #[test]
+    fn attributes() {
+        let error = parse(r#"[data-state^="ERR" i]"#);
+        assert!(error.matches(&[keys(&["[data-state=error-network]"])]));
+        assert!(!error.matches(&[keys(&["[data-state=ok]"])]));
+        assert!(parse("[lang|=en]").matches(&[keys(&["[lang=en-GB]"])]));
+        assert!(parse("[class~=big]").matches(&[keys(&["[class=big red]"])]));
+
+        let bracket = parse(r#"[title="a]b"]"#);
+        assert!(bracket.matches(&[keys(&["[title=a]b]"])]));
+        assert!(!bracket.matches(&[keys(&["[title=a]"])]));
+    }
+}
@@ -5,9 +5,14 @@
 //! also helpers to convert to a Size object from a list of values, max of 4, and
 //! from single unit values.
 
+use std::fmt;
+
 use cssparser::{BasicParseError, ParseError, Parser, Token};
 
-use crate::Unit;
+use crate::{
+    geometry::{Insets, InsetsF},
+    Unit,
+};
 
 /// A sizing shorthand for `inset`, `padding`, and `margin`
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -61,6 +66,24 @@ impl From<Unit> for Size {
     }
 }
 
+/// Serialize as the four value shorthand, `top right bottom left`. Unset edges are `0`.
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edge = |unit: &Unit| match unit {
+            Unit::Default => "0".to_string(),
+            unit => unit.to_string(),
+        };
+        write!(
+            f,
+            "{} {} {} {}",
+            edge(&self.top),
+            edge(&self.right),
+            edge(&self.bottom),
+            edge(&self.left)
+        )
+    }
+}
+
 impl Default for Size {
     fn default() -> Self {
         Size {
@@ -82,11 +105,11 @@ impl Size {
         }
     }
 
-    /// Calculate the i32 values for top, right, bottom, left in that order.
+    /// Calculate the i32 insets for each edge.
     ///
     /// Provided a total with and total height the final values are calculated
-    pub fn calc(&self, width: i32, height: i32) -> (i32, i32, i32, i32) {
-        (
+    pub fn calc(&self, width: i32, height: i32) -> Insets {
+        Insets::new(
             self.top.as_i32(height, 0),
             self.right.as_i32(width, 0),
             self.bottom.as_i32(height, 0),
@@ -94,6 +117,16 @@ impl Size {
         )
     }
 
+    /// Calculate the sub-pixel insets for each edge.
+    pub fn calc_f32(&self, width: f32, height: f32) -> InsetsF {
+        Insets::new(
+            self.top.as_f32(height, 0.),
+            self.right.as_f32(width, 0.),
+            self.bottom.as_f32(height, 0.),
+            self.left.as_f32(width, 0.),
+        )
+    }
+
     /// Create a block shorthand size
     ///
     /// This refers to styles like `padding-block`. Left and right values